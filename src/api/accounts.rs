@@ -566,4 +566,43 @@ mod tests {
 
         assert_eq!(signed, expected);
     }
+
+    #[test]
+    fn should_round_trip_raw_transaction_with_rlp() {
+        use crate::rlp::{Rlp, RlpStream};
+
+        let raw = hex!("f869808504e3b29200831e848094f0109fc8df283027b6285cc889f5aa624eac1f55843b9aca008025a0c9cf86333bcb065d140032ecaab5d9281bde80f21b9687b3e94161de42d51895a0727a108a0b8d101465414033c3f705a9c7b826e596766046ee1183dbc8aeaa68");
+
+        let rlp = Rlp::new(&raw);
+        let nonce: U256 = rlp.val_at(0).unwrap();
+        let gas_price: U256 = rlp.val_at(1).unwrap();
+        let gas: U256 = rlp.val_at(2).unwrap();
+        let to: Address = rlp.val_at(3).unwrap();
+        let value: U256 = rlp.val_at(4).unwrap();
+        let data: Vec<u8> = rlp.val_at(5).unwrap();
+        let v: u64 = rlp.val_at(6).unwrap();
+        let r: U256 = rlp.val_at(7).unwrap();
+        let s: U256 = rlp.val_at(8).unwrap();
+
+        assert_eq!(rlp.item_count(), Ok(9));
+        assert_eq!(nonce, 0.into());
+        assert_eq!(gas_price, 21_000_000_000u64.into());
+        assert_eq!(gas, 2_000_000.into());
+        assert_eq!(to, hex!("F0109fC8DF283027b6285cc889F5aA624EaC1F55").into());
+        assert_eq!(value, 1_000_000_000.into());
+        assert!(data.is_empty());
+        assert_eq!(v, 0x25);
+
+        let mut stream = RlpStream::new_list(9);
+        stream.append(&nonce);
+        stream.append(&gas_price);
+        stream.append(&gas);
+        stream.append(&to);
+        stream.append(&value);
+        stream.append(&data);
+        stream.append(&v);
+        stream.append(&r);
+        stream.append(&s);
+        assert_eq!(stream.out().to_vec(), raw.to_vec());
+    }
 }
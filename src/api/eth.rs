@@ -2,14 +2,16 @@
 
 use crate::{
     api::Namespace,
+    error,
     helpers::{self, CallFuture},
     types::{
-        Address, Block, BlockHeader, BlockId, BlockNumber, Bytes, CallRequest, FeeHistory, Filter, Index, Log, Proof,
-        SyncState, Transaction, TransactionId, TransactionReceipt, TransactionRequest, Work, H256, H520, H64, U256,
-        U64,
+        AccessListResult, Address, Block, BlockHeader, BlockId, BlockNumber, Bytes, CallRequest, FeeHistory, Filter,
+        Index, Log, Proof, StateOverride, SyncState, Transaction, TransactionId, TransactionReceipt,
+        TransactionRequest, TypedData, Work, H256, H520, H64, U256, U64,
     },
     Transport,
 };
+use futures::{Future, TryFutureExt};
 
 /// `Eth` namespace
 #[derive(Debug, Clone)]
@@ -42,6 +44,9 @@ impl<T: Transport> Eth<T> {
     }
 
     /// Call a constant method of contract without changing the state of the blockchain.
+    ///
+    /// Executes against the latest block unless `block` is given; use `BlockNumber::Pending`
+    /// to simulate against the pending state.
     pub fn call(&self, req: CallRequest, block: Option<BlockId>) -> CallFuture<Bytes, T::Out> {
         let req = helpers::serialize(&req);
         let block = helpers::serialize(&block.unwrap_or_else(|| BlockNumber::Latest.into()));
@@ -49,6 +54,22 @@ impl<T: Transport> Eth<T> {
         CallFuture::new(self.transport.execute("eth_call", vec![req, block]))
     }
 
+    /// Call a constant method of contract as if the state was modified by given overrides.
+    ///
+    /// Overrides only apply to this call and are not supported by every node.
+    pub fn call_with_overrides(
+        &self,
+        req: CallRequest,
+        block: Option<BlockId>,
+        overrides: StateOverride,
+    ) -> CallFuture<Bytes, T::Out> {
+        let req = helpers::serialize(&req);
+        let block = helpers::serialize(&block.unwrap_or_else(|| BlockNumber::Latest.into()));
+        let overrides = helpers::serialize(&overrides);
+
+        CallFuture::new(self.transport.execute("eth_call", vec![req, block, overrides]))
+    }
+
     /// Get coinbase address
     pub fn coinbase(&self) -> CallFuture<Address, T::Out> {
         CallFuture::new(self.transport.execute("eth_coinbase", vec![]))
@@ -84,11 +105,31 @@ impl<T: Transport> Eth<T> {
         CallFuture::new(self.transport.execute("eth_estimateGas", args))
     }
 
+    /// Generate an access list for given transaction, along with the gas it uses with the access list attached.
+    pub fn create_access_list(
+        &self,
+        req: CallRequest,
+        block: Option<BlockNumber>,
+    ) -> CallFuture<AccessListResult, T::Out> {
+        let req = helpers::serialize(&req);
+        let block = helpers::serialize(&block.unwrap_or(BlockNumber::Latest));
+
+        CallFuture::new(self.transport.execute("eth_createAccessList", vec![req, block]))
+    }
+
     /// Get current recommended gas price
     pub fn gas_price(&self) -> CallFuture<U256, T::Out> {
         CallFuture::new(self.transport.execute("eth_gasPrice", vec![]))
     }
 
+    /// Get the node's suggested priority fee (tip) per gas for EIP-1559 transactions.
+    ///
+    /// Not every node implements this method; those that don't fail with `Error::Rpc`
+    /// (usually "method not found"), in which case the tip can be estimated from `fee_history` instead.
+    pub fn max_priority_fee_per_gas(&self) -> CallFuture<U256, T::Out> {
+        CallFuture::new(self.transport.execute("eth_maxPriorityFeePerGas", vec![]))
+    }
+
     /// Returns a collection of historical gas information. This can be used for evaluating the max_fee_per_gas
     /// and max_priority_fee_per_gas to send the future transactions.
     pub fn fee_history(
@@ -182,6 +223,15 @@ impl<T: Transport> Eth<T> {
         CallFuture::new(self.transport.execute("eth_getCode", vec![address, block]))
     }
 
+    /// Check whether there is contract code under given address, i.e. it's not an externally owned account
+    pub fn is_contract(
+        &self,
+        address: Address,
+        block: Option<BlockNumber>,
+    ) -> impl Future<Output = error::Result<bool>> {
+        self.code(address, block).map_ok(|code| !code.0.is_empty())
+    }
+
     /// Get supported compilers
     pub fn compilers(&self) -> CallFuture<Vec<String>, T::Out> {
         CallFuture::new(self.transport.execute("eth_getCompilers", vec![]))
@@ -320,7 +370,7 @@ impl<T: Transport> Eth<T> {
         CallFuture::new(self.transport.execute("eth_newPendingTransactionFilter", vec![]))
     }
 
-    /// Start new pending transaction filter
+    /// Get the Ethereum protocol version supported by the node
     pub fn protocol_version(&self) -> CallFuture<String, T::Out> {
         CallFuture::new(self.transport.execute("eth_protocolVersion", vec![]))
     }
@@ -344,6 +394,16 @@ impl<T: Transport> Eth<T> {
         CallFuture::new(self.transport.execute("eth_sign", vec![address, data]))
     }
 
+    /// Signs EIP-712 typed data
+    pub fn sign_typed_data(&self, address: Address, typed_data: TypedData) -> CallFuture<H520, T::Out> {
+        let address = helpers::serialize(&address);
+        let typed_data = helpers::serialize(&typed_data);
+        CallFuture::new(
+            self.transport
+                .execute("eth_signTypedData_v4", vec![address, typed_data]),
+        )
+    }
+
     /// Submit hashrate of external miner
     pub fn submit_hashrate(&self, rate: U256, id: H256) -> CallFuture<bool, T::Out> {
         let rate = helpers::serialize(&rate);
@@ -387,10 +447,12 @@ mod tests {
     use crate::{
         api::Namespace,
         rpc::Value,
+        transports::test::TestTransport,
         types::{
-            Address, Block, BlockHeader, BlockId, BlockNumber, CallRequest, FeeHistory, FilterBuilder, Log, Proof,
-            SyncInfo, SyncState, Transaction, TransactionId, TransactionReceipt, TransactionRequest, Work, H256, H520,
-            H64, U256,
+            AccessListItem, AccessListResult, AccountOverride, Address, Block, BlockHeader, BlockId, BlockNumber,
+            CallRequest, FeeHistory, FilterBuilder, Log, Proof, StateOverride, SyncInfo, SyncState, Transaction,
+            TransactionId, TransactionReceipt, TransactionRequest, TypedData, TypedDataDomain, TypedDataField, Work,
+            H256, H520, H64, U256,
         },
     };
     use hex_literal::hex;
@@ -557,6 +619,11 @@ mod tests {
       Value::Array(vec![Value::String("0x0000000000000000000000000000000000000123".into())]) => vec![Address::from_low_u64_be(0x123)]
     );
 
+    rpc_test! (
+      Eth:accounts:accounts_empty => "eth_accounts";
+      Value::Array(vec![]) => Vec::<Address>::new()
+    );
+
     rpc_test! (
       Eth:block_number => "eth_blockNumber";
       Value::String("0x123".into()) => 0x123
@@ -575,6 +642,39 @@ mod tests {
       Value::String("0x010203".into()) => hex!("010203")
     );
 
+    rpc_test! (
+      Eth:call:call_at_pending_block, CallRequest {
+        from: None, to: Some(Address::from_low_u64_be(0x123)),
+        gas: None, gas_price: None,
+        value: Some(0x1.into()), data: None,
+        transaction_type: None, access_list: None,
+        max_fee_per_gas: None, max_priority_fee_per_gas: None,
+      }, Some(BlockId::Number(BlockNumber::Pending))
+      =>
+      "eth_call", vec![r#"{"to":"0x0000000000000000000000000000000000000123","value":"0x1"}"#, r#""pending""#];
+      Value::String("0x010203".into()) => hex!("010203")
+    );
+
+    rpc_test! (
+      Eth:call_with_overrides, CallRequest {
+        from: None, to: Some(Address::from_low_u64_be(0x123)),
+        gas: None, gas_price: None,
+        value: Some(0x1.into()), data: None,
+        transaction_type: None, access_list: None,
+        max_fee_per_gas: None, max_priority_fee_per_gas: None,
+      }, None, vec![(
+        Address::from_low_u64_be(0x456),
+        AccountOverride { balance: Some(0x10.into()), ..Default::default() },
+      )].into_iter().collect::<StateOverride>()
+      =>
+      "eth_call", vec![
+        r#"{"to":"0x0000000000000000000000000000000000000123","value":"0x1"}"#,
+        r#""latest""#,
+        r#"{"0x0000000000000000000000000000000000000456":{"balance":"0x10"}}"#
+      ];
+      Value::String("0x010203".into()) => hex!("010203")
+    );
+
     rpc_test! (
       Eth:coinbase => "eth_coinbase";
       Value::String("0x0000000000000000000000000000000000000123".into()) => Address::from_low_u64_be(0x123)
@@ -608,6 +708,32 @@ mod tests {
       Value::String("0x123".into()) => 0x123
     );
 
+    rpc_test! (
+      Eth:create_access_list, CallRequest {
+        from: None, to: Some(Address::from_low_u64_be(0x123)),
+        gas: None, gas_price: None,
+        value: None, data: Some(hex!("06fdde03").into()),
+        transaction_type: None, access_list: None,
+        max_fee_per_gas: None, max_priority_fee_per_gas: None,
+      }, None
+      =>
+      "eth_createAccessList", vec![r#"{"data":"0x06fdde03","to":"0x0000000000000000000000000000000000000123"}"#, r#""latest""#];
+      json!({
+        "accessList": [{
+          "address": "0x0000000000000000000000000000000000000123",
+          "storageKeys": ["0x0000000000000000000000000000000000000000000000000000000000000001"]
+        }],
+        "gasUsed": "0x6f4b"
+      }) => AccessListResult {
+        access_list: vec![AccessListItem {
+          address: Address::from_low_u64_be(0x123),
+          storage_keys: vec![H256::from_low_u64_be(1)],
+        }],
+        gas_used: 0x6f4b.into(),
+        error: None,
+      }
+    );
+
     rpc_test! (
       Eth:estimate_gas:optional_to_addr, CallRequest {
         from: None, to: None,
@@ -639,6 +765,11 @@ mod tests {
       Value::String("0x123".into()) => 0x123
     );
 
+    rpc_test! (
+      Eth:max_priority_fee_per_gas => "eth_maxPriorityFeePerGas";
+      Value::String("0x3b9aca00".into()) => 0x3b9aca00
+    );
+
     rpc_test! (
       Eth:fee_history, 0x3, BlockNumber::Latest, None => "eth_feeHistory", vec![r#""0x3""#, r#""latest""#, r#"null"#];
       ::serde_json::from_str(EXAMPLE_FEE_HISTORY).unwrap()
@@ -689,6 +820,20 @@ mod tests {
       Value::String("0x123".into()) => Some(0x123.into())
     );
 
+    rpc_test! (
+      Eth:block_transaction_count:block_tx_count_by_unknown_hash, BlockId::Hash(H256::from_low_u64_be(0x123))
+      =>
+      "eth_getBlockTransactionCountByHash", vec![r#""0x0000000000000000000000000000000000000000000000000000000000000123""#];
+      Value::Null => None
+    );
+
+    rpc_test! (
+      Eth:block_transaction_count:block_tx_count_by_number, BlockNumber::Number(0x123.into())
+      =>
+      "eth_getBlockTransactionCountByNumber", vec![r#""0x123""#];
+      Value::String("0x10".into()) => Some(0x10.into())
+    );
+
     rpc_test! (
       Eth:block_transaction_count, BlockNumber::Pending
       =>
@@ -703,6 +848,38 @@ mod tests {
       Value::String("0x0123".into()) => hex!("0123")
     );
 
+    #[test]
+    fn is_contract() {
+        // given
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String("0x0123".into()));
+        transport.add_response(Value::String("0x".into()));
+        let eth = Eth::new(&transport);
+
+        // when
+        let contract = futures::executor::block_on(eth.is_contract(Address::from_low_u64_be(0x123), None));
+        let account = futures::executor::block_on(eth.is_contract(Address::from_low_u64_be(0x456), None));
+
+        // then
+        transport.assert_request(
+            "eth_getCode",
+            &[
+                r#""0x0000000000000000000000000000000000000123""#.into(),
+                r#""latest""#.into(),
+            ],
+        );
+        transport.assert_request(
+            "eth_getCode",
+            &[
+                r#""0x0000000000000000000000000000000000000456""#.into(),
+                r#""latest""#.into(),
+            ],
+        );
+        transport.assert_no_more_requests();
+        assert_eq!(contract, Ok(true));
+        assert_eq!(account, Ok(false));
+    }
+
     rpc_test! (
       Eth:compilers => "eth_getCompilers";
       Value::Array(vec![]) => vec![]
@@ -739,6 +916,13 @@ mod tests {
       => Some(::serde_json::from_str::<Transaction>(EXAMPLE_TX).unwrap())
     );
 
+    rpc_test! (
+      Eth:transaction:tx_by_hash_unknown, H256::from_low_u64_be(0x123)
+      =>
+      "eth_getTransactionByHash", vec![r#""0x0000000000000000000000000000000000000000000000000000000000000123""#];
+      Value::Null => None
+    );
+
     rpc_test! (
       Eth:transaction:tx_by_block_hash_and_index, TransactionId::Block(
         BlockId::Hash(H256::from_low_u64_be(0x123)),
@@ -784,6 +968,21 @@ mod tests {
       => Some(::serde_json::from_str::<BlockHeader>(EXAMPLE_BLOCK).unwrap())
     );
 
+    rpc_test! (
+      Eth:uncle:uncle_by_hash_out_of_range, BlockId::Hash(H256::from_low_u64_be(0x123)), 6
+      =>
+      "eth_getUncleByBlockHashAndIndex", vec![r#""0x0000000000000000000000000000000000000000000000000000000000000123""#, r#""0x6""#];
+      Value::Null => None
+    );
+
+    rpc_test! (
+      Eth:uncle:uncle_by_number, BlockNumber::Number(0x1b4.into()), 0
+      =>
+      "eth_getUncleByBlockNumberAndIndex", vec![r#""0x1b4""#, r#""0x0""#];
+      ::serde_json::from_str(EXAMPLE_BLOCK).unwrap()
+      => Some(::serde_json::from_str::<Block<H256>>(EXAMPLE_BLOCK).unwrap())
+    );
+
     rpc_test! (
       Eth:uncle:uncle_by_no, BlockNumber::Earliest, 5
       =>
@@ -879,6 +1078,33 @@ mod tests {
       Value::String("0x0000000000000000000000000000000000000000000000000000000000000123".into()) => H256::from_low_u64_be(0x123)
     );
 
+    fn typed_data() -> TypedData {
+        TypedData {
+            types: vec![(
+                "EIP712Domain".to_owned(),
+                vec![TypedDataField {
+                    name: "name".into(),
+                    r#type: "string".into(),
+                }],
+            )]
+            .into_iter()
+            .collect(),
+            primary_type: "EIP712Domain".into(),
+            domain: TypedDataDomain {
+                name: Some("Test".into()),
+                ..Default::default()
+            },
+            message: Default::default(),
+        }
+    }
+
+    rpc_test! (
+      Eth:sign_typed_data, Address::from_low_u64_be(0x123), typed_data()
+      =>
+      "eth_signTypedData_v4", vec![r#""0x0000000000000000000000000000000000000123""#, r#"{"domain":{"name":"Test"},"message":{},"primaryType":"EIP712Domain","types":{"EIP712Domain":[{"name":"name","type":"string"}]}}"#];
+      Value::String("0x0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000123".into()) => H520::from_low_u64_be(0x123)
+    );
+
     rpc_test! (
       Eth:sign, H256::from_low_u64_be(0x123), hex!("01020304")
       =>
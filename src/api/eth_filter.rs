@@ -1,33 +1,89 @@
 //! `Eth` namespace, filters.
 
 use crate::{
-    api::Namespace,
+    api::{Eth, Namespace},
     error, helpers, rpc,
-    types::{Filter, Log, H256},
+    types::{Block, BlockId, Filter, Log, H256},
     Transport,
 };
-use futures::{stream, Stream, TryStreamExt};
+use futures::{stream, Stream, TryFutureExt, TryStreamExt};
 use futures_timer::Delay;
 use serde::de::DeserializeOwned;
 use std::{fmt, marker::PhantomData, time::Duration, vec};
 
-fn filter_stream<T: Transport, I: DeserializeOwned>(
+/// Describes how polling slows down while the node keeps failing.
+///
+/// After a failed poll the delay before the next one is doubled with every further failure, up to `max_delay`. Once
+/// `unhealthy_after` polls failed in a row `Error::NodeUnhealthy` is reported instead of the individual errors. The
+/// first successful poll restores the poll interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PollBackoff {
+    /// Upper bound of the delay between polls.
+    pub max_delay: Duration,
+    /// Number of consecutive failed polls after which the node is considered unhealthy.
+    pub unhealthy_after: u32,
+}
+
+impl Default for PollBackoff {
+    fn default() -> Self {
+        PollBackoff {
+            max_delay: Duration::from_secs(60),
+            unhealthy_after: 5,
+        }
+    }
+}
+
+impl PollBackoff {
+    /// Returns the delay before the next poll given the number of consecutive failures so far.
+    fn delay(&self, poll_interval: Duration, failures: u32) -> Duration {
+        if failures == 0 {
+            return poll_interval;
+        }
+        let backoff = poll_interval
+            .checked_mul(1 << failures.min(16))
+            .unwrap_or(self.max_delay);
+        backoff.min(self.max_delay).max(poll_interval)
+    }
+}
+
+/// Returns the stream of the items reported by every poll of the filter.
+fn filter_polls<T: Transport, I: DeserializeOwned>(
     base: BaseFilter<T, I>,
     poll_interval: Duration,
-) -> impl Stream<Item = error::Result<I>> {
+    backoff: PollBackoff,
+) -> impl Stream<Item = error::Result<Vec<I>>> {
     let id = helpers::serialize(&base.id);
-    stream::unfold((base, id), move |state| async move {
-        let (base, id) = state;
-        Delay::new(poll_interval).await;
+    stream::unfold((base, id, 0), move |state| async move {
+        let (base, id, failures) = state;
+        Delay::new(backoff.delay(poll_interval, failures)).await;
         let response = base.transport.execute("eth_getFilterChanges", vec![id.clone()]).await;
         let items: error::Result<Option<Vec<I>>> = response.and_then(helpers::decode);
-        let items = items.map(Option::unwrap_or_default);
-        Some((items, (base, id)))
+        let (items, failures) = match items {
+            Ok(items) => (Ok(items.unwrap_or_default()), 0),
+            // Once the breaker trips, report the node as unhealthy instead of the individual errors.
+            Err(error) if failures + 1 >= backoff.unhealthy_after => (
+                Err(error::Error::NodeUnhealthy {
+                    failures: failures + 1,
+                    error: Box::new(error),
+                }),
+                failures + 1,
+            ),
+            Err(error) => (Err(error), failures + 1),
+        };
+        Some((items, (base, id, failures)))
     })
-    // map I to Result<I> even though it is always Ok so that try_flatten works
-    .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
-    .try_flatten()
-    .into_stream()
+}
+
+fn filter_stream<T: Transport, I: DeserializeOwned>(
+    base: BaseFilter<T, I>,
+    poll_interval: Duration,
+    backoff: PollBackoff,
+) -> impl Stream<Item = error::Result<I>> {
+    filter_polls(base, poll_interval, backoff)
+        // map I to Result<I> even though it is always Ok so that try_flatten works
+        .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+        .try_flatten()
+        .into_stream()
 }
 
 /// Specifies filter items and constructor method.
@@ -134,13 +190,32 @@ impl<T: Transport, I: DeserializeOwned> BaseFilter<T, I> {
     }
 
     /// Returns the stream of items which automatically polls the server
+    ///
+    /// Polling backs off while the node keeps failing, see `PollBackoff`.
     pub fn stream(self, poll_interval: Duration) -> impl Stream<Item = error::Result<I>> {
-        filter_stream(self, poll_interval)
+        self.stream_with_backoff(poll_interval, PollBackoff::default())
+    }
+
+    /// Like `stream` but backing off from a failing node according to given `backoff`.
+    pub fn stream_with_backoff(
+        self,
+        poll_interval: Duration,
+        backoff: PollBackoff,
+    ) -> impl Stream<Item = error::Result<I>> {
+        filter_stream(self, poll_interval, backoff)
+    }
+
+    /// Like `stream` but yielding all items reported by a single poll at once.
+    pub(crate) fn poll_stream(self, poll_interval: Duration) -> impl Stream<Item = error::Result<Vec<I>>> {
+        filter_polls(self, poll_interval, PollBackoff::default())
     }
 }
 
 impl<T: Transport> BaseFilter<T, Log> {
     /// Returns future with all logs matching given filter
+    ///
+    /// Unlike `poll` this returns every matching log, not only the ones since the previous poll,
+    /// so it can be used to backfill logs right after creating the filter.
     pub async fn logs(&self) -> error::Result<Vec<Log>> {
         let id = helpers::serialize(&self.id);
         let response = self.transport.execute("eth_getFilterLogs", vec![id]).await?;
@@ -197,19 +272,42 @@ impl<T: Transport> EthFilter<T> {
     pub async fn create_pending_transactions_filter(self) -> error::Result<BaseFilter<T, H256>> {
         create_filter::<_, PendingTransactionsFilter>(self.transport, vec![]).await
     }
+
+    /// Returns a stream of new blocks.
+    ///
+    /// A blocks filter is installed and every reported block is fetched in full and yielded in the order reported by
+    /// the node. Failures to fetch a block (including a block that can no longer be found, e.g. after a reorg) are
+    /// yielded as errors and the stream continues with the next block.
+    pub fn blocks_stream(self, poll_interval: Duration) -> impl Stream<Item = error::Result<Block<H256>>> {
+        let eth = Eth::new(self.transport.clone());
+        self.create_blocks_filter()
+            .map_ok(move |filter| {
+                filter.stream(poll_interval).and_then(move |hash| {
+                    let eth = eth.clone();
+                    async move {
+                        eth.block(BlockId::Hash(hash))
+                            .await?
+                            .ok_or_else(|| error::Error::InvalidResponse(format!("block {:?} not found", hash)))
+                    }
+                })
+            })
+            .try_flatten_stream()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::EthFilter;
+    use super::{EthFilter, PollBackoff};
     use crate::{
         api::Namespace,
+        error::Error,
         rpc::Value,
         transports::test::TestTransport,
-        types::{Address, FilterBuilder, Log, H256},
+        types::{Address, Block, FilterBuilder, Log, H256},
     };
     use futures::stream::StreamExt;
     use hex_literal::hex;
+    use serde_json::json;
     use std::time::Duration;
 
     #[test]
@@ -315,6 +413,53 @@ mod tests {
         transport.assert_no_more_requests();
     }
 
+    #[test]
+    fn logs_filter_stream() {
+        // given
+        let log = |index: u64| Log {
+            address: Address::from_low_u64_be(2),
+            topics: vec![H256::from_low_u64_be(index)],
+            data: hex!("").into(),
+            block_hash: Some(H256::from_low_u64_be(2)),
+            block_number: Some(1.into()),
+            transaction_hash: Some(H256::from_low_u64_be(3)),
+            transaction_index: Some(0.into()),
+            log_index: Some(index.into()),
+            transaction_log_index: Some(index.into()),
+            log_type: None,
+            removed: None,
+        };
+
+        let mut transport = TestTransport::default();
+        transport.set_response(Value::String("0x123".into()));
+        transport.add_response(Value::Array(vec![serde_json::to_value(log(0)).unwrap()]));
+        transport.add_response(Value::Array(vec![]));
+        transport.add_response(Value::Array(vec![serde_json::to_value(log(1)).unwrap()]));
+        let result: Vec<_> = {
+            let eth = EthFilter::new(&transport);
+
+            // when
+            let filter = FilterBuilder::default()
+                .address(vec![Address::from_low_u64_be(2)])
+                .build();
+            let filter = futures::executor::block_on(eth.create_logs_filter(filter)).unwrap();
+            futures::executor::block_on_stream(filter.stream(Duration::from_secs(0)).boxed_local())
+                .take(2)
+                .collect()
+        };
+
+        // then
+        assert_eq!(result, vec![Ok(log(0)), Ok(log(1))]);
+        transport.assert_request(
+            "eth_newFilter",
+            &[r#"{"address":"0x0000000000000000000000000000000000000002"}"#.into()],
+        );
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_no_more_requests();
+    }
+
     #[test]
     fn blocks_filter() {
         // given
@@ -398,6 +543,65 @@ mod tests {
         transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
     }
 
+    #[test]
+    fn blocks_filter_stream_reports_unhealthy_node() {
+        // given
+        let mut transport = TestTransport::default();
+        transport.set_response(Value::String("0x123".into()));
+        for _ in 0..6 {
+            transport.add_response(Value::Bool(true));
+        }
+        transport.add_response(Value::Array(vec![Value::String(
+            r#"0x0000000000000000000000000000000000000000000000000000000000000456"#.into(),
+        )]));
+        transport.add_response(Value::Bool(true));
+        let backoff = PollBackoff {
+            max_delay: Duration::from_secs(0),
+            unhealthy_after: 3,
+        };
+        let result: Vec<_> = {
+            let eth = EthFilter::new(&transport);
+
+            // when
+            let filter = futures::executor::block_on(eth.create_blocks_filter()).unwrap();
+            futures::executor::block_on_stream(
+                filter
+                    .stream_with_backoff(Duration::from_secs(0), backoff)
+                    .boxed_local(),
+            )
+            .take(8)
+            .collect()
+        };
+
+        // then
+        let unhealthy = result
+            .iter()
+            .map(|item| matches!(item, Err(Error::NodeUnhealthy { .. })))
+            .collect::<Vec<_>>();
+        assert_eq!(unhealthy, [false, false, true, true, true, true, false, false]);
+        assert!(matches!(result[0], Err(Error::Decoder(_))));
+        assert!(matches!(
+            result[5],
+            Err(Error::NodeUnhealthy { failures: 6, ref error }) if matches!(**error, Error::Decoder(_))
+        ));
+        assert_eq!(result[6], Ok(H256::from_low_u64_be(0x456)));
+        // the breaker is reset by a successful poll
+        assert!(matches!(result[7], Err(Error::Decoder(_))));
+    }
+
+    #[test]
+    fn poll_backoff_doubles_delay_up_to_max() {
+        let interval = Duration::from_secs(1);
+        let backoff = PollBackoff::default();
+
+        assert_eq!(backoff.delay(interval, 0), interval);
+        assert_eq!(backoff.delay(interval, 1), Duration::from_secs(2));
+        assert_eq!(backoff.delay(interval, 2), Duration::from_secs(4));
+        assert_eq!(backoff.delay(interval, 1_000), backoff.max_delay);
+        // the poll interval is never shortened
+        assert_eq!(backoff.delay(Duration::from_secs(120), 3), Duration::from_secs(120));
+    }
+
     #[test]
     fn pending_transactions_filter() {
         // given
@@ -439,4 +643,53 @@ mod tests {
         transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
         transport.assert_no_more_requests();
     }
+
+    #[test]
+    fn blocks_stream() {
+        // given
+        let mut transport = TestTransport::default();
+        let block = |number: u64| Block::<H256> {
+            hash: Some(H256::from_low_u64_be(0x456 + number)),
+            number: Some(number.into()),
+            ..Default::default()
+        };
+        transport.add_response(Value::String("0x123".into()));
+        transport.add_response(json!([H256::from_low_u64_be(0x456), H256::from_low_u64_be(0x457)]));
+        transport.add_response(json!(block(0)));
+        transport.add_response(json!(block(1)));
+        transport.add_response(json!([H256::from_low_u64_be(0x458)]));
+        transport.add_response(Value::Null);
+
+        // when
+        let blocks = {
+            let stream = EthFilter::new(&transport).blocks_stream(Duration::from_secs(0)).take(3);
+            futures::executor::block_on(stream.collect::<Vec<_>>())
+        };
+
+        // then
+        let block_params = |hash: u64| {
+            [
+                format!("{:?}", format!("{:?}", H256::from_low_u64_be(hash))),
+                "false".into(),
+            ]
+        };
+        transport.assert_request("eth_newBlockFilter", &[]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_getBlockByHash", &block_params(0x456));
+        transport.assert_request("eth_getBlockByHash", &block_params(0x457));
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_getBlockByHash", &block_params(0x458));
+        transport.assert_no_more_requests();
+        assert_eq!(
+            blocks,
+            vec![
+                Ok(block(0)),
+                Ok(block(1)),
+                Err(Error::InvalidResponse(format!(
+                    "block {:?} not found",
+                    H256::from_low_u64_be(0x458)
+                ))),
+            ]
+        );
+    }
 }
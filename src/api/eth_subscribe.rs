@@ -3,7 +3,7 @@
 use crate::{
     api::Namespace,
     error, helpers,
-    types::{BlockHeader, Filter, Log, SyncState, H256},
+    types::{BlockHeader, Filter, Log, SyncState, Transaction, H256},
     DuplexTransport,
 };
 use futures::{
@@ -45,6 +45,9 @@ impl From<String> for SubscriptionId {
 /// Stream of notifications from a subscription
 /// Given a type deserializable from rpc::Value and a subscription id, yields items of that type as
 /// notifications are delivered.
+///
+/// Dropping the stream sends `eth_unsubscribe` through `DuplexTransport::send_and_forget` without waiting for the
+/// response. Use `unsubscribe` to wait for the node to confirm.
 #[pin_project(PinnedDrop)]
 #[derive(Debug)]
 pub struct SubscriptionStream<T: DuplexTransport, I> {
@@ -52,6 +55,7 @@ pub struct SubscriptionStream<T: DuplexTransport, I> {
     id: SubscriptionId,
     #[pin]
     rx: T::NotificationStream,
+    unsubscribed: bool,
     _marker: PhantomData<I>,
 }
 
@@ -62,6 +66,7 @@ impl<T: DuplexTransport, I> SubscriptionStream<T, I> {
             transport,
             id,
             rx,
+            unsubscribed: false,
             _marker: PhantomData,
         })
     }
@@ -72,7 +77,8 @@ impl<T: DuplexTransport, I> SubscriptionStream<T, I> {
     }
 
     /// Unsubscribe from the event represented by this stream
-    pub async fn unsubscribe(self) -> error::Result<bool> {
+    pub async fn unsubscribe(mut self) -> error::Result<bool> {
+        self.unsubscribed = true;
         let &SubscriptionId(ref id) = &self.id;
         let id = helpers::serialize(&id);
         let response = self.transport.execute("eth_unsubscribe", vec![id]).await?;
@@ -100,7 +106,12 @@ where
     T: DuplexTransport,
 {
     fn drop(self: Pin<&mut Self>) {
-        let _ = self.transport.unsubscribe(self.id().clone());
+        let this = self.project();
+        if !*this.unsubscribed {
+            let id = helpers::serialize(&this.id.0);
+            this.transport.send_and_forget("eth_unsubscribe", vec![id]);
+        }
+        let _ = this.transport.unsubscribe(this.id.clone());
     }
 }
 
@@ -133,6 +144,21 @@ impl<T: DuplexTransport> EthSubscribe<T> {
         SubscriptionStream::new(self.transport.clone(), SubscriptionId(id))
     }
 
+    /// Create a pending transactions subscription yielding full transaction objects instead of hashes
+    ///
+    /// Not every node supports this variant (e.g. geth does), on others subscribing fails and
+    /// `subscribe_new_pending_transactions` should be used instead.
+    pub async fn subscribe_full_pending_transactions(&self) -> error::Result<SubscriptionStream<T, Transaction>> {
+        let subscription = helpers::serialize(&&"newPendingTransactions");
+        let full = helpers::serialize(&true);
+        let response = self
+            .transport
+            .execute("eth_subscribe", vec![subscription, full])
+            .await?;
+        let id: String = helpers::decode(response)?;
+        SubscriptionStream::new(self.transport.clone(), SubscriptionId(id))
+    }
+
     /// Create a sync status subscription
     pub async fn subscribe_syncing(&self) -> error::Result<SubscriptionStream<T, SyncState>> {
         let subscription = helpers::serialize(&&"syncing");
@@ -141,3 +167,120 @@ impl<T: DuplexTransport> EthSubscribe<T> {
         SubscriptionStream::new(self.transport.clone(), SubscriptionId(id))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{EthSubscribe, SubscriptionId};
+    use crate::{
+        api::Namespace,
+        error, rpc,
+        rpc::Value,
+        transports::test::TestTransport,
+        types::{FilterBuilder, SyncState, H256},
+        DuplexTransport, RequestId, Transport,
+    };
+    use futures::StreamExt;
+    use std::{cell::RefCell, rc::Rc};
+
+    // Records the methods of requests actually sent, rather than only prepared.
+    #[derive(Debug, Clone, Default)]
+    struct Sent {
+        inner: TestTransport,
+        methods: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl Transport for Sent {
+        type Out = <TestTransport as Transport>::Out;
+
+        fn prepare(&self, method: &str, params: Vec<Value>) -> (RequestId, rpc::Call) {
+            self.inner.prepare(method, params)
+        }
+
+        fn send(&self, id: RequestId, request: rpc::Call) -> Self::Out {
+            if let rpc::Call::MethodCall(ref call) = request {
+                self.methods.borrow_mut().push(call.method.clone());
+            }
+            self.inner.send(id, request)
+        }
+    }
+
+    impl DuplexTransport for Sent {
+        type NotificationStream = <TestTransport as DuplexTransport>::NotificationStream;
+
+        fn subscribe(&self, id: SubscriptionId) -> error::Result<Self::NotificationStream> {
+            self.inner.subscribe(id)
+        }
+
+        fn unsubscribe(&self, id: SubscriptionId) -> error::Result<()> {
+            self.inner.unsubscribe(id)
+        }
+    }
+
+    #[test]
+    fn should_subscribe_to_new_pending_transactions() {
+        // given
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String("0x1".into()));
+        transport.add_response(Value::Bool(true));
+        let eth = EthSubscribe::new(transport.clone());
+        let hash = H256::from_low_u64_be(1);
+
+        // when
+        let mut stream = futures::executor::block_on(eth.subscribe_new_pending_transactions()).unwrap();
+        let notified = transport.notify("0x1", serde_json::to_value(hash).unwrap());
+        let notification = futures::executor::block_on(stream.next());
+        let unsubscribed = futures::executor::block_on(stream.unsubscribe());
+
+        // then
+        assert!(notified);
+        assert_eq!(notification.unwrap().unwrap(), hash);
+        assert_eq!(unsubscribed, Ok(true));
+        assert!(!transport.notify("0x1", Value::Null));
+        transport.assert_request("eth_subscribe", &[r#""newPendingTransactions""#.into()]);
+        transport.assert_request("eth_unsubscribe", &[r#""0x1""#.into()]);
+        transport.assert_no_more_requests();
+    }
+
+    #[test]
+    fn should_subscribe_to_logs_and_syncing() {
+        // given
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String("0x1".into()));
+        transport.add_response(Value::String("0x2".into()));
+        let eth = EthSubscribe::new(transport.clone());
+        let filter = FilterBuilder::default().limit(10).build();
+
+        // when
+        let logs = futures::executor::block_on(eth.subscribe_logs(filter)).unwrap();
+        let mut syncing = futures::executor::block_on(eth.subscribe_syncing()).unwrap();
+        transport.notify("0x2", Value::Bool(false));
+        let sync_state = futures::executor::block_on(syncing.next());
+
+        // then
+        assert_eq!(logs.id(), &"0x1".to_owned().into());
+        assert_eq!(sync_state.unwrap().unwrap(), SyncState::NotSyncing);
+        transport.assert_request("eth_subscribe", &[r#""logs""#.into(), r#"{"limit":10}"#.into()]);
+        transport.assert_request("eth_subscribe", &[r#""syncing""#.into()]);
+    }
+
+    #[test]
+    fn should_send_unsubscribe_when_dropped() {
+        // given
+        let mut transport = Sent::default();
+        transport.inner.add_response(Value::String("0x1".into()));
+        let eth = EthSubscribe::new(transport.clone());
+        let stream = futures::executor::block_on(eth.subscribe_new_heads()).unwrap();
+
+        // when
+        drop(stream);
+
+        // then
+        assert_eq!(*transport.methods.borrow(), vec!["eth_subscribe", "eth_unsubscribe"]);
+        assert!(!transport.inner.notify("0x1", Value::Null));
+        transport
+            .inner
+            .assert_request("eth_subscribe", &[r#""newHeads""#.into()]);
+        transport.inner.assert_request("eth_unsubscribe", &[r#""0x1""#.into()]);
+        transport.inner.assert_no_more_requests();
+    }
+}
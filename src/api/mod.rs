@@ -16,7 +16,7 @@ mod web3;
 pub use self::{
     accounts::Accounts,
     eth::Eth,
-    eth_filter::{BaseFilter, EthFilter},
+    eth_filter::{BaseFilter, EthFilter, PollBackoff},
     eth_subscribe::{EthSubscribe, SubscriptionId, SubscriptionStream},
     net::Net,
     parity::Parity,
@@ -29,8 +29,9 @@ pub use self::{
 };
 
 use crate::{
-    confirm, error,
-    types::{Bytes, TransactionReceipt, TransactionRequest, U64},
+    confirm::{self, Clock, SystemClock},
+    error,
+    types::{Bytes, NodeHealth, TransactionReceipt, TransactionRequest, U64},
     DuplexTransport, Transport,
 };
 use futures::Future;
@@ -155,6 +156,28 @@ impl<T: Transport> Web3<T> {
     ) -> error::Result<TransactionReceipt> {
         confirm::send_raw_transaction_with_confirmation(self.transport.clone(), tx, poll_interval, confirmations).await
     }
+
+    /// Probes the node with `net_version`, `eth_syncing` and `eth_blockNumber`, e.g. for health checks of
+    /// failover setups or dashboards.
+    ///
+    /// Fails if any of the probes fails, which means the node is unreachable or broken.
+    pub async fn node_status(&self) -> error::Result<NodeHealth> {
+        self.node_status_with_clock(SystemClock).await
+    }
+
+    /// Same as `node_status`, but measures the latency with a custom `clock`.
+    pub async fn node_status_with_clock<C: Clock>(&self, clock: C) -> error::Result<NodeHealth> {
+        let started = clock.now();
+        let network_id = self.net().version().await?;
+        let latency = clock.now().saturating_duration_since(started);
+        let (sync_state, block_number) = futures::try_join!(self.eth().syncing(), self.eth().block_number())?;
+        Ok(NodeHealth {
+            network_id,
+            sync_state,
+            block_number,
+            latency,
+        })
+    }
 }
 
 impl<T: DuplexTransport> Web3<T> {
@@ -163,3 +186,110 @@ impl<T: DuplexTransport> Web3<T> {
         self.api()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Web3;
+    use crate::{
+        confirm::Clock,
+        rpc::Value,
+        transports::test::TestTransport,
+        types::{SyncState, U64},
+        Error,
+    };
+    use futures::future;
+    use std::{
+        cell::Cell,
+        time::{Duration, Instant},
+    };
+
+    #[test]
+    fn should_share_transport_between_namespaces() {
+        // given
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String("0x123".into()));
+        transport.add_response(Value::String("1".into()));
+        transport.add_response(Value::String("Test123".into()));
+        let web3 = Web3::new(transport.clone());
+
+        // when
+        let block_number = futures::executor::block_on(web3.eth().block_number());
+        let network_id = futures::executor::block_on(web3.net().version());
+        let client_version = futures::executor::block_on(web3.web3().client_version());
+
+        // then
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("net_version", &[]);
+        transport.assert_request("web3_clientVersion", &[]);
+        transport.assert_no_more_requests();
+        assert_eq!(block_number, Ok(U64::from(0x123)));
+        assert_eq!(network_id, Ok("1".to_owned()));
+        assert_eq!(client_version, Ok("Test123".to_owned()));
+    }
+
+    #[test]
+    fn should_report_node_status() {
+        // given
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String("1".into()));
+        transport.add_response(Value::Bool(false));
+        transport.add_response(Value::String("0x123".into()));
+        let web3 = Web3::new(transport.clone());
+
+        // when
+        let status = futures::executor::block_on(web3.node_status()).unwrap();
+        let unreachable = futures::executor::block_on(web3.node_status());
+
+        // then
+        transport.assert_request("net_version", &[]);
+        transport.assert_request("eth_syncing", &[]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("net_version", &[]);
+        transport.assert_no_more_requests();
+        assert_eq!(status.network_id, "1");
+        assert_eq!(status.sync_state, SyncState::NotSyncing);
+        assert_eq!(status.block_number, U64::from(0x123));
+        assert!(status.is_synced());
+        assert_eq!(unreachable, Err(Error::Unreachable));
+    }
+
+    #[test]
+    fn should_measure_node_latency_with_clock() {
+        // given
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String("1".into()));
+        transport.add_response(Value::Bool(false));
+        transport.add_response(Value::String("0x123".into()));
+        let web3 = Web3::new(transport.clone());
+        let clock = SteppingClock {
+            start: Instant::now(),
+            readings: Cell::new(0),
+        };
+
+        // when
+        let status = futures::executor::block_on(web3.node_status_with_clock(&clock)).unwrap();
+
+        // then
+        assert_eq!(status.latency, Duration::from_millis(10));
+    }
+
+    // Advances by 10ms on every reading.
+    struct SteppingClock {
+        start: Instant,
+        readings: Cell<u32>,
+    }
+
+    impl Clock for &SteppingClock {
+        type Delay = future::Pending<()>;
+
+        fn now(&self) -> Instant {
+            let readings = self.readings.get();
+            self.readings.set(readings + 1);
+            self.start + Duration::from_millis(10) * readings
+        }
+
+        fn delay(&self, _duration: Duration) -> Self::Delay {
+            future::pending()
+        }
+    }
+}
@@ -2,7 +2,10 @@ use crate::{
     api::Namespace,
     helpers::{self, CallFuture},
     rpc::Value,
-    types::{Bytes, CallRequest, ParityPendingTransactionFilter, Transaction},
+    types::{
+        Address, Bytes, CallRequest, ParityGasPriceHistogram, ParityPeerType, ParityPendingTransactionFilter,
+        ParityVersionInfo, Transaction, U256,
+    },
     Transport,
 };
 
@@ -50,6 +53,58 @@ impl<T: Transport> Parity<T> {
 
         CallFuture::new(self.transport.execute("parity_pendingTransactions", params))
     }
+
+    /// Get all transactions known to the node, pending and future ones
+    pub fn all_transactions(&self) -> CallFuture<Vec<Transaction>, T::Out> {
+        CallFuture::new(self.transport.execute("parity_allTransactions", vec![]))
+    }
+
+    /// Get the enode URI of the node
+    pub fn enode(&self) -> CallFuture<String, T::Out> {
+        CallFuture::new(self.transport.execute("parity_enode", vec![]))
+    }
+
+    /// Get the peers connected to the node
+    pub fn net_peers(&self) -> CallFuture<ParityPeerType, T::Out> {
+        CallFuture::new(self.transport.execute("parity_netPeers", vec![]))
+    }
+
+    /// Get the version of the node
+    pub fn version_info(&self) -> CallFuture<ParityVersionInfo, T::Out> {
+        CallFuture::new(self.transport.execute("parity_versionInfo", vec![]))
+    }
+
+    /// Get the histogram of gas prices of transactions in recent blocks
+    pub fn gas_price_histogram(&self) -> CallFuture<ParityGasPriceHistogram, T::Out> {
+        CallFuture::new(self.transport.execute("parity_gasPriceHistogram", vec![]))
+    }
+
+    /// Get the name of the node
+    pub fn node_name(&self) -> CallFuture<String, T::Out> {
+        CallFuture::new(self.transport.execute("parity_nodeName", vec![]))
+    }
+
+    /// Get the name of the chain the node is connected to
+    pub fn chain(&self) -> CallFuture<String, T::Out> {
+        CallFuture::new(self.transport.execute("parity_chain", vec![]))
+    }
+
+    /// Get the mode of the node, e.g. `active`, `passive`, `dark` or `offline`
+    pub fn mode(&self) -> CallFuture<String, T::Out> {
+        CallFuture::new(self.transport.execute("parity_mode", vec![]))
+    }
+
+    /// Get the default extra data of mined blocks
+    pub fn default_extra_data(&self) -> CallFuture<Bytes, T::Out> {
+        CallFuture::new(self.transport.execute("parity_defaultExtraData", vec![]))
+    }
+
+    /// Get the next available nonce of the account, taking pending transactions into account
+    pub fn next_nonce(&self, address: Address) -> CallFuture<U256, T::Out> {
+        let address = helpers::serialize(&address);
+
+        CallFuture::new(self.transport.execute("parity_nextNonce", vec![address]))
+    }
 }
 
 #[cfg(test)]
@@ -58,7 +113,10 @@ mod tests {
     use crate::{
         api::Namespace,
         rpc::Value,
-        types::{Address, CallRequest, FilterCondition, ParityPendingTransactionFilter, Transaction, U64},
+        types::{
+            Address, CallRequest, FilterCondition, ParityGasPriceHistogram, ParityPendingTransactionFilter,
+            ParityVersion, ParityVersionInfo, Transaction, H160, U256, U64,
+        },
     };
     use hex_literal::hex;
 
@@ -146,4 +204,48 @@ mod tests {
         Value::Array(vec![::serde_json::from_str(EXAMPLE_PENDING_TX).unwrap()])
       => vec![::serde_json::from_str::<Transaction>(EXAMPLE_PENDING_TX).unwrap()]
     );
+
+    rpc_test!(
+        Parity:all_transactions => "parity_allTransactions";
+        Value::Array(vec![::serde_json::from_str(EXAMPLE_PENDING_TX).unwrap()])
+      => vec![::serde_json::from_str::<Transaction>(EXAMPLE_PENDING_TX).unwrap()]
+    );
+
+    rpc_test!(
+        Parity:enode => "parity_enode";
+        Value::String("enode://050929adcfe47dbe0b002cb7ef2bf91ca74f77c4e0f68730e39e717f1ce38908542369ae017148bee4e0d968340885e2ad5adea4acd19c95055080a4b625df6a@172.17.0.1:30303".into())
+      => "enode://050929adcfe47dbe0b002cb7ef2bf91ca74f77c4e0f68730e39e717f1ce38908542369ae017148bee4e0d968340885e2ad5adea4acd19c95055080a4b625df6a@172.17.0.1:30303"
+    );
+
+    rpc_test!(
+        Parity:version_info => "parity_versionInfo";
+        ::serde_json::from_str::<Value>(
+            r#"{"hash":"0x2ae8b4ca278dd7b896090366615fef81cbbbc0e0","track":"null","version":{"major":1,"minor":6,"patch":0}}"#
+        ).unwrap()
+      => ParityVersionInfo {
+            hash: H160::from(hex!("2ae8b4ca278dd7b896090366615fef81cbbbc0e0")),
+            track: "null".into(),
+            version: ParityVersion { major: 1, minor: 6, patch: 0 },
+        }
+    );
+
+    rpc_test!(
+        Parity:gas_price_histogram => "parity_gasPriceHistogram";
+        ::serde_json::from_str::<Value>(r#"{"bucketBounds":["0x4a817c800","0x525433d01","0x5a26eb202"],"counts":[487,9]}"#).unwrap()
+      => ParityGasPriceHistogram {
+            bucket_bounds: vec![U256::from(0x4a817c800_u64), U256::from(0x525433d01_u64), U256::from(0x5a26eb202_u64)],
+            counts: vec![487, 9],
+        }
+    );
+
+    rpc_test!(
+        Parity:mode => "parity_mode";
+        Value::String("active".into()) => "active"
+    );
+
+    rpc_test!(
+        Parity:next_nonce, Address::from_low_u64_be(0x123)
+      => "parity_nextNonce", vec![r#""0x0000000000000000000000000000000000000123""#];
+        Value::String("0x5".into()) => U256::from(5)
+    );
 }
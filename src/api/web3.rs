@@ -56,4 +56,12 @@ mod tests {
       "web3_sha3", vec![r#""0x01020304""#];
       Value::String("0x0000000000000000000000000000000000000000000000000000000000000123".into()) => H256::from_low_u64_be(0x123)
     );
+
+    rpc_test! (
+      Web3:sha3:sha3_empty, Vec::<u8>::new()
+      =>
+      "web3_sha3", vec![r#""0x""#];
+      Value::String("0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470".into())
+      => H256::from(hex!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"))
+    );
 }
@@ -2,12 +2,25 @@
 
 use crate::{
     api::{Eth, EthFilter, Namespace},
-    error,
-    types::{Bytes, TransactionReceipt, TransactionRequest, H256, U64},
+    contract, error,
+    time::Instant,
+    types::{BlockNumber, Bytes, TransactionId, TransactionReceipt, TransactionRequest, H256, U256, U64},
     Transport,
 };
-use futures::{Future, StreamExt};
-use std::time::Duration;
+use futures::{future, stream, Future, Stream, StreamExt, TryFutureExt, TryStreamExt};
+use futures_timer::Delay;
+use parking_lot::Mutex;
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// Number of consecutive polls a transaction seen pending must be unknown to the node to be considered dropped.
+const DROPPED_AFTER_POLLS: usize = 3;
 
 /// Checks whether an event has been confirmed.
 pub trait ConfirmationCheck {
@@ -30,7 +43,66 @@ where
     }
 }
 
+/// Number of most recent blocks `ConfirmationProgress::average_block_time` is computed from.
+const BLOCK_TIME_WINDOW: usize = 10;
+
+/// Shared handle reporting the confirmations accrued while waiting, e.g. to render "2 of 6 confirmations", and the
+/// observed block time.
+#[derive(Debug, Clone, Default)]
+pub struct ConfirmationProgress {
+    state: Arc<Mutex<ProgressState>>,
+}
+
+#[derive(Debug, Default)]
+struct ProgressState {
+    confirmations: Option<u64>,
+    blocks_seen_at: VecDeque<Instant>,
+}
+
+impl ConfirmationProgress {
+    /// Creates a new handle with no confirmations observed yet.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns the number of confirmations observed on the last poll, computed as the last seen block number minus
+    /// the block number of the confirmed event, or `None` if the event hasn't been confirmed yet.
+    pub fn confirmations_so_far(&self) -> Option<u64> {
+        self.state.lock().confirmations
+    }
+
+    /// Returns the average wall-clock time between the last few blocks reported by the blocks filter, or `None` until
+    /// at least two blocks have been seen.
+    ///
+    /// Blocks are timestamped when the filter reports them, so the estimate is only as precise as the poll interval.
+    /// It can be used to tune the `poll_interval` to the block time of the chain.
+    pub fn average_block_time(&self) -> Option<Duration> {
+        let state = self.state.lock();
+        match (state.blocks_seen_at.front(), state.blocks_seen_at.back()) {
+            (Some(first), Some(last)) if state.blocks_seen_at.len() > 1 => {
+                Some((*last - *first) / (state.blocks_seen_at.len() - 1) as u32)
+            }
+            _ => None,
+        }
+    }
+
+    fn set(&self, confirmations: Option<u64>) {
+        self.state.lock().confirmations = confirmations;
+    }
+
+    fn block_seen(&self, at: Instant) {
+        let mut state = self.state.lock();
+        if state.blocks_seen_at.len() == BLOCK_TIME_WINDOW {
+            state.blocks_seen_at.pop_front();
+        }
+        state.blocks_seen_at.push_back(at);
+    }
+}
+
 /// Should be used to wait for confirmations
+///
+/// Once the event is included, the current block number is requested together with every check. Wrap the transport
+/// in `Batch::with_auto_flush` to send both in a single batch request, other transports send them one by one.
 pub async fn wait_for_confirmations<T, V, F>(
     eth: Eth<T>,
     eth_filter: EthFilter<T>,
@@ -43,29 +115,320 @@ where
     V: ConfirmationCheck<Check = F>,
     F: Future<Output = error::Result<Option<U64>>>,
 {
+    wait_for_confirmations_(
+        eth,
+        eth_filter,
+        poll_interval,
+        confirmations,
+        check,
+        None,
+        None,
+        SystemClock,
+    )
+    .await
+}
+
+/// Like `wait_for_confirmations` but stops waiting once `cancel` is set.
+///
+/// The flag is checked on every poll. When set, the blocks filter is uninstalled and
+/// `Error::Cancelled` is returned.
+pub async fn wait_for_confirmations_cancellable<T, V, F>(
+    eth: Eth<T>,
+    eth_filter: EthFilter<T>,
+    poll_interval: Duration,
+    confirmations: usize,
+    check: V,
+    cancel: Arc<AtomicBool>,
+) -> error::Result<()>
+where
+    T: Transport,
+    V: ConfirmationCheck<Check = F>,
+    F: Future<Output = error::Result<Option<U64>>>,
+{
+    wait_for_confirmations_(
+        eth,
+        eth_filter,
+        poll_interval,
+        confirmations,
+        check,
+        Some(cancel),
+        None,
+        SystemClock,
+    )
+    .await
+}
+
+/// Like `wait_for_confirmations` but records the confirmations accrued so far in `progress`.
+///
+/// Unlike `wait_for_confirmations`, the check is performed on every new block (rather than only after `confirmations`
+/// blocks passed) so that `progress` is kept up to date.
+pub async fn wait_for_confirmations_with_progress<T, V, F>(
+    eth: Eth<T>,
+    eth_filter: EthFilter<T>,
+    poll_interval: Duration,
+    confirmations: usize,
+    check: V,
+    progress: ConfirmationProgress,
+) -> error::Result<()>
+where
+    T: Transport,
+    V: ConfirmationCheck<Check = F>,
+    F: Future<Output = error::Result<Option<U64>>>,
+{
+    wait_for_confirmations_(
+        eth,
+        eth_filter,
+        poll_interval,
+        confirmations,
+        check,
+        None,
+        Some(progress),
+        SystemClock,
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn wait_for_confirmations_<T, V, F, C>(
+    eth: Eth<T>,
+    eth_filter: EthFilter<T>,
+    poll_interval: Duration,
+    confirmations: usize,
+    check: V,
+    cancel: Option<Arc<AtomicBool>>,
+    progress: Option<ConfirmationProgress>,
+    clock: C,
+) -> error::Result<()>
+where
+    T: Transport,
+    V: ConfirmationCheck<Check = F>,
+    F: Future<Output = error::Result<Option<U64>>>,
+    C: Clock,
+{
+    let is_cancelled = || cancel.as_ref().is_some_and(|cancel| cancel.load(Ordering::SeqCst));
     let filter = eth_filter.create_blocks_filter().await?;
-    // TODO #396: The stream should have additional checks.
-    // * We should not continue calling next on a stream that has completed (has returned None). We expect this to never
-    //   happen for the blocks filter but to be safe we should handle this case for example by `fuse`ing the stream or
-    //   erroring when it does complete.
-    // * We do not handle the case where the stream returns an error which means we are wrongly counting it as a
-    //   confirmation.
-    let filter_stream = filter.stream(poll_interval).skip(confirmations);
-    futures::pin_mut!(filter_stream);
+    // TODO #396: We should not continue calling next on a stream that has completed (has returned None). We expect
+    // this to never happen for the blocks filter but to be safe we should handle this case for example by `fuse`ing the
+    // stream or erroring when it does complete.
+    let mut skip = if progress.is_some() { 0 } else { confirmations };
+    let polls = filter.clone().poll_stream(poll_interval);
+    futures::pin_mut!(polls);
+    let mut included = false;
     loop {
-        let _ = filter_stream.next().await;
-        if let Some(confirmation_block_number) = check.check().await? {
-            let block_number = eth.block_number().await?;
-            if confirmation_block_number.low_u64() + confirmations as u64 <= block_number.low_u64() {
-                return Ok(());
+        if is_cancelled() {
+            filter.uninstall().await?;
+            return Err(error::Error::Cancelled);
+        }
+        // A failed poll is not a new block, the stream backs off and gives up once the node is unhealthy.
+        let new_blocks = match polls.next().await {
+            Some(Err(err @ error::Error::NodeUnhealthy { .. })) => return Err(err),
+            Some(Err(_)) => continue,
+            Some(Ok(blocks)) => blocks.len(),
+            None => 1,
+        };
+        // The blocks reported by a single poll are seen at the same time, only the poll is timestamped.
+        if let Some(progress) = progress.as_ref().filter(|_| new_blocks > 0) {
+            progress.block_seen(clock.now());
+        }
+        for _ in 0..new_blocks {
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+            // Once the event is included every poll needs the block number as well. It is requested along with the
+            // check, so that a batching transport (like `Batch::with_auto_flush`) sends both in a single round trip.
+            let block_number = if included { Some(eth.block_number()) } else { None };
+            match check.check().await? {
+                Some(confirmation_block_number) => {
+                    included = true;
+                    let block_number = match block_number {
+                        Some(block_number) => block_number.await?,
+                        None => eth.block_number().await?,
+                    };
+                    if let Some(progress) = &progress {
+                        progress.set(Some(
+                            block_number
+                                .low_u64()
+                                .saturating_sub(confirmation_block_number.low_u64()),
+                        ));
+                    }
+                    if confirmation_block_number.low_u64() + confirmations as u64 <= block_number.low_u64() {
+                        return Ok(());
+                    }
+                }
+                None => {
+                    included = false;
+                    if let Some(progress) = &progress {
+                        progress.set(None);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Tracks whether a pending transaction is still known to the node.
+#[derive(Debug, Default)]
+struct DroppedCheck {
+    seen: AtomicBool,
+    misses: AtomicUsize,
+}
+
+/// Tracks the block including a transaction, to count its confirmations anew once it moves to another block.
+#[derive(Debug, Default)]
+struct ReorgCheck {
+    block_hash: Mutex<Option<H256>>,
+    counted_from: Mutex<Option<U64>>,
+}
+
+impl ReorgCheck {
+    /// Returns the block number the confirmations of a transaction included in given block are counted from.
+    ///
+    /// That's the including block, unless the transaction moved to a different block (i.e. after a reorg), in which
+    /// case the count restarts from the latest block at the time the move was noticed.
+    async fn counted_from<T: Transport>(
+        &self,
+        eth: &Eth<T>,
+        block_hash: H256,
+        block_number: U64,
+    ) -> error::Result<U64> {
+        let moved = self
+            .block_hash
+            .lock()
+            .replace(block_hash)
+            .is_some_and(|previous| previous != block_hash);
+        if moved {
+            let latest = eth.block_number().await?;
+            *self.counted_from.lock() = Some(latest);
+        }
+        let counted_from = *self.counted_from.lock();
+        Ok(counted_from.map_or(block_number, |counted_from| counted_from.max(block_number)))
+    }
+}
+
+/// Returns the block number of the transaction receipt, failing with `Error::TransactionDropped` once a transaction
+/// seen pending is unknown to the node for `DROPPED_AFTER_POLLS` consecutive checks.
+///
+/// Once the transaction moves to a different block its confirmations are counted anew, see `ReorgCheck`.
+async fn transaction_receipt_or_dropped_check<T: Transport>(
+    eth: &Eth<T>,
+    hash: H256,
+    dropped: &DroppedCheck,
+    reorg: &ReorgCheck,
+) -> error::Result<Option<U64>> {
+    let receipt = eth.transaction_receipt(hash).await?;
+    if let Some(receipt) = receipt {
+        match (receipt.block_hash, receipt.block_number) {
+            (Some(block_hash), Some(block_number)) => {
+                return reorg.counted_from(eth, block_hash, block_number).await.map(Some)
             }
+            (None, Some(block_number)) => return Ok(Some(block_number)),
+            _ => {}
         }
     }
+    if eth.transaction(TransactionId::Hash(hash)).await?.is_some() {
+        dropped.seen.store(true, Ordering::SeqCst);
+        dropped.misses.store(0, Ordering::SeqCst);
+    } else if dropped.seen.load(Ordering::SeqCst)
+        && dropped.misses.fetch_add(1, Ordering::SeqCst) + 1 >= DROPPED_AFTER_POLLS
+    {
+        return Err(error::Error::TransactionDropped(hash));
+    }
+    Ok(None)
+}
+
+/// Polls `check` every `poll_interval` until it resolves with `true`.
+///
+/// `check` produces a fresh future on every poll from a clone of `transport`, e.g. an `eth_call` of a view function
+/// reading some contract state. Unlike the confirmation helpers, the condition is not tied to new blocks or transaction
+/// receipts.
+pub async fn wait_until<T, F, C>(transport: T, poll_interval: Duration, check: F) -> error::Result<()>
+where
+    T: Transport,
+    F: Fn(T) -> C,
+    C: Future<Output = error::Result<bool>>,
+{
+    wait_until_with_clock(transport, poll_interval, check, SystemClock).await
+}
+
+/// Like `wait_until` but waiting between the polls with given `clock`.
+pub async fn wait_until_with_clock<T, F, C, K>(
+    transport: T,
+    poll_interval: Duration,
+    check: F,
+    clock: K,
+) -> error::Result<()>
+where
+    T: Transport,
+    F: Fn(T) -> C,
+    C: Future<Output = error::Result<bool>>,
+    K: Clock,
+{
+    while !check(transport.clone()).await? {
+        clock.delay(poll_interval).await;
+    }
+    Ok(())
 }
 
-async fn transaction_receipt_block_number_check<T: Transport>(eth: &Eth<T>, hash: H256) -> error::Result<Option<U64>> {
+/// Returns the hash of the block including the transaction and its confirmation count.
+async fn transaction_confirmations<T: Transport>(eth: &Eth<T>, hash: H256) -> error::Result<Option<(H256, u64)>> {
     let receipt = eth.transaction_receipt(hash).await?;
-    Ok(receipt.and_then(|receipt| receipt.block_number))
+    match receipt.and_then(|receipt| receipt.block_hash.zip(receipt.block_number)) {
+        Some((block_hash, receipt_block_number)) => {
+            let block_number = eth.block_number().await?;
+            let confirmations = block_number.low_u64().saturating_sub(receipt_block_number.low_u64());
+            Ok(Some((block_hash, confirmations)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Returns a stream of confirmation counts of the transaction with given hash.
+///
+/// Every new block the transaction receipt is checked and each confirmation count is yielded once, in order
+/// (`1, 2, 3, ...`), as the chain advances. Confirmations are counted the same way as in `wait_for_confirmations`.
+/// If the receipt disappears or moves to a different block (i.e. after a reorg), the count starts over from `1`.
+/// The stream never ends on its own; use e.g. `take` to stop after the desired number of confirmations.
+pub fn confirmations_stream<T: Transport>(
+    transport: T,
+    poll_interval: Duration,
+    hash: H256,
+) -> impl Stream<Item = error::Result<u64>> {
+    let eth = Eth::new(transport.clone());
+    let eth_filter = EthFilter::new(transport);
+    eth_filter
+        .create_blocks_filter()
+        .map_ok(move |filter| {
+            filter
+                .stream(poll_interval)
+                .and_then(move |_| {
+                    let eth = eth.clone();
+                    async move { transaction_confirmations(&eth, hash).await }
+                })
+                .scan((None, 0), |(included_in, last), confirmations| {
+                    let items = match confirmations {
+                        // Compare the block hashes, a reorg can move the transaction to another block of the same height.
+                        Ok(Some((block_hash, confirmations))) => {
+                            if *included_in != Some(block_hash) {
+                                *included_in = Some(block_hash);
+                                *last = 0;
+                            }
+                            let items = (*last + 1..=confirmations).map(Ok).collect();
+                            *last = confirmations.max(*last);
+                            items
+                        }
+                        Ok(None) => {
+                            *included_in = None;
+                            *last = 0;
+                            vec![]
+                        }
+                        Err(err) => vec![Err(err)],
+                    };
+                    future::ready(Some(stream::iter(items)))
+                })
+                .flatten()
+        })
+        .try_flatten_stream()
 }
 
 async fn send_transaction_with_confirmation_<T: Transport>(
@@ -73,23 +436,66 @@ async fn send_transaction_with_confirmation_<T: Transport>(
     transport: T,
     poll_interval: Duration,
     confirmations: usize,
+    cancel: Option<Arc<AtomicBool>>,
+) -> error::Result<TransactionReceipt> {
+    let confirm = confirm_transaction(hash, transport, poll_interval, confirmations, cancel);
+    // With the `tracing` feature requests of `Traced` transports are grouped under a span of the whole flow.
+    #[cfg(feature = "tracing")]
+    let confirm = tracing::Instrument::instrument(
+        confirm,
+        tracing::info_span!("send_transaction_with_confirmation", hash = ?hash, confirmations),
+    );
+    confirm.await
+}
+
+async fn confirm_transaction<T: Transport>(
+    hash: H256,
+    transport: T,
+    poll_interval: Duration,
+    confirmations: usize,
+    cancel: Option<Arc<AtomicBool>>,
 ) -> error::Result<TransactionReceipt> {
     let eth = Eth::new(transport.clone());
     if confirmations > 0 {
-        let confirmation_check = || transaction_receipt_block_number_check(&eth, hash);
+        let dropped = DroppedCheck::default();
+        let reorg = ReorgCheck::default();
+        let confirmation_check = || transaction_receipt_or_dropped_check(&eth, hash, &dropped, &reorg);
         let eth_filter = EthFilter::new(transport.clone());
         let eth = eth.clone();
-        wait_for_confirmations(eth, eth_filter, poll_interval, confirmations, confirmation_check).await?;
+        wait_for_confirmations_(
+            eth,
+            eth_filter,
+            poll_interval,
+            confirmations,
+            confirmation_check,
+            cancel,
+            None,
+            SystemClock,
+        )
+        .await?;
     }
-    // TODO #397: We should remove this `expect`. No matter what happens inside the node, this shouldn't be a panic.
-    let receipt = eth
-        .transaction_receipt(hash)
+    // The receipt can still vanish after the confirmations, e.g. due to a reorg.
+    eth.transaction_receipt(hash)
         .await?
-        .expect("receipt can't be null after wait for confirmations; qed");
-    Ok(receipt)
+        .ok_or(error::Error::ReceiptUnavailable(hash))
+}
+
+/// Fails with `Error::MissingFrom` if the sender of `tx` is unset (the zero address).
+///
+/// The node fills in a default sender (usually its first account) for such transactions. The confirmation helpers
+/// sending transactions call this before sending to rule that out.
+pub fn ensure_from(tx: &TransactionRequest) -> error::Result<()> {
+    if tx.from.is_zero() {
+        return Err(error::Error::MissingFrom);
+    }
+    Ok(())
 }
 
 /// Sends transaction and returns future resolved after transaction is confirmed
+///
+/// Fails with `Error::TransactionDropped` if the transaction was pending but the node stopped knowing it (e.g. it got
+/// replaced or evicted from the mempool). Fails with `Error::MissingFrom` without sending anything if `tx.from` is not
+/// set, see `ensure_from`.
 pub async fn send_transaction_with_confirmation<T>(
     transport: T,
     tx: TransactionRequest,
@@ -99,11 +505,33 @@ pub async fn send_transaction_with_confirmation<T>(
 where
     T: Transport,
 {
+    ensure_from(&tx)?;
+    let hash = Eth::new(&transport).send_transaction(tx).await?;
+    send_transaction_with_confirmation_(hash, transport, poll_interval, confirmations, None).await
+}
+
+/// Like `send_transaction_with_confirmation` but stops waiting once `cancel` is set.
+///
+/// See `wait_for_confirmations_cancellable` for details.
+pub async fn send_transaction_with_confirmation_cancellable<T>(
+    transport: T,
+    tx: TransactionRequest,
+    poll_interval: Duration,
+    confirmations: usize,
+    cancel: Arc<AtomicBool>,
+) -> error::Result<TransactionReceipt>
+where
+    T: Transport,
+{
+    ensure_from(&tx)?;
     let hash = Eth::new(&transport).send_transaction(tx).await?;
-    send_transaction_with_confirmation_(hash, transport, poll_interval, confirmations).await
+    send_transaction_with_confirmation_(hash, transport, poll_interval, confirmations, Some(cancel)).await
 }
 
 /// Sends raw transaction and returns future resolved after transaction is confirmed
+///
+/// Useful for locally signed transactions, the confirmations are awaited the same way as in
+/// `send_transaction_with_confirmation`, using the hash returned by `eth_sendRawTransaction`.
 pub async fn send_raw_transaction_with_confirmation<T>(
     transport: T,
     tx: Bytes,
@@ -114,19 +542,299 @@ where
     T: Transport,
 {
     let hash = Eth::new(&transport).send_raw_transaction(tx).await?;
-    send_transaction_with_confirmation_(hash, transport, poll_interval, confirmations).await
+    send_transaction_with_confirmation_(hash, transport, poll_interval, confirmations, None).await
+}
+
+/// Like `send_raw_transaction_with_confirmation` but stops waiting once `cancel` is set.
+///
+/// See `wait_for_confirmations_cancellable` for details.
+pub async fn send_raw_transaction_with_confirmation_cancellable<T>(
+    transport: T,
+    tx: Bytes,
+    poll_interval: Duration,
+    confirmations: usize,
+    cancel: Arc<AtomicBool>,
+) -> error::Result<TransactionReceipt>
+where
+    T: Transport,
+{
+    let hash = Eth::new(&transport).send_raw_transaction(tx).await?;
+    send_transaction_with_confirmation_(hash, transport, poll_interval, confirmations, Some(cancel)).await
+}
+
+/// Returns `estimate` increased by `margin_percent` or `Error::GasLimitExceeded` if that's more than `max_gas`.
+fn gas_with_margin(estimate: U256, margin_percent: u64, max_gas: U256) -> error::Result<U256> {
+    let margin = estimate.saturating_mul(margin_percent.into()) / 100;
+    let gas = estimate.saturating_add(margin);
+    if gas > max_gas {
+        return Err(error::Error::GasLimitExceeded { gas, max_gas });
+    }
+    Ok(gas)
+}
+
+/// Sends transaction with its gas limit set to the estimated gas plus a safety margin and waits for confirmations.
+///
+/// The gas is estimated with `eth_estimateGas` and increased by `margin_percent` (e.g. `25` for 1.25x). If the result
+/// exceeds `max_gas`, nothing is sent and `Error::GasLimitExceeded` is returned. A gas limit already set on `tx` is
+/// replaced.
+pub async fn send_transaction_auto_gas<T: Transport>(
+    transport: T,
+    mut tx: TransactionRequest,
+    margin_percent: u64,
+    max_gas: U256,
+    poll_interval: Duration,
+    confirmations: usize,
+) -> error::Result<TransactionReceipt> {
+    let estimate = Eth::new(&transport).estimate_gas(tx.clone().into(), None).await?;
+    tx.gas = Some(gas_with_margin(estimate, margin_percent, max_gas)?);
+    send_transaction_with_confirmation(transport, tx, poll_interval, confirmations).await
+}
+
+/// Sends transaction after checking that it doesn't revert and waits for confirmations.
+///
+/// The transaction is first executed with `eth_call` against the pending block. If it would revert, nothing is
+/// sent and `Error::Revert` with the decoded reason is returned instead.
+pub async fn send_transaction_checked<T: Transport>(
+    transport: T,
+    tx: TransactionRequest,
+    poll_interval: Duration,
+    confirmations: usize,
+) -> error::Result<TransactionReceipt> {
+    let output = Eth::new(&transport)
+        .call(tx.clone().into(), Some(BlockNumber::Pending.into()))
+        .await
+        .map_err(|err| match contract::revert_reason(&err) {
+            Some(reason) => error::Error::Revert(reason),
+            None => err,
+        })?;
+    if let Some(reason) = contract::decode_revert_reason(&output.0) {
+        return Err(error::Error::Revert(reason));
+    }
+    send_transaction_with_confirmation(transport, tx, poll_interval, confirmations).await
+}
+
+/// Describes how the fees of a pending transaction are bumped by `send_transaction_with_deadline`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeeBumpPolicy {
+    /// Number of new blocks without the transaction being included after which its fees are bumped.
+    pub blocks: usize,
+    /// Percentage by which the fees are increased on every bump.
+    ///
+    /// Nodes usually require at least 10% to accept a replacement transaction.
+    pub percent: u64,
+    /// Upper bound of the bumped fees, no further replacements are sent once it is reached.
+    ///
+    /// Without it the fees are only capped by `U256::MAX`.
+    pub max_fee: Option<U256>,
+}
+
+impl Default for FeeBumpPolicy {
+    fn default() -> Self {
+        FeeBumpPolicy {
+            blocks: 3,
+            percent: 12,
+            max_fee: None,
+        }
+    }
+}
+
+/// Returns the transaction with its fees bumped according to `policy` or `None` if they can't be bumped any further.
+fn bump_fees(tx: &TransactionRequest, policy: &FeeBumpPolicy) -> Option<TransactionRequest> {
+    let bump = |fee: U256| {
+        let percent = U256::from(policy.percent);
+        // Avoid overflowing with huge fees by dividing first, the precision lost is negligible then.
+        let increase = fee
+            .checked_mul(percent)
+            .map_or_else(|| (fee / 100).saturating_mul(percent), |increase| increase / 100);
+        let bumped = fee.saturating_add(increase.max(1.into()));
+        policy.max_fee.map_or(bumped, |max_fee| bumped.min(max_fee.max(fee)))
+    };
+    let max_fee_per_gas = tx.max_fee_per_gas.map(bump);
+    // The tip can't exceed the fee cap, otherwise the node rejects the transaction.
+    let max_priority_fee_per_gas = tx
+        .max_priority_fee_per_gas
+        .map(bump)
+        .map(|tip| max_fee_per_gas.map_or(tip, |max_fee| tip.min(max_fee)));
+    let bumped = TransactionRequest {
+        gas_price: tx.gas_price.map(bump),
+        max_fee_per_gas,
+        max_priority_fee_per_gas,
+        ..tx.clone()
+    };
+    if bumped == *tx {
+        None
+    } else {
+        Some(bumped)
+    }
+}
+
+/// Source of time used by the confirmation utilities.
+///
+/// `SystemClock` is used by default, a custom implementation allows testing timeouts without waiting.
+pub trait Clock {
+    /// Future resolved once a delay elapses.
+    type Delay: Future<Output = ()> + Unpin;
+
+    /// Returns the current instant.
+    fn now(&self) -> Instant;
+
+    /// Returns a future resolved after given duration.
+    fn delay(&self, duration: Duration) -> Self::Delay;
+}
+
+/// Clock using the real time.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    type Delay = Delay;
+
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn delay(&self, duration: Duration) -> Self::Delay {
+        Delay::new(duration)
+    }
+}
+
+/// Sends transaction and keeps replacing it with higher fees until it is confirmed or the `deadline` elapses.
+///
+/// The nonce and gas price are fetched from the node if not set, so that every replacement uses the same nonce.
+/// Fails with `Error::MissingFrom` if `tx.from` is not set.
+/// Whenever `policy.blocks` new blocks pass without any of the sent transactions being included, the transaction
+/// is resent with fees bumped by `policy.percent`. Once one of the sent transactions gets `confirmations`
+/// confirmations its receipt is returned.
+///
+/// A replacement rejected by the node doesn't stop the wait for the transactions sent before.
+///
+/// If the deadline elapses first, the blocks filter is uninstalled and `Error::DeadlineExceeded` is returned with
+/// the hash of the last sent transaction, which may still get included later.
+pub async fn send_transaction_with_deadline<T: Transport>(
+    transport: T,
+    tx: TransactionRequest,
+    poll_interval: Duration,
+    confirmations: usize,
+    deadline: Instant,
+    policy: FeeBumpPolicy,
+) -> error::Result<TransactionReceipt> {
+    send_transaction_with_deadline_and_clock(
+        transport,
+        tx,
+        poll_interval,
+        confirmations,
+        deadline,
+        policy,
+        SystemClock,
+    )
+    .await
+}
+
+/// Like `send_transaction_with_deadline` but measuring the deadline with given `clock`.
+pub async fn send_transaction_with_deadline_and_clock<T: Transport, C: Clock>(
+    transport: T,
+    mut tx: TransactionRequest,
+    poll_interval: Duration,
+    confirmations: usize,
+    deadline: Instant,
+    policy: FeeBumpPolicy,
+    clock: C,
+) -> error::Result<TransactionReceipt> {
+    ensure_from(&tx)?;
+    let eth = Eth::new(transport.clone());
+    if tx.nonce.is_none() {
+        tx.nonce = Some(eth.transaction_count(tx.from, Some(BlockNumber::Pending)).await?);
+    }
+    if tx.gas_price.is_none() && tx.max_fee_per_gas.is_none() {
+        tx.gas_price = Some(eth.gas_price().await?);
+    }
+
+    let mut last_hash = eth.send_transaction(tx.clone()).await?;
+    let mut hashes = vec![last_hash];
+    let filter = EthFilter::new(transport).create_blocks_filter().await?;
+    let filter_stream = filter.clone().stream(poll_interval);
+    futures::pin_mut!(filter_stream);
+    let mut blocks_since_bump = 0;
+    loop {
+        let remaining = deadline.saturating_duration_since(clock.now());
+        if remaining == Duration::from_secs(0) {
+            // Failing to uninstall the filter shouldn't hide that the deadline passed, the node drops it eventually.
+            let _ = filter.uninstall().await;
+            return Err(error::Error::DeadlineExceeded { last_hash });
+        }
+        match future::select(filter_stream.next(), clock.delay(remaining)).await {
+            future::Either::Left((Some(Err(err @ error::Error::NodeUnhealthy { .. })), _)) => return Err(err),
+            future::Either::Left((Some(Err(_)), _)) => continue,
+            future::Either::Left(_) => {}
+            future::Either::Right(_) => continue,
+        }
+
+        let mut included = false;
+        for hash in hashes.iter().rev() {
+            if let Some(receipt) = eth.transaction_receipt(*hash).await? {
+                if let Some(receipt_block_number) = receipt.block_number {
+                    if confirmations == 0
+                        || receipt_block_number.low_u64() + confirmations as u64 <= eth.block_number().await?.low_u64()
+                    {
+                        return Ok(receipt);
+                    }
+                    included = true;
+                    break;
+                }
+            }
+        }
+
+        blocks_since_bump += 1;
+        if included || blocks_since_bump < policy.blocks {
+            continue;
+        }
+        blocks_since_bump = 0;
+        if let Some(bumped) = bump_fees(&tx, &policy) {
+            tx = bumped;
+            match eth.send_transaction(tx.clone()).await {
+                Ok(hash) => {
+                    last_hash = hash;
+                    hashes.push(hash);
+                }
+                // The node rejected the replacement (e.g. nonce too low, underpriced or already known), one of the
+                // transactions sent so far may still get included.
+                Err(err) if matches!(err.root(), error::Error::Rpc(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::send_transaction_with_confirmation;
+    use super::{
+        bump_fees, confirmations_stream, ensure_from, gas_with_margin, send_raw_transaction_with_confirmation,
+        send_raw_transaction_with_confirmation_cancellable, send_transaction_auto_gas, send_transaction_checked,
+        send_transaction_with_confirmation, send_transaction_with_deadline, send_transaction_with_deadline_and_clock,
+        wait_for_confirmations_, wait_for_confirmations_with_progress, wait_until, Clock, ConfirmationProgress,
+        FeeBumpPolicy,
+    };
     use crate::{
-        rpc::Value,
-        transports::test::TestTransport,
-        types::{Address, TransactionReceipt, TransactionRequest, H256, U64},
+        api::{Eth, EthFilter, Namespace},
+        error::Error,
+        helpers,
+        rpc::{self, Value},
+        transports::{test::TestTransport, Batch},
+        types::{Address, Bytes, Transaction, TransactionReceipt, TransactionRequest, H256, U256, U64},
+        BatchTransport, RequestId, Transport,
     };
+    use futures::{future, StreamExt, TryFutureExt};
+    use parking_lot::Mutex;
     use serde_json::json;
-    use std::time::Duration;
+    use std::{
+        cell::Cell,
+        collections::{BTreeMap, VecDeque},
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc,
+        },
+        time::{Duration, Instant},
+    };
 
     #[test]
     fn test_send_transaction_with_confirmation() {
@@ -181,11 +889,13 @@ mod tests {
             r#"0x0000000000000000000000000000000000000000000000000000000000000459"#.into(),
         )]));
         transport.add_response(Value::Null);
+        transport.add_response(Value::Null);
         transport.add_response(Value::Array(vec![
             Value::String(r#"0x0000000000000000000000000000000000000000000000000000000000000460"#.into()),
             Value::String(r#"0x0000000000000000000000000000000000000000000000000000000000000461"#.into()),
         ]));
         transport.add_response(Value::Null);
+        transport.add_response(Value::Null);
         transport.add_response(json!(transaction_receipt));
         transport.add_response(Value::String("0x6".into()));
         transport.add_response(json!(transaction_receipt));
@@ -197,7 +907,15 @@ mod tests {
             futures::executor::block_on(future)
         };
 
-        transport.assert_request("eth_sendTransaction", &[r#"{"from":"0x0000000000000000000000000000000000000123","gasPrice":"0x1","to":"0x0000000000000000000000000000000000000123","value":"0x1"}"#.into()]);
+        transport.assert_request_values(
+            "eth_sendTransaction",
+            &[json!({
+                "from": "0x0000000000000000000000000000000000000123",
+                "to": "0x0000000000000000000000000000000000000123",
+                "gasPrice": "0x1",
+                "value": "0x1",
+            })],
+        );
         transport.assert_request("eth_newBlockFilter", &[]);
         transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
         transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
@@ -206,11 +924,19 @@ mod tests {
             "eth_getTransactionReceipt",
             &[r#""0x0000000000000000000000000000000000000000000000000000000000000111""#.into()],
         );
+        transport.assert_request(
+            "eth_getTransactionByHash",
+            &[r#""0x0000000000000000000000000000000000000000000000000000000000000111""#.into()],
+        );
         transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
         transport.assert_request(
             "eth_getTransactionReceipt",
             &[r#""0x0000000000000000000000000000000000000000000000000000000000000111""#.into()],
         );
+        transport.assert_request(
+            "eth_getTransactionByHash",
+            &[r#""0x0000000000000000000000000000000000000000000000000000000000000111""#.into()],
+        );
         transport.assert_request(
             "eth_getTransactionReceipt",
             &[r#""0x0000000000000000000000000000000000000000000000000000000000000111""#.into()],
@@ -223,4 +949,1104 @@ mod tests {
         transport.assert_no_more_requests();
         assert_eq!(confirmation, Ok(transaction_receipt));
     }
+
+    #[test]
+    fn test_send_raw_transaction_with_confirmation_node_unhealthy() {
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String(
+            r#"0x0000000000000000000000000000000000000000000000000000000000000111"#.into(),
+        ));
+        transport.add_response(Value::String("0x123".into()));
+        for _ in 0..5 {
+            transport.add_response(Value::Bool(true));
+        }
+
+        let result = futures::executor::block_on(send_raw_transaction_with_confirmation(
+            &transport,
+            Bytes(vec![1, 2, 3]),
+            Duration::from_secs(0),
+            1,
+        ));
+
+        transport.assert_request("eth_sendRawTransaction", &[r#""0x010203""#.into()]);
+        transport.assert_request("eth_newBlockFilter", &[]);
+        for _ in 0..5 {
+            transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        }
+        transport.assert_no_more_requests();
+        assert!(matches!(result, Err(Error::NodeUnhealthy { failures: 5, .. })));
+    }
+
+    #[test]
+    fn test_ensure_from() {
+        let tx = TransactionRequest {
+            to: Some(Address::from_low_u64_be(0x123)),
+            ..Default::default()
+        };
+
+        assert_eq!(ensure_from(&tx), Err(Error::MissingFrom));
+        assert_eq!(
+            ensure_from(&TransactionRequest {
+                from: Address::from_low_u64_be(0x123),
+                ..tx
+            }),
+            Ok(())
+        );
+    }
+
+    /// Batching transport answering every method with the next of its scripted responses (repeating the last one)
+    /// and recording the methods of every batch sent.
+    #[derive(Debug, Clone, Default)]
+    struct ScriptedBatchTransport {
+        next_id: Arc<AtomicUsize>,
+        responses: Arc<Mutex<BTreeMap<String, VecDeque<Value>>>>,
+        batches: Arc<Mutex<Vec<Vec<String>>>>,
+    }
+
+    impl ScriptedBatchTransport {
+        fn respond(&self, method: &str, responses: Vec<Value>) {
+            self.responses.lock().insert(method.into(), responses.into());
+        }
+
+        fn response(&self, request: &rpc::Call) -> Result<Value, Error> {
+            let method = match request {
+                rpc::Call::MethodCall(call) => &call.method,
+                _ => return Err(Error::Internal),
+            };
+            let mut responses = self.responses.lock();
+            let responses = responses.get_mut(method).ok_or(Error::Unreachable)?;
+            match responses.len() {
+                0 => Err(Error::Unreachable),
+                1 => Ok(responses[0].clone()),
+                _ => Ok(responses.pop_front().unwrap()),
+            }
+        }
+    }
+
+    impl Transport for ScriptedBatchTransport {
+        type Out = future::Ready<Result<Value, Error>>;
+
+        fn prepare(&self, method: &str, params: Vec<Value>) -> (RequestId, rpc::Call) {
+            let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+            (id, helpers::build_request(id, method, params))
+        }
+
+        fn send(&self, _id: RequestId, request: rpc::Call) -> Self::Out {
+            future::ready(self.response(&request))
+        }
+    }
+
+    impl BatchTransport for ScriptedBatchTransport {
+        type Batch = future::Ready<Result<Vec<Result<Value, Error>>, Error>>;
+
+        fn send_batch<T>(&self, requests: T) -> Self::Batch
+        where
+            T: IntoIterator<Item = (RequestId, rpc::Call)>,
+        {
+            let requests = requests.into_iter().map(|(_, request)| request).collect::<Vec<_>>();
+            self.batches.lock().push(
+                requests
+                    .iter()
+                    .filter_map(|request| match request {
+                        rpc::Call::MethodCall(call) => Some(call.method.clone()),
+                        _ => None,
+                    })
+                    .collect(),
+            );
+            future::ready(Ok(requests.iter().map(|request| self.response(request)).collect()))
+        }
+    }
+
+    #[test]
+    fn test_send_transaction_with_confirmation_batches_polls() {
+        let inner = ScriptedBatchTransport::default();
+        let receipt = TransactionReceipt {
+            transaction_hash: H256::from_low_u64_be(0x111),
+            block_hash: Some(H256::from_low_u64_be(0x2)),
+            block_number: Some(2.into()),
+            ..Default::default()
+        };
+        inner.respond("eth_sendTransaction", vec![json!(H256::from_low_u64_be(0x111))]);
+        inner.respond("eth_newBlockFilter", vec![json!("0x123")]);
+        inner.respond("eth_getFilterChanges", vec![json!([H256::from_low_u64_be(0x456)])]);
+        inner.respond("eth_getTransactionReceipt", vec![json!(receipt)]);
+        inner.respond("eth_blockNumber", vec![json!("0x2"), json!("0x3")]);
+        // single requests are flushed after the delay, the block number and the receipt fill a batch
+        let transport = Batch::new(inner.clone()).with_auto_flush(2, Duration::from_millis(1));
+
+        let result = futures::executor::block_on(send_transaction_with_confirmation(
+            &transport,
+            deadline_request(),
+            Duration::from_secs(0),
+            1,
+        ));
+
+        assert_eq!(result, Ok(receipt));
+        let single = |method: &str| vec![method.to_owned()];
+        assert_eq!(
+            *inner.batches.lock(),
+            vec![
+                single("eth_sendTransaction"),
+                single("eth_newBlockFilter"),
+                single("eth_getFilterChanges"),
+                single("eth_getFilterChanges"),
+                single("eth_getTransactionReceipt"),
+                single("eth_blockNumber"),
+                single("eth_getFilterChanges"),
+                vec!["eth_blockNumber".to_owned(), "eth_getTransactionReceipt".to_owned()],
+                single("eth_getTransactionReceipt"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_send_transaction_with_confirmation_without_from() {
+        let transport = TestTransport::default();
+
+        let result = futures::executor::block_on(send_transaction_with_confirmation(
+            &transport,
+            TransactionRequest {
+                to: Some(Address::from_low_u64_be(0x123)),
+                ..Default::default()
+            },
+            Duration::from_secs(0),
+            0,
+        ));
+
+        transport.assert_no_more_requests();
+        assert_eq!(result, Err(Error::MissingFrom));
+    }
+
+    #[test]
+    fn test_send_transaction_with_confirmation_receipt_unavailable() {
+        let mut transport = TestTransport::default();
+        let hash = H256::from_low_u64_be(0x111);
+        transport.add_response(json!(hash));
+        transport.add_response(Value::Null);
+
+        let result = futures::executor::block_on(send_transaction_with_confirmation(
+            &transport,
+            TransactionRequest {
+                from: Address::from_low_u64_be(0x123),
+                ..Default::default()
+            },
+            Duration::from_secs(0),
+            0,
+        ));
+
+        transport.assert_request(
+            "eth_sendTransaction",
+            &[r#"{"from":"0x0000000000000000000000000000000000000123"}"#.into()],
+        );
+        transport.assert_request(
+            "eth_getTransactionReceipt",
+            &[r#""0x0000000000000000000000000000000000000000000000000000000000000111""#.into()],
+        );
+        transport.assert_no_more_requests();
+        assert_eq!(result, Err(Error::ReceiptUnavailable(hash)));
+    }
+
+    #[test]
+    fn test_confirmations_stream() {
+        let mut transport = TestTransport::default();
+        let hash = H256::from_low_u64_be(0x111);
+        let receipt = json!(TransactionReceipt {
+            transaction_hash: hash,
+            transaction_index: U64::zero(),
+            block_hash: Some(H256::zero()),
+            block_number: Some(2.into()),
+            from: Address::from_low_u64_be(0x123),
+            to: Some(Address::from_low_u64_be(0x123)),
+            cumulative_gas_used: 0.into(),
+            gas_used: Some(0.into()),
+            contract_address: None,
+            logs: vec![],
+            status: Some(1.into()),
+            root: Some(H256::zero()),
+            logs_bloom: Default::default(),
+            transaction_type: None,
+            effective_gas_price: Default::default(),
+        });
+
+        transport.add_response(Value::String("0x123".into()));
+        transport.add_response(json!([H256::from_low_u64_be(0x456)]));
+        transport.add_response(Value::Null);
+        transport.add_response(json!([H256::from_low_u64_be(0x457)]));
+        transport.add_response(receipt.clone());
+        transport.add_response(Value::String("0x3".into()));
+        transport.add_response(json!([H256::from_low_u64_be(0x458)]));
+        transport.add_response(receipt);
+        transport.add_response(Value::String("0x5".into()));
+
+        let confirmations = {
+            let stream = confirmations_stream(&transport, Duration::from_secs(0), hash).take(3);
+            futures::executor::block_on(stream.collect::<Vec<_>>())
+        };
+
+        let receipt_params = [r#""0x0000000000000000000000000000000000000000000000000000000000000111""#.to_string()];
+        transport.assert_request("eth_newBlockFilter", &[]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_getTransactionReceipt", &receipt_params);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_getTransactionReceipt", &receipt_params);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_getTransactionReceipt", &receipt_params);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_no_more_requests();
+        assert_eq!(confirmations, vec![Ok(1), Ok(2), Ok(3)]);
+    }
+
+    #[test]
+    fn test_send_raw_transaction_with_confirmation() {
+        let mut transport = TestTransport::default();
+        let hash = H256::from_low_u64_be(0x111);
+        let receipt = TransactionReceipt {
+            transaction_hash: hash,
+            block_number: Some(2.into()),
+            ..Default::default()
+        };
+        transport.add_response(json!(hash));
+        transport.add_response(Value::String("0x123".into()));
+        transport.add_response(json!([H256::from_low_u64_be(0x456), H256::from_low_u64_be(0x457)]));
+        transport.add_response(json!(receipt));
+        transport.add_response(Value::String("0x3".into()));
+        transport.add_response(json!(receipt));
+
+        let result = futures::executor::block_on(send_raw_transaction_with_confirmation(
+            &transport,
+            Bytes(vec![1, 2, 3]),
+            Duration::from_secs(0),
+            1,
+        ));
+
+        let receipt_params = [r#""0x0000000000000000000000000000000000000000000000000000000000000111""#.to_string()];
+        transport.assert_request("eth_sendRawTransaction", &[r#""0x010203""#.into()]);
+        transport.assert_request("eth_newBlockFilter", &[]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_getTransactionReceipt", &receipt_params);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getTransactionReceipt", &receipt_params);
+        transport.assert_no_more_requests();
+        assert_eq!(result, Ok(receipt));
+    }
+
+    #[test]
+    fn test_send_raw_transaction_with_confirmation_cancelled() {
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String(
+            r#"0x0000000000000000000000000000000000000000000000000000000000000111"#.into(),
+        ));
+        transport.add_response(Value::String("0x123".into()));
+        transport.add_response(Value::Bool(true));
+
+        let cancel = Arc::new(AtomicBool::new(true));
+        let result = futures::executor::block_on(send_raw_transaction_with_confirmation_cancellable(
+            &transport,
+            Bytes(vec![1, 2, 3]),
+            Duration::from_secs(0),
+            3,
+            cancel,
+        ));
+
+        transport.assert_request("eth_sendRawTransaction", &[r#""0x010203""#.into()]);
+        transport.assert_request("eth_newBlockFilter", &[]);
+        transport.assert_request("eth_uninstallFilter", &[r#""0x123""#.into()]);
+        transport.assert_no_more_requests();
+        assert_eq!(result, Err(Error::Cancelled));
+    }
+
+    #[test]
+    fn test_average_block_time() {
+        let progress = ConfirmationProgress::new();
+        let start = Instant::now();
+        assert_eq!(progress.average_block_time(), None);
+
+        progress.block_seen(start);
+        assert_eq!(progress.average_block_time(), None);
+
+        progress.block_seen(start + Duration::from_secs(2));
+        progress.block_seen(start + Duration::from_secs(6));
+        assert_eq!(progress.average_block_time(), Some(Duration::from_secs(3)));
+
+        for second in 7..17 {
+            progress.block_seen(start + Duration::from_secs(second));
+        }
+        assert_eq!(progress.average_block_time(), Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_wait_for_confirmations_with_progress() {
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String("0x123".into()));
+        transport.add_response(json!([H256::from_low_u64_be(0x456)]));
+        transport.add_response(json!([H256::from_low_u64_be(0x457)]));
+        transport.add_response(Value::String("0x3".into()));
+        transport.add_response(json!([H256::from_low_u64_be(0x458)]));
+        transport.add_response(Value::String("0x4".into()));
+
+        let progress = ConfirmationProgress::new();
+        let checks = Cell::new(0);
+        let observed = std::cell::RefCell::new(vec![]);
+        let check = || {
+            observed.borrow_mut().push(progress.confirmations_so_far());
+            checks.set(checks.get() + 1);
+            let included_in = if checks.get() == 1 { None } else { Some(U64::from(2)) };
+            future::ready(Ok(included_in))
+        };
+        let result = futures::executor::block_on(wait_for_confirmations_with_progress(
+            Eth::new(&transport),
+            EthFilter::new(&transport),
+            Duration::from_secs(0),
+            2,
+            check,
+            progress.clone(),
+        ));
+
+        transport.assert_request("eth_newBlockFilter", &[]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_no_more_requests();
+        assert_eq!(result, Ok(()));
+        assert_eq!(observed.into_inner(), vec![None, None, Some(1)]);
+        assert_eq!(progress.confirmations_so_far(), Some(2));
+        assert!(progress.average_block_time().is_some());
+    }
+
+    #[test]
+    fn test_confirmations_stream_restarts_after_reorg() {
+        let mut transport = TestTransport::default();
+        let hash = H256::from_low_u64_be(0x111);
+        let receipt = |block_number: u64| {
+            json!(TransactionReceipt {
+                transaction_hash: hash,
+                transaction_index: U64::zero(),
+                block_hash: Some(H256::from_low_u64_be(block_number)),
+                block_number: Some(block_number.into()),
+                from: Address::from_low_u64_be(0x123),
+                to: Some(Address::from_low_u64_be(0x123)),
+                cumulative_gas_used: 0.into(),
+                gas_used: Some(0.into()),
+                contract_address: None,
+                logs: vec![],
+                status: Some(1.into()),
+                root: Some(H256::zero()),
+                logs_bloom: Default::default(),
+                transaction_type: None,
+                effective_gas_price: Default::default(),
+            })
+        };
+
+        transport.add_response(Value::String("0x123".into()));
+        transport.add_response(json!([H256::from_low_u64_be(0x456)]));
+        transport.add_response(receipt(5));
+        transport.add_response(Value::String("0x7".into()));
+        // the transaction got re-included in a lower block
+        transport.add_response(json!([H256::from_low_u64_be(0x457)]));
+        transport.add_response(receipt(4));
+        transport.add_response(Value::String("0x5".into()));
+
+        let confirmations = {
+            let stream = confirmations_stream(&transport, Duration::from_secs(0), hash).take(3);
+            futures::executor::block_on(stream.collect::<Vec<_>>())
+        };
+
+        let receipt_params = [r#""0x0000000000000000000000000000000000000000000000000000000000000111""#.to_string()];
+        transport.assert_request("eth_newBlockFilter", &[]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_getTransactionReceipt", &receipt_params);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_getTransactionReceipt", &receipt_params);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_no_more_requests();
+        assert_eq!(confirmations, vec![Ok(1), Ok(2), Ok(1)]);
+    }
+
+    #[test]
+    fn test_send_transaction_with_confirmation_restarts_count_after_reorg() {
+        let mut transport = TestTransport::default();
+        let hash = H256::from_low_u64_be(0x111);
+        let receipt = |block_hash| TransactionReceipt {
+            transaction_hash: hash,
+            block_hash: Some(H256::from_low_u64_be(block_hash)),
+            block_number: Some(2.into()),
+            ..Default::default()
+        };
+        transport.add_response(json!(hash));
+        transport.add_response(Value::String("0x123".into()));
+        transport.add_response(json!((0x456..0x459).map(H256::from_low_u64_be).collect::<Vec<_>>()));
+        transport.add_response(json!(receipt(0xa)));
+        transport.add_response(Value::String("0x3".into()));
+        // The transaction moves to another block at the same height, which would have been its 2nd confirmation.
+        transport.add_response(json!([H256::from_low_u64_be(0x459)]));
+        transport.add_response(Value::String("0x4".into()));
+        transport.add_response(json!(receipt(0xb)));
+        transport.add_response(Value::String("0x4".into()));
+        transport.add_response(json!([H256::from_low_u64_be(0x45a)]));
+        transport.add_response(Value::String("0x5".into()));
+        transport.add_response(json!(receipt(0xb)));
+        transport.add_response(json!([H256::from_low_u64_be(0x45b)]));
+        transport.add_response(Value::String("0x6".into()));
+        transport.add_response(json!(receipt(0xb)));
+        transport.add_response(json!(receipt(0xb)));
+
+        let result = futures::executor::block_on(send_transaction_with_confirmation(
+            &transport,
+            TransactionRequest {
+                from: Address::from_low_u64_be(0x123),
+                ..Default::default()
+            },
+            Duration::from_secs(0),
+            2,
+        ));
+
+        let hash_params = [r#""0x0000000000000000000000000000000000000000000000000000000000000111""#.to_string()];
+        transport.assert_request(
+            "eth_sendTransaction",
+            &[r#"{"from":"0x0000000000000000000000000000000000000123"}"#.into()],
+        );
+        transport.assert_request("eth_newBlockFilter", &[]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_getTransactionReceipt", &hash_params);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getTransactionReceipt", &hash_params);
+        transport.assert_request("eth_blockNumber", &[]);
+        for _ in 0..2 {
+            transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+            transport.assert_request("eth_blockNumber", &[]);
+            transport.assert_request("eth_getTransactionReceipt", &hash_params);
+        }
+        transport.assert_request("eth_getTransactionReceipt", &hash_params);
+        transport.assert_no_more_requests();
+        assert_eq!(result, Ok(receipt(0xb)));
+    }
+
+    #[test]
+    fn test_confirmations_stream_restarts_after_reorg_at_same_height() {
+        let mut transport = TestTransport::default();
+        let hash = H256::from_low_u64_be(0x111);
+        let receipt = |block_hash: u64| {
+            json!(TransactionReceipt {
+                transaction_hash: hash,
+                block_hash: Some(H256::from_low_u64_be(block_hash)),
+                block_number: Some(5.into()),
+                ..Default::default()
+            })
+        };
+
+        transport.add_response(Value::String("0x123".into()));
+        transport.add_response(json!([H256::from_low_u64_be(0x456)]));
+        transport.add_response(receipt(0xa));
+        transport.add_response(Value::String("0x6".into()));
+        // the block including the transaction got replaced by another one at the same height
+        transport.add_response(json!([H256::from_low_u64_be(0x457)]));
+        transport.add_response(receipt(0xb));
+        transport.add_response(Value::String("0x6".into()));
+
+        let confirmations = {
+            let stream = confirmations_stream(&transport, Duration::from_secs(0), hash).take(2);
+            futures::executor::block_on(stream.collect::<Vec<_>>())
+        };
+
+        assert_eq!(confirmations, vec![Ok(1), Ok(1)]);
+    }
+
+    fn deadline_request() -> TransactionRequest {
+        TransactionRequest {
+            from: Address::from_low_u64_be(0x123),
+            to: Some(Address::from_low_u64_be(0x123)),
+            gas_price: Some(100.into()),
+            nonce: Some(1.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_send_transaction_with_deadline_bumps_fees() {
+        let mut transport = TestTransport::default();
+        let receipt = TransactionReceipt {
+            transaction_hash: H256::from_low_u64_be(0x112),
+            transaction_index: U64::zero(),
+            block_hash: Some(H256::zero()),
+            block_number: Some(2.into()),
+            from: Address::from_low_u64_be(0x123),
+            to: Some(Address::from_low_u64_be(0x123)),
+            cumulative_gas_used: 0.into(),
+            gas_used: Some(0.into()),
+            contract_address: None,
+            logs: vec![],
+            status: Some(1.into()),
+            root: Some(H256::zero()),
+            logs_bloom: Default::default(),
+            transaction_type: None,
+            effective_gas_price: Default::default(),
+        };
+
+        transport.add_response(json!(H256::from_low_u64_be(0x111)));
+        transport.add_response(Value::String("0x123".into()));
+        transport.add_response(json!([H256::from_low_u64_be(0x456)]));
+        transport.add_response(Value::Null);
+        transport.add_response(json!(H256::from_low_u64_be(0x112)));
+        transport.add_response(json!([H256::from_low_u64_be(0x457)]));
+        transport.add_response(json!(receipt));
+
+        let policy = FeeBumpPolicy {
+            blocks: 1,
+            percent: 10,
+            max_fee: None,
+        };
+        let result = futures::executor::block_on(send_transaction_with_deadline(
+            &transport,
+            deadline_request(),
+            Duration::from_secs(0),
+            0,
+            Instant::now() + Duration::from_secs(60),
+            policy,
+        ));
+
+        transport.assert_request("eth_sendTransaction", &[r#"{"from":"0x0000000000000000000000000000000000000123","gasPrice":"0x64","nonce":"0x1","to":"0x0000000000000000000000000000000000000123"}"#.into()]);
+        transport.assert_request("eth_newBlockFilter", &[]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request(
+            "eth_getTransactionReceipt",
+            &[r#""0x0000000000000000000000000000000000000000000000000000000000000111""#.into()],
+        );
+        transport.assert_request("eth_sendTransaction", &[r#"{"from":"0x0000000000000000000000000000000000000123","gasPrice":"0x6e","nonce":"0x1","to":"0x0000000000000000000000000000000000000123"}"#.into()]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request(
+            "eth_getTransactionReceipt",
+            &[r#""0x0000000000000000000000000000000000000000000000000000000000000112""#.into()],
+        );
+        transport.assert_no_more_requests();
+        assert_eq!(result, Ok(receipt));
+    }
+
+    #[test]
+    fn test_send_transaction_with_deadline_exceeded() {
+        let mut transport = TestTransport::default();
+        transport.add_response(json!(H256::from_low_u64_be(0x111)));
+        transport.add_response(Value::String("0x123".into()));
+        transport.add_response(Value::Bool(true));
+
+        let result = futures::executor::block_on(send_transaction_with_deadline(
+            &transport,
+            deadline_request(),
+            Duration::from_secs(0),
+            1,
+            Instant::now(),
+            FeeBumpPolicy::default(),
+        ));
+
+        transport.assert_request("eth_sendTransaction", &[r#"{"from":"0x0000000000000000000000000000000000000123","gasPrice":"0x64","nonce":"0x1","to":"0x0000000000000000000000000000000000000123"}"#.into()]);
+        transport.assert_request("eth_newBlockFilter", &[]);
+        transport.assert_request("eth_uninstallFilter", &[r#""0x123""#.into()]);
+        transport.assert_no_more_requests();
+        assert_eq!(
+            result,
+            Err(Error::DeadlineExceeded {
+                last_hash: H256::from_low_u64_be(0x111)
+            })
+        );
+    }
+
+    #[test]
+    fn test_send_transaction_with_deadline_keeps_waiting_after_rejected_replacement() {
+        let mut transport = TestTransport::default();
+        let receipt = TransactionReceipt {
+            transaction_hash: H256::from_low_u64_be(0x111),
+            block_number: Some(2.into()),
+            ..Default::default()
+        };
+        transport.add_response(json!(H256::from_low_u64_be(0x111)));
+        transport.add_response(Value::String("0x123".into()));
+        transport.add_response(json!([H256::from_low_u64_be(0x456)]));
+        transport.add_response(Value::Null);
+        transport.add_error(Error::Rpc(rpc::Error {
+            code: rpc::ErrorCode::ServerError(-32000),
+            message: "nonce too low".into(),
+            data: None,
+        }));
+        transport.add_response(json!([H256::from_low_u64_be(0x457)]));
+        transport.add_response(json!(receipt));
+
+        let policy = FeeBumpPolicy {
+            blocks: 1,
+            ..Default::default()
+        };
+        let result = futures::executor::block_on(send_transaction_with_deadline(
+            &transport,
+            deadline_request(),
+            Duration::from_secs(0),
+            0,
+            Instant::now() + Duration::from_secs(60),
+            policy,
+        ));
+
+        let receipt_params = [r#""0x0000000000000000000000000000000000000000000000000000000000000111""#.to_string()];
+        transport.assert_request("eth_sendTransaction", &[r#"{"from":"0x0000000000000000000000000000000000000123","gasPrice":"0x64","nonce":"0x1","to":"0x0000000000000000000000000000000000000123"}"#.into()]);
+        transport.assert_request("eth_newBlockFilter", &[]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_getTransactionReceipt", &receipt_params);
+        transport.assert_request("eth_sendTransaction", &[r#"{"from":"0x0000000000000000000000000000000000000123","gasPrice":"0x70","nonce":"0x1","to":"0x0000000000000000000000000000000000000123"}"#.into()]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_getTransactionReceipt", &receipt_params);
+        transport.assert_no_more_requests();
+        assert_eq!(result, Ok(receipt));
+    }
+
+    #[test]
+    fn test_send_transaction_with_deadline_exceeded_ignores_uninstall_failure() {
+        let mut transport = TestTransport::default();
+        transport.add_response(json!(H256::from_low_u64_be(0x111)));
+        transport.add_response(Value::String("0x123".into()));
+        transport.add_error(Error::Unreachable);
+
+        let result = futures::executor::block_on(send_transaction_with_deadline(
+            &transport,
+            deadline_request(),
+            Duration::from_secs(0),
+            1,
+            Instant::now(),
+            FeeBumpPolicy::default(),
+        ));
+
+        transport.assert_request("eth_sendTransaction", &[r#"{"from":"0x0000000000000000000000000000000000000123","gasPrice":"0x64","nonce":"0x1","to":"0x0000000000000000000000000000000000000123"}"#.into()]);
+        transport.assert_request("eth_newBlockFilter", &[]);
+        transport.assert_request("eth_uninstallFilter", &[r#""0x123""#.into()]);
+        transport.assert_no_more_requests();
+        assert_eq!(
+            result,
+            Err(Error::DeadlineExceeded {
+                last_hash: H256::from_low_u64_be(0x111)
+            })
+        );
+    }
+
+    #[test]
+    fn test_bump_fees_keeps_priority_fee_below_max_fee() {
+        let tx = TransactionRequest {
+            max_fee_per_gas: Some(100.into()),
+            max_priority_fee_per_gas: Some(200.into()),
+            ..Default::default()
+        };
+
+        let bumped = bump_fees(&tx, &FeeBumpPolicy::default()).unwrap();
+
+        assert_eq!(bumped.max_fee_per_gas, Some(112.into()));
+        assert_eq!(bumped.max_priority_fee_per_gas, Some(112.into()));
+    }
+
+    #[test]
+    fn test_bump_fees_does_not_overflow() {
+        let policy = FeeBumpPolicy::default();
+        let tx = |fee: U256| TransactionRequest {
+            max_fee_per_gas: Some(fee),
+            max_priority_fee_per_gas: Some(1.into()),
+            ..Default::default()
+        };
+
+        let bumped = bump_fees(&tx(U256::MAX / 2), &policy).unwrap();
+        let capped = bump_fees(
+            &tx(U256::MAX / 2),
+            &FeeBumpPolicy {
+                max_fee: Some(U256::MAX / 100 * 55),
+                ..policy
+            },
+        )
+        .unwrap();
+        let saturated = bump_fees(&tx(U256::MAX), &policy).unwrap();
+
+        assert_eq!(bumped.max_fee_per_gas, Some(U256::MAX / 2 + U256::MAX / 200 * 12));
+        assert_eq!(capped.max_fee_per_gas, Some(U256::MAX / 100 * 55));
+        assert_eq!(saturated.max_fee_per_gas, Some(U256::MAX));
+        assert_eq!(saturated.max_priority_fee_per_gas, Some(2.into()));
+        assert_eq!(
+            bump_fees(
+                &TransactionRequest {
+                    gas_price: Some(U256::MAX),
+                    ..Default::default()
+                },
+                &policy
+            ),
+            None
+        );
+    }
+
+    /// Clock advancing by one second every time it is read and never resolving delays.
+    struct MockClock {
+        start: Instant,
+        elapsed: Cell<Duration>,
+    }
+
+    impl Clock for &MockClock {
+        type Delay = future::Pending<()>;
+
+        fn now(&self) -> Instant {
+            let elapsed = self.elapsed.get();
+            self.elapsed.set(elapsed + Duration::from_secs(1));
+            self.start + elapsed
+        }
+
+        fn delay(&self, _duration: Duration) -> Self::Delay {
+            future::pending()
+        }
+    }
+
+    #[test]
+    fn test_send_transaction_with_deadline_uses_clock() {
+        let mut transport = TestTransport::default();
+        transport.add_response(json!(H256::from_low_u64_be(0x111)));
+        transport.add_response(Value::String("0x123".into()));
+        transport.add_response(json!([H256::from_low_u64_be(0x456)]));
+        transport.add_response(Value::Null);
+        transport.add_response(json!([H256::from_low_u64_be(0x457)]));
+        transport.add_response(Value::Null);
+        transport.add_response(Value::Bool(true));
+
+        let clock = MockClock {
+            start: Instant::now(),
+            elapsed: Cell::new(Duration::from_secs(0)),
+        };
+        let result = futures::executor::block_on(send_transaction_with_deadline_and_clock(
+            &transport,
+            deadline_request(),
+            Duration::from_secs(0),
+            0,
+            clock.start + Duration::from_secs(2),
+            FeeBumpPolicy::default(),
+            &clock,
+        ));
+
+        let receipt_params = [r#""0x0000000000000000000000000000000000000000000000000000000000000111""#.to_string()];
+        transport.assert_request("eth_sendTransaction", &[r#"{"from":"0x0000000000000000000000000000000000000123","gasPrice":"0x64","nonce":"0x1","to":"0x0000000000000000000000000000000000000123"}"#.into()]);
+        transport.assert_request("eth_newBlockFilter", &[]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_getTransactionReceipt", &receipt_params);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_getTransactionReceipt", &receipt_params);
+        transport.assert_request("eth_uninstallFilter", &[r#""0x123""#.into()]);
+        transport.assert_no_more_requests();
+        assert_eq!(
+            result,
+            Err(Error::DeadlineExceeded {
+                last_hash: H256::from_low_u64_be(0x111)
+            })
+        );
+    }
+
+    #[test]
+    fn test_gas_with_margin() {
+        assert_eq!(
+            gas_with_margin(100_000.into(), 25, 1_000_000.into()),
+            Ok(125_000.into())
+        );
+        assert_eq!(gas_with_margin(100_000.into(), 25, 125_000.into()), Ok(125_000.into()));
+        assert_eq!(
+            gas_with_margin(100_000.into(), 25, 110_000.into()),
+            Err(Error::GasLimitExceeded {
+                gas: 125_000.into(),
+                max_gas: 110_000.into()
+            })
+        );
+        assert_eq!(
+            gas_with_margin(U256::max_value(), 25, U256::max_value()),
+            Ok(U256::max_value())
+        );
+    }
+
+    #[test]
+    fn test_send_transaction_auto_gas() {
+        let mut transport = TestTransport::default();
+        let receipt = TransactionReceipt {
+            transaction_hash: H256::from_low_u64_be(0x111),
+            block_number: Some(2.into()),
+            ..Default::default()
+        };
+        transport.add_response(Value::String("0x5208".into()));
+        transport.add_response(json!(H256::from_low_u64_be(0x111)));
+        transport.add_response(json!(receipt));
+
+        let result = futures::executor::block_on(send_transaction_auto_gas(
+            &transport,
+            deadline_request(),
+            25,
+            1_000_000.into(),
+            Duration::from_secs(0),
+            0,
+        ));
+
+        transport.assert_request("eth_estimateGas", &[r#"{"from":"0x0000000000000000000000000000000000000123","gasPrice":"0x64","to":"0x0000000000000000000000000000000000000123"}"#.into()]);
+        transport.assert_request("eth_sendTransaction", &[r#"{"from":"0x0000000000000000000000000000000000000123","gas":"0x668a","gasPrice":"0x64","nonce":"0x1","to":"0x0000000000000000000000000000000000000123"}"#.into()]);
+        transport.assert_request(
+            "eth_getTransactionReceipt",
+            &[r#""0x0000000000000000000000000000000000000000000000000000000000000111""#.into()],
+        );
+        transport.assert_no_more_requests();
+        assert_eq!(result, Ok(receipt));
+    }
+
+    #[test]
+    fn test_send_transaction_auto_gas_above_max_gas() {
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String("0x5208".into()));
+
+        let result = futures::executor::block_on(send_transaction_auto_gas(
+            &transport,
+            deadline_request(),
+            25,
+            21_000.into(),
+            Duration::from_secs(0),
+            0,
+        ));
+
+        transport.assert_request("eth_estimateGas", &[r#"{"from":"0x0000000000000000000000000000000000000123","gasPrice":"0x64","to":"0x0000000000000000000000000000000000000123"}"#.into()]);
+        transport.assert_no_more_requests();
+        assert_eq!(
+            result,
+            Err(Error::GasLimitExceeded {
+                gas: 26_250.into(),
+                max_gas: 21_000.into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_send_transaction_with_confirmation_dropped() {
+        let mut transport = TestTransport::default();
+        let hash = H256::from_low_u64_be(0x111);
+        transport.add_response(json!(hash));
+        transport.add_response(Value::String("0x123".into()));
+        transport.add_response(json!((0x456..0x45b).map(H256::from_low_u64_be).collect::<Vec<_>>()));
+        transport.add_response(Value::Null);
+        transport.add_response(json!(Transaction {
+            hash,
+            ..Default::default()
+        }));
+        for _ in 0..3 {
+            transport.add_response(Value::Null);
+            transport.add_response(Value::Null);
+        }
+
+        let result = futures::executor::block_on(send_transaction_with_confirmation(
+            &transport,
+            TransactionRequest {
+                from: Address::from_low_u64_be(0x123),
+                ..Default::default()
+            },
+            Duration::from_secs(0),
+            1,
+        ));
+
+        let hash_params = [r#""0x0000000000000000000000000000000000000000000000000000000000000111""#.to_string()];
+        transport.assert_request(
+            "eth_sendTransaction",
+            &[r#"{"from":"0x0000000000000000000000000000000000000123"}"#.into()],
+        );
+        transport.assert_request("eth_newBlockFilter", &[]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        for _ in 0..4 {
+            transport.assert_request("eth_getTransactionReceipt", &hash_params);
+            transport.assert_request("eth_getTransactionByHash", &hash_params);
+        }
+        transport.assert_no_more_requests();
+        assert_eq!(result, Err(Error::TransactionDropped(hash)));
+    }
+
+    #[test]
+    fn test_send_transaction_with_confirmation_requests_block_number_with_receipt() {
+        let mut transport = TestTransport::default();
+        let hash = H256::from_low_u64_be(0x111);
+        let receipt = TransactionReceipt {
+            transaction_hash: hash,
+            block_number: Some(2.into()),
+            ..Default::default()
+        };
+        transport.add_response(json!(hash));
+        transport.add_response(Value::String("0x123".into()));
+        transport.add_response(json!([H256::from_low_u64_be(0x456), H256::from_low_u64_be(0x457)]));
+        transport.add_response(json!(receipt));
+        transport.add_response(Value::String("0x2".into()));
+        transport.add_response(json!([H256::from_low_u64_be(0x458)]));
+        transport.add_response(Value::String("0x3".into()));
+        transport.add_response(json!(receipt));
+        transport.add_response(json!(receipt));
+
+        let result = futures::executor::block_on(send_transaction_with_confirmation(
+            &transport,
+            TransactionRequest {
+                from: Address::from_low_u64_be(0x123),
+                ..Default::default()
+            },
+            Duration::from_secs(0),
+            1,
+        ));
+
+        let hash_params = [r#""0x0000000000000000000000000000000000000000000000000000000000000111""#.to_string()];
+        transport.assert_request(
+            "eth_sendTransaction",
+            &[r#"{"from":"0x0000000000000000000000000000000000000123"}"#.into()],
+        );
+        transport.assert_request("eth_newBlockFilter", &[]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_getTransactionReceipt", &hash_params);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getTransactionReceipt", &hash_params);
+        transport.assert_request("eth_getTransactionReceipt", &hash_params);
+        transport.assert_no_more_requests();
+        assert_eq!(result, Ok(receipt));
+    }
+
+    #[test]
+    fn test_wait_for_confirmations_measures_block_time_with_clock() {
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String("0x123".into()));
+        transport.add_response(json!([H256::from_low_u64_be(0x456)]));
+        transport.add_response(Value::String("0x2".into()));
+        transport.add_response(json!([H256::from_low_u64_be(0x457)]));
+        transport.add_response(Value::String("0x3".into()));
+
+        let clock = MockClock {
+            start: Instant::now(),
+            elapsed: Cell::new(Duration::from_secs(0)),
+        };
+        let progress = ConfirmationProgress::new();
+        let check = || future::ready(Ok(Some(U64::from(2))));
+        let result = futures::executor::block_on(wait_for_confirmations_(
+            Eth::new(&transport),
+            EthFilter::new(&transport),
+            Duration::from_secs(0),
+            1,
+            check,
+            None,
+            Some(progress.clone()),
+            &clock,
+        ));
+
+        transport.assert_request("eth_newBlockFilter", &[]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_no_more_requests();
+        assert_eq!(result, Ok(()));
+        assert_eq!(progress.average_block_time(), Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_wait_for_confirmations_timestamps_every_poll_once() {
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String("0x123".into()));
+        transport.add_response(json!([H256::from_low_u64_be(0x456), H256::from_low_u64_be(0x457)]));
+        transport.add_response(Value::String("0x2".into()));
+        transport.add_response(Value::String("0x3".into()));
+        transport.add_response(json!([H256::from_low_u64_be(0x458)]));
+        transport.add_response(Value::String("0x4".into()));
+
+        let clock = MockClock {
+            start: Instant::now(),
+            elapsed: Cell::new(Duration::from_secs(0)),
+        };
+        let check = || future::ready(Ok(Some(U64::from(2))));
+        let result = futures::executor::block_on(wait_for_confirmations_(
+            Eth::new(&transport),
+            EthFilter::new(&transport),
+            Duration::from_secs(0),
+            2,
+            check,
+            None,
+            Some(ConfirmationProgress::new()),
+            &clock,
+        ));
+
+        assert_eq!(result, Ok(()));
+        transport.assert_request("eth_newBlockFilter", &[]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        transport.assert_request("eth_blockNumber", &[]);
+        transport.assert_no_more_requests();
+        // two polls, so the clock was read twice
+        assert_eq!(clock.elapsed.get(), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_send_transaction_checked() {
+        let mut transport = TestTransport::default();
+        let receipt = TransactionReceipt {
+            transaction_hash: H256::from_low_u64_be(0x111),
+            block_number: Some(2.into()),
+            ..Default::default()
+        };
+        transport.add_response(Value::String("0x".into()));
+        transport.add_response(json!(H256::from_low_u64_be(0x111)));
+        transport.add_response(json!(receipt));
+
+        let result = futures::executor::block_on(send_transaction_checked(
+            &transport,
+            deadline_request(),
+            Duration::from_secs(0),
+            0,
+        ));
+
+        transport.assert_request("eth_call", &[r#"{"from":"0x0000000000000000000000000000000000000123","gasPrice":"0x64","to":"0x0000000000000000000000000000000000000123"}"#.into(), r#""pending""#.into()]);
+        transport.assert_request("eth_sendTransaction", &[r#"{"from":"0x0000000000000000000000000000000000000123","gasPrice":"0x64","nonce":"0x1","to":"0x0000000000000000000000000000000000000123"}"#.into()]);
+        transport.assert_request(
+            "eth_getTransactionReceipt",
+            &[r#""0x0000000000000000000000000000000000000000000000000000000000000111""#.into()],
+        );
+        transport.assert_no_more_requests();
+        assert_eq!(result, Ok(receipt));
+    }
+
+    #[test]
+    fn test_send_transaction_checked_revert() {
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::String("0x08c379a00000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000001a4e6f7420656e6f7567682045746865722070726f76696465642e000000000000".into()));
+
+        let result = futures::executor::block_on(send_transaction_checked(
+            &transport,
+            deadline_request(),
+            Duration::from_secs(0),
+            0,
+        ));
+
+        transport.assert_request("eth_call", &[r#"{"from":"0x0000000000000000000000000000000000000123","gasPrice":"0x64","to":"0x0000000000000000000000000000000000000123"}"#.into(), r#""pending""#.into()]);
+        transport.assert_no_more_requests();
+        assert_eq!(result, Err(Error::Revert("Not enough Ether provided.".into())));
+    }
+
+    #[test]
+    fn test_wait_until() {
+        let mut transport = TestTransport::default();
+        transport.add_response(Value::Bool(false));
+        transport.add_response(Value::Bool(false));
+        transport.add_response(Value::Bool(true));
+        let call = || {
+            json!({
+                "to": "0x0000000000000000000000000000000000000123",
+                "data": "0x01",
+            })
+        };
+
+        let result = futures::executor::block_on(wait_until(&transport, Duration::from_secs(0), |transport| {
+            transport
+                .execute("eth_call", vec![call(), json!("latest")])
+                .map_ok(|value| value == Value::Bool(true))
+        }));
+
+        assert_eq!(result, Ok(()));
+        for _ in 0..3 {
+            transport.assert_request_values("eth_call", &[call(), json!("latest")]);
+        }
+        transport.assert_no_more_requests();
+    }
 }
@@ -214,26 +214,46 @@ impl<T: Transport> Builder<T> {
             max_priority_fee_per_gas: options.max_priority_fee_per_gas,
         };
         let receipt = send(tx).await?;
-        match receipt.status {
-            Some(status) if status == 0.into() => Err(Error::ContractDeploymentFailure(receipt.transaction_hash)),
-            // If the `status` field is not present we use the presence of `contract_address` to
-            // determine if deployment was successfull.
-            _ => match receipt.contract_address {
-                Some(address) => Ok(Contract::new(eth, address, abi)),
-                None => Err(Error::ContractDeploymentFailure(receipt.transaction_hash)),
-            },
-        }
+        let address = deployed_address(&receipt)?;
+        Ok(Contract::new(eth, address, abi))
     }
 }
 
+/// Returns the address of the contract created by the transaction with given receipt.
+fn deployed_address(receipt: &TransactionReceipt) -> Result<Address, Error> {
+    match receipt.status {
+        Some(status) if status == 0.into() => Err(Error::ContractDeploymentFailure(receipt.transaction_hash)),
+        // If the `status` field is not present we use the presence of `contract_address` to
+        // determine if deployment was successfull.
+        _ => match receipt.contract_address {
+            Some(address) => Ok(address),
+            None => Err(Error::ContractDeploymentFailure(receipt.transaction_hash)),
+        },
+    }
+}
+
+/// Sends a contract creation transaction and returns the address of the contract once confirmed.
+///
+/// Fails with `Error::ContractDeploymentFailure` if the transaction failed or did not create a contract.
+pub async fn deploy_contract<T: Transport>(
+    transport: T,
+    tx: TransactionRequest,
+    poll_interval: time::Duration,
+    confirmations: usize,
+) -> Result<Address, Error> {
+    let receipt = confirm::send_transaction_with_confirmation(transport, tx, poll_interval, confirmations).await?;
+    deployed_address(&receipt)
+}
+
 #[cfg(test)]
 mod tests {
+    use super::{deploy_contract, Error};
     use crate::{
         api::{self, Namespace},
         contract::{Contract, Options},
         rpc,
         transports::test::TestTransport,
-        types::{Address, U256},
+        types::{Address, Bytes, TransactionReceipt, TransactionRequest, H256, U256},
     };
     use serde_json::Value;
     use std::collections::HashMap;
@@ -336,13 +356,13 @@ mod tests {
         let lib_address;
         {
             let builder = Contract::deploy(api::Eth::new(&transport), &lib_abi).unwrap();
-            lib_address = futures::executor::block_on(builder.execute(lib_code, (), Address::zero()))
+            lib_address = futures::executor::block_on(builder.execute(lib_code, (), Address::from_low_u64_be(5)))
                 .unwrap()
                 .address();
         }
 
         transport.assert_request("eth_sendTransaction", &[
-            "{\"data\":\"0x60ad61002f600b82828239805160001a6073146000811461001f57610021565bfe5b5030600052607381538281f3fe73000000000000000000000000000000000000000030146080604052600436106056576000357c0100000000000000000000000000000000000000000000000000000000900463ffffffff168063f8a8fd6d14605b575b600080fd5b60616077565b6040518082815260200191505060405180910390f35b600061010090509056fea165627a7a72305820b50091adcb7ef9987dd8daa665cec572801bf8243530d70d52631f9d5ddb943e0029\",\"from\":\"0x0000000000000000000000000000000000000000\"}"
+            "{\"data\":\"0x60ad61002f600b82828239805160001a6073146000811461001f57610021565bfe5b5030600052607381538281f3fe73000000000000000000000000000000000000000030146080604052600436106056576000357c0100000000000000000000000000000000000000000000000000000000900463ffffffff168063f8a8fd6d14605b575b600080fd5b60616077565b6040518082815260200191505060405180910390f35b600061010090509056fea165627a7a72305820b50091adcb7ef9987dd8daa665cec572801bf8243530d70d52631f9d5ddb943e0029\",\"from\":\"0x0000000000000000000000000000000000000005\"}"
             .into()]);
         transport.assert_request("eth_newBlockFilter", &[]);
         transport.assert_request("eth_getFilterChanges", &["\"0x0\"".into()]);
@@ -364,11 +384,11 @@ mod tests {
                 linker
             })
             .unwrap();
-            let _ = futures::executor::block_on(builder.execute(main_code, (), Address::zero())).unwrap();
+            let _ = futures::executor::block_on(builder.execute(main_code, (), Address::from_low_u64_be(5))).unwrap();
         }
 
         transport.assert_request("eth_sendTransaction", &[
-            "{\"data\":\"0x608060405234801561001057600080fd5b5061013f806100206000396000f3fe608060405260043610610041576000357c0100000000000000000000000000000000000000000000000000000000900463ffffffff168063f8a8fd6d14610046575b600080fd5b34801561005257600080fd5b5061005b610071565b6040518082815260200191505060405180910390f35b600073600515dfe465f600f0c9793fa27cd2794f3ec0e163f8a8fd6d6040518163ffffffff167c010000000000000000000000000000000000000000000000000000000002815260040160206040518083038186803b1580156100d357600080fd5b505af41580156100e7573d6000803e3d6000fd5b505050506040513d60208110156100fd57600080fd5b810190808051906020019092919050505090509056fea165627a7a72305820580d3776b3d132142f431e141a2e20bd4dd4907fa304feea7b604e8f39ed59520029\",\"from\":\"0x0000000000000000000000000000000000000000\"}"
+            "{\"data\":\"0x608060405234801561001057600080fd5b5061013f806100206000396000f3fe608060405260043610610041576000357c0100000000000000000000000000000000000000000000000000000000900463ffffffff168063f8a8fd6d14610046575b600080fd5b34801561005257600080fd5b5061005b610071565b6040518082815260200191505060405180910390f35b600073600515dfe465f600f0c9793fa27cd2794f3ec0e163f8a8fd6d6040518163ffffffff167c010000000000000000000000000000000000000000000000000000000002815260040160206040518083038186803b1580156100d357600080fd5b505af41580156100e7573d6000803e3d6000fd5b505050506040513d60208110156100fd57600080fd5b810190808051906020019092919050505090509056fea165627a7a72305820580d3776b3d132142f431e141a2e20bd4dd4907fa304feea7b604e8f39ed59520029\",\"from\":\"0x0000000000000000000000000000000000000005\"}"
             .into()]);
 
         transport.assert_request("eth_newBlockFilter", &[]);
@@ -385,4 +405,73 @@ mod tests {
         );
         transport.assert_no_more_requests();
     }
+
+    fn deployment_receipt(contract_address: Option<Address>) -> TransactionReceipt {
+        TransactionReceipt {
+            transaction_hash: H256::from_low_u64_be(0x111),
+            block_hash: Some(H256::from_low_u64_be(0x222)),
+            block_number: Some(0x256.into()),
+            from: Address::from_low_u64_be(5),
+            contract_address,
+            status: Some(1.into()),
+            ..Default::default()
+        }
+    }
+
+    fn deployment_request() -> TransactionRequest {
+        TransactionRequest {
+            from: Address::from_low_u64_be(5),
+            data: Some(Bytes(vec![1, 2, 3, 4])),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_return_deployed_contract_address() {
+        // given
+        let mut transport = TestTransport::default();
+        transport.add_response(serde_json::json!(H256::from_low_u64_be(0x111)));
+        transport.add_response(serde_json::json!(deployment_receipt(Some(Address::from_low_u64_be(
+            0x600
+        )))));
+
+        // when
+        let address = futures::executor::block_on(deploy_contract(
+            &transport,
+            deployment_request(),
+            std::time::Duration::from_secs(0),
+            0,
+        ));
+
+        // then
+        transport.assert_request(
+            "eth_sendTransaction",
+            &[r#"{"data":"0x01020304","from":"0x0000000000000000000000000000000000000005"}"#.into()],
+        );
+        transport.assert_request(
+            "eth_getTransactionReceipt",
+            &[r#""0x0000000000000000000000000000000000000000000000000000000000000111""#.into()],
+        );
+        transport.assert_no_more_requests();
+        assert_eq!(address.unwrap(), Address::from_low_u64_be(0x600));
+    }
+
+    #[test]
+    fn should_fail_when_no_contract_was_created() {
+        // given
+        let mut transport = TestTransport::default();
+        transport.add_response(serde_json::json!(H256::from_low_u64_be(0x111)));
+        transport.add_response(serde_json::json!(deployment_receipt(None)));
+
+        // when
+        let result = futures::executor::block_on(deploy_contract(
+            &transport,
+            deployment_request(),
+            std::time::Duration::from_secs(0),
+            0,
+        ));
+
+        // then
+        assert!(matches!(result, Err(Error::ContractDeploymentFailure(hash)) if hash == H256::from_low_u64_be(0x111)));
+    }
 }
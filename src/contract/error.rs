@@ -1,9 +1,12 @@
 //! Contract call/query error.
 
-use crate::error::Error as ApiError;
+use crate::{error::Error as ApiError, rpc};
 use derive_more::{Display, From};
 use ethabi::Error as EthError;
 
+/// Selector of the `Error(string)` revert payload.
+const REVERT_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
 /// Contract error.
 #[derive(Debug, Display, From)]
 pub enum Error {
@@ -15,7 +18,12 @@ pub enum Error {
     Abi(EthError),
     /// Rpc error
     #[display(fmt = "Api error: {}", _0)]
+    #[from(ignore)]
     Api(ApiError),
+    /// The call reverted with given reason
+    #[display(fmt = "Reverted: {}", _0)]
+    #[from(ignore)]
+    Revert(String),
     /// An error during deployment.
     #[display(fmt = "Deployment error: {}", _0)]
     Deployment(crate::contract::deploy::Error),
@@ -26,7 +34,7 @@ pub enum Error {
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match *self {
-            Error::InvalidOutputType(_) => None,
+            Error::InvalidOutputType(_) | Error::Revert(_) => None,
             Error::Abi(ref e) => Some(e),
             Error::Api(ref e) => Some(e),
             Error::Deployment(ref e) => Some(e),
@@ -35,6 +43,52 @@ impl std::error::Error for Error {
     }
 }
 
+impl From<ApiError> for Error {
+    fn from(err: ApiError) -> Self {
+        match revert_reason(&err) {
+            Some(reason) => Error::Revert(reason),
+            None => Error::Api(err),
+        }
+    }
+}
+
+/// Decodes the revert reason carried by an RPC error of a reverted call, if any.
+pub(crate) fn revert_reason(err: &ApiError) -> Option<String> {
+    match err.root() {
+        ApiError::Rpc(rpc_err) => rpc_err
+            .data
+            .as_ref()
+            .and_then(revert_data)
+            .as_deref()
+            .and_then(decode_revert_reason),
+        _ => None,
+    }
+}
+
+/// Extracts the revert payload from the `data` field of an RPC error.
+///
+/// Nodes return it either as a hex string or as an object with a nested `data` field.
+fn revert_data(data: &rpc::Value) -> Option<Vec<u8>> {
+    match data {
+        rpc::Value::String(data) => hex::decode(data.strip_prefix("0x").unwrap_or(data)).ok(),
+        rpc::Value::Object(object) => object.get("data").and_then(revert_data),
+        _ => None,
+    }
+}
+
+/// Decodes the reason of a revert from an `Error(string)` payload.
+///
+/// Returns `None` if `data` is not such a payload.
+pub fn decode_revert_reason(data: &[u8]) -> Option<String> {
+    if data.len() < 4 || data[..4] != REVERT_SELECTOR {
+        return None;
+    }
+    match ethabi::decode(&[ethabi::ParamType::String], &data[4..]).ok()?.pop()? {
+        ethabi::Token::String(reason) => Some(reason),
+        _ => None,
+    }
+}
+
 pub mod deploy {
     use crate::{error::Error as ApiError, types::H256};
     use derive_more::{Display, From};
@@ -63,3 +117,49 @@ pub mod deploy {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_revert_reason, Error};
+    use crate::{error::Error as ApiError, rpc};
+    use hex_literal::hex;
+
+    const REVERT: &str = "0x08c379a00000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000001a4e6f7420656e6f7567682045746865722070726f76696465642e000000000000";
+
+    fn rpc_error(data: Option<rpc::Value>) -> ApiError {
+        ApiError::Rpc(rpc::Error {
+            code: rpc::ErrorCode::ServerError(3),
+            message: "execution reverted".into(),
+            data,
+        })
+    }
+
+    #[test]
+    fn should_decode_revert_reason() {
+        let data = hex::decode(&REVERT[2..]).unwrap();
+        assert_eq!(decode_revert_reason(&data), Some("Not enough Ether provided.".into()));
+        assert_eq!(decode_revert_reason(&hex!("08c379a0")), None);
+        assert_eq!(decode_revert_reason(&hex!("4e487b71")), None);
+    }
+
+    #[test]
+    fn should_decode_revert_reason_from_rpc_error_data() {
+        let err = Error::from(rpc_error(Some(rpc::Value::String(REVERT.into()))));
+        assert!(matches!(err, Error::Revert(ref reason) if reason == "Not enough Ether provided."));
+
+        let err = Error::from(rpc_error(Some(serde_json::json!({ "data": REVERT }))));
+        assert!(matches!(err, Error::Revert(ref reason) if reason == "Not enough Ether provided."));
+
+        let err = Error::from(rpc_error(None));
+        assert!(matches!(err, Error::Api(ApiError::Rpc(_))));
+    }
+
+    #[test]
+    fn should_decode_revert_reason_from_error_with_request_id() {
+        let err = Error::from(ApiError::Request {
+            id: 1,
+            error: Box::new(rpc_error(Some(rpc::Value::String(REVERT.into())))),
+        });
+        assert!(matches!(err, Error::Revert(ref reason) if reason == "Not enough Ether provided."));
+    }
+}
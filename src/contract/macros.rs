@@ -0,0 +1,198 @@
+//! Typed wrappers of contracts.
+
+/// Declares a typed wrapper around [`Contract`](crate::contract::Contract).
+///
+/// This is not code generation from the ABI: the wrapped functions and their Rust signatures
+/// are written out in the declaration, the macro only turns each of them into a method with
+/// typed arguments, executed through the underlying `Contract`. `query` functions are constant
+/// calls decoding the output into the declared return type, `call` functions send a
+/// transaction and return its hash.
+///
+/// The ABI is loaded (and every declared function looked up in it) when the wrapper is
+/// created, so a function missing from the ABI is reported by `new`. Whether the declared
+/// types match the ABI is only checked when the function is called, by encoding the
+/// arguments and decoding the output.
+///
+/// ```
+/// use web3::types::{Address, H256, U256};
+///
+/// web3::contract_interface! {
+///     /// ERC-20 token.
+///     pub struct Token(include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/contract/res/token.json"))) {
+///         /// Returns the token balance of `owner`.
+///         query fn balance_of = "balanceOf"(owner: Address) -> U256;
+///         /// Transfers `amount` tokens to `to`.
+///         call fn transfer = "transfer"(to: Address, amount: U256);
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! contract_interface {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident($abi:expr) {
+            $(
+                $(#[$fn_attr:meta])*
+                $kind:ident fn $fn_name:ident = $func:literal ($($arg:ident : $arg_ty:ty),* $(,)?) $(-> $ret:ty)?;
+            )*
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone)]
+        $vis struct $name<T: $crate::Transport> {
+            contract: $crate::contract::Contract<T>,
+        }
+
+        impl<T: $crate::Transport> $name<T> {
+            /// Creates the interface of a contract deployed at given address.
+            pub fn new(eth: $crate::api::Eth<T>, address: $crate::types::Address) -> $crate::ethabi::Result<Self> {
+                let contract = $crate::contract::Contract::from_json(eth, address, $abi)?;
+                $(
+                    contract.abi().function($func)?;
+                )*
+                Ok($name { contract })
+            }
+
+            /// Returns the underlying untyped contract.
+            pub fn contract(&self) -> &$crate::contract::Contract<T> {
+                &self.contract
+            }
+
+            $(
+                $crate::contract_interface!(
+                    @method $kind $(#[$fn_attr])* $fn_name $func ($($arg: $arg_ty),*) $(-> $ret)?
+                );
+            )*
+        }
+    };
+
+    (@method query $(#[$fn_attr:meta])* $fn_name:ident $func:literal ($($arg:ident : $arg_ty:ty),*) -> $ret:ty) => {
+        $(#[$fn_attr])*
+        pub fn $fn_name(
+            &self,
+            $($arg: $arg_ty,)*
+            options: $crate::contract::Options,
+            block: impl Into<Option<$crate::types::BlockId>>,
+        ) -> impl $crate::futures::Future<Output = $crate::contract::Result<$ret>> + '_ {
+            self.contract.query($func, ($($arg,)*), None, options, block)
+        }
+    };
+
+    (@method call $(#[$fn_attr:meta])* $fn_name:ident $func:literal ($($arg:ident : $arg_ty:ty),*)) => {
+        $(#[$fn_attr])*
+        pub fn $fn_name(
+            &self,
+            $($arg: $arg_ty,)*
+            from: $crate::types::Address,
+            options: $crate::contract::Options,
+        ) -> impl $crate::futures::Future<Output = $crate::contract::Result<$crate::types::H256>> + '_ {
+            self.contract.call($func, ($($arg,)*), from, options)
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::{self, Namespace},
+        contract::Options,
+        rpc,
+        transports::test::TestTransport,
+        types::{Address, H256, U256},
+    };
+
+    crate::contract_interface! {
+        struct Token(include_bytes!("./res/token.json")) {
+            query fn name = "name"() -> String;
+            query fn balance_of = "balanceOf"(owner: Address) -> U256;
+            call fn transfer = "transfer"(to: Address, amount: U256);
+        }
+    }
+
+    #[allow(dead_code)]
+    mod unknown {
+        use crate::types::U256;
+
+        crate::contract_interface! {
+            pub struct Unknown(include_bytes!("./res/token.json")) {
+                query fn missing = "missing"() -> U256;
+            }
+        }
+    }
+
+    fn token(transport: &TestTransport) -> Token<&TestTransport> {
+        Token::new(api::Eth::new(transport), Address::from_low_u64_be(1)).unwrap()
+    }
+
+    #[test]
+    fn should_query_without_params() {
+        // given
+        let mut transport = TestTransport::default();
+        transport.set_response(rpc::Value::String("0x0000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000000c48656c6c6f20576f726c64210000000000000000000000000000000000000000".into()));
+
+        // when
+        let token = token(&transport);
+        let result = futures::executor::block_on(token.name(Options::default(), None));
+        let address = token.contract().address();
+
+        // then
+        transport.assert_request(
+            "eth_call",
+            &[
+                "{\"data\":\"0x06fdde03\",\"to\":\"0x0000000000000000000000000000000000000001\"}".into(),
+                "\"latest\"".into(),
+            ],
+        );
+        transport.assert_no_more_requests();
+        assert_eq!(result.unwrap(), "Hello World!".to_owned());
+        assert_eq!(address, Address::from_low_u64_be(1));
+    }
+
+    #[test]
+    fn should_query_with_typed_params() {
+        // given
+        let mut transport = TestTransport::default();
+        transport.set_response(rpc::Value::String(
+            "0x0000000000000000000000000000000000000000000000000000000000000020".into(),
+        ));
+
+        // when
+        let result = futures::executor::block_on(token(&transport).balance_of(
+            Address::from_low_u64_be(5),
+            Options::default(),
+            None,
+        ));
+
+        // then
+        transport.assert_request("eth_call", &["{\"data\":\"0x70a082310000000000000000000000000000000000000000000000000000000000000005\",\"to\":\"0x0000000000000000000000000000000000000001\"}".into(), "\"latest\"".into()]);
+        transport.assert_no_more_requests();
+        assert_eq!(result.unwrap(), 0x20.into());
+    }
+
+    #[test]
+    fn should_send_transaction_with_typed_params() {
+        // given
+        let mut transport = TestTransport::default();
+        transport.set_response(rpc::Value::String(format!("{:?}", H256::from_low_u64_be(5))));
+
+        // when
+        let result = futures::executor::block_on(token(&transport).transfer(
+            Address::from_low_u64_be(2),
+            3.into(),
+            Address::from_low_u64_be(5),
+            Options::default(),
+        ));
+
+        // then
+        transport.assert_request("eth_sendTransaction", &["{\"data\":\"0xa9059cbb00000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000003\",\"from\":\"0x0000000000000000000000000000000000000005\",\"to\":\"0x0000000000000000000000000000000000000001\"}".into()]);
+        transport.assert_no_more_requests();
+        assert_eq!(result.unwrap(), H256::from_low_u64_be(5));
+    }
+
+    #[test]
+    fn should_reject_functions_missing_from_abi() {
+        let transport = TestTransport::default();
+        let result = unknown::Unknown::new(api::Eth::new(&transport), Address::from_low_u64_be(1));
+        assert!(result.is_err());
+    }
+}
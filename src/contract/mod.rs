@@ -1,10 +1,10 @@
 //! Ethereum Contract Interface
 
 use crate::{
-    api::{Eth, Namespace},
+    api::{Eth, EthFilter, Namespace},
     confirm,
     contract::tokens::{Detokenize, Tokenize},
-    futures::Future,
+    futures::{future, Future, Stream, TryFutureExt, TryStreamExt},
     types::{
         AccessList, Address, BlockId, Bytes, CallRequest, FilterBuilder, TransactionCondition, TransactionReceipt,
         TransactionRequest, H256, U256, U64,
@@ -16,9 +16,12 @@ use std::{collections::HashMap, hash::Hash, time};
 pub mod deploy;
 pub mod ens;
 mod error;
+mod macros;
+pub mod multicall;
 pub mod tokens;
 
-pub use crate::contract::error::Error;
+pub(crate) use crate::contract::error::revert_reason;
+pub use crate::contract::error::{decode_revert_reason, Error};
 
 /// Contract `Result` type.
 pub type Result<T> = std::result::Result<T, Error>;
@@ -273,6 +276,9 @@ impl<T: Transport> Contract<T> {
         async {
             let (call_future, function) = result?;
             let bytes = call_future.await?;
+            if let Some(reason) = decode_revert_reason(&bytes.0) {
+                return Err(Error::Revert(reason));
+            }
             let output = function.decode_output(&bytes.0)?;
             R::from_tokens(output)
         }
@@ -323,6 +329,54 @@ impl<T: Transport> Contract<T> {
             })
             .collect::<Result<Vec<R>>>()
     }
+
+    /// Watch for new events of this contract, decoded into named parameters.
+    ///
+    /// A logs filter matching the event and given topics is installed and polled every `poll_interval`.
+    /// Logs of other events are skipped, logs which fail to decode are yielded as errors.
+    pub fn events_stream(
+        &self,
+        event: &str,
+        topics: ethabi::RawTopicFilter,
+        poll_interval: time::Duration,
+    ) -> impl Stream<Item = Result<ethabi::Log>> {
+        let eth_filter = EthFilter::new(self.eth.transport().clone());
+        let address = self.address;
+        let event = self
+            .abi
+            .event(event)
+            .and_then(|ev| Ok((ev.clone(), ev.filter(topics)?)))
+            .map_err(Error::from);
+
+        future::ready(event)
+            .and_then(move |(event, topic_filter)| async move {
+                let filter = FilterBuilder::default()
+                    .address(vec![address])
+                    .topic_filter(topic_filter)
+                    .build();
+                let logs = eth_filter.create_logs_filter(filter).await?;
+                Ok::<_, Error>(
+                    logs.stream(poll_interval)
+                        .map_err(Error::from)
+                        .try_filter_map(move |log| {
+                            let matches = event.anonymous || log.topics.first() == Some(&event.signature());
+                            let decoded = if matches {
+                                event
+                                    .parse_log(ethabi::RawLog {
+                                        topics: log.topics,
+                                        data: log.data.0,
+                                    })
+                                    .map(Some)
+                                    .map_err(Error::from)
+                            } else {
+                                Ok(None)
+                            };
+                            future::ready(decoded)
+                        }),
+                )
+            })
+            .try_flatten_stream()
+    }
 }
 
 #[cfg(feature = "signing")]
@@ -413,7 +467,7 @@ mod contract_signing {
 
 #[cfg(test)]
 mod tests {
-    use super::{Contract, Options};
+    use super::{Contract, Error, Options};
     use crate::{
         api::{self, Namespace},
         rpc,
@@ -519,6 +573,30 @@ mod tests {
         assert_eq!(result, "Hello World!".to_owned());
     }
 
+    #[test]
+    fn should_surface_revert_reason_of_query() {
+        // given
+        let mut transport = TestTransport::default();
+        transport.set_response(rpc::Value::String("0x08c379a00000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000001a4e6f7420656e6f7567682045746865722070726f76696465642e000000000000".into()));
+
+        // when
+        let result: Result<String, _> = {
+            let token = contract(&transport);
+            futures::executor::block_on(token.query("name", (), None, Options::default(), None))
+        };
+
+        // then
+        transport.assert_request(
+            "eth_call",
+            &[
+                "{\"data\":\"0x06fdde03\",\"to\":\"0x0000000000000000000000000000000000000001\"}".into(),
+                "\"latest\"".into(),
+            ],
+        );
+        transport.assert_no_more_requests();
+        assert!(matches!(result, Err(Error::Revert(ref reason)) if reason == "Not enough Ether provided."));
+    }
+
     #[test]
     fn should_call_a_contract_function() {
         // given
@@ -586,4 +664,57 @@ mod tests {
         transport.assert_no_more_requests();
         assert_eq!(result, 0x20.into());
     }
+
+    #[test]
+    fn should_stream_decoded_events() {
+        use futures::StreamExt;
+
+        // given
+        let mut transport = TestTransport::default();
+        let transfer = contract(&transport).abi().event("Transfer").unwrap().signature();
+        let topic = |address: u64| H256::from(Address::from_low_u64_be(address));
+        transport.add_response(rpc::Value::String("0x123".into()));
+        transport.add_response(serde_json::json!([
+            {
+                "address": Address::from_low_u64_be(1),
+                "topics": [transfer, topic(5), topic(6)],
+                "data": "0x0000000000000000000000000000000000000000000000000000000000000020",
+            },
+            {
+                "address": Address::from_low_u64_be(1),
+                "topics": [H256::from_low_u64_be(0xff)],
+                "data": "0x",
+            },
+            {
+                "address": Address::from_low_u64_be(1),
+                "topics": [transfer],
+                "data": "0x",
+            },
+        ]));
+
+        let events = {
+            let token = contract(&transport);
+
+            // when
+            let stream = token.events_stream(
+                "Transfer",
+                ethabi::RawTopicFilter::default(),
+                std::time::Duration::from_secs(0),
+            );
+            futures::executor::block_on(stream.take(2).collect::<Vec<_>>())
+        };
+
+        // then
+        transport.assert_request("eth_newFilter", &["{\"address\":\"0x0000000000000000000000000000000000000001\",\"topics\":[\"0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef\"]}".into()]);
+        transport.assert_request("eth_getFilterChanges", &["\"0x123\"".into()]);
+        transport.assert_no_more_requests();
+        assert_eq!(events.len(), 2);
+        let params = &events[0].as_ref().unwrap().params;
+        assert_eq!(params[0].name, "from");
+        assert_eq!(params[0].value, ethabi::Token::Address(Address::from_low_u64_be(5)));
+        assert_eq!(params[1].value, ethabi::Token::Address(Address::from_low_u64_be(6)));
+        assert_eq!(params[2].name, "value");
+        assert_eq!(params[2].value, ethabi::Token::Uint(0x20.into()));
+        assert!(matches!(events[1], Err(Error::Abi(_))));
+    }
 }
@@ -0,0 +1,164 @@
+//! Batching read-only calls through the Multicall3 contract.
+
+use crate::{
+    api::Eth,
+    contract::{decode_revert_reason, Error, Result},
+    signing,
+    types::{Address, BlockId, Bytes, CallRequest, H160},
+    Transport,
+};
+use ethabi::{ParamType, Token};
+
+/// Address of the Multicall3 contract, deployed at the same address on most EVM chains.
+pub const MULTICALL3_ADDRESS: Address = H160([
+    0xca, 0x11, 0xbd, 0xe0, 0x59, 0x77, 0xb3, 0x63, 0x11, 0x67, 0x02, 0x88, 0x62, 0xbe, 0x2a, 0x17, 0x39, 0x76, 0xca,
+    0x11,
+]);
+
+const TRY_AGGREGATE: &str = "tryAggregate(bool,(address,bytes)[])";
+
+/// Executes given `(target, calldata)` pairs in a single `eth_call` using the Multicall3 contract
+/// at its well-known address.
+///
+/// See `multicall_at` for details.
+pub async fn multicall<T: Transport>(
+    eth: &Eth<T>,
+    calls: Vec<(Address, Bytes)>,
+    block: Option<BlockId>,
+) -> Result<Vec<Result<Bytes>>> {
+    multicall_at(eth, MULTICALL3_ADDRESS, calls, block).await
+}
+
+/// Executes given `(target, calldata)` pairs in a single `eth_call` using the Multicall3 contract at `address`.
+///
+/// Results are returned in the order of `calls`. A reverted call does not fail the whole batch,
+/// it is reported as `Error::Revert` in its slot instead (with an empty reason if the revert carried none).
+pub async fn multicall_at<T: Transport>(
+    eth: &Eth<T>,
+    address: Address,
+    calls: Vec<(Address, Bytes)>,
+    block: Option<BlockId>,
+) -> Result<Vec<Result<Bytes>>> {
+    let expected = calls.len();
+    let req = CallRequest {
+        to: Some(address),
+        data: Some(encode_try_aggregate(calls)),
+        ..Default::default()
+    };
+    let output = eth.call(req, block).await?;
+    let results = decode_try_aggregate(&output.0)?;
+    if results.len() != expected {
+        return Err(Error::InvalidOutputType(format!(
+            "Expected {} multicall results, got {}",
+            expected,
+            results.len()
+        )));
+    }
+    Ok(results)
+}
+
+fn encode_try_aggregate(calls: Vec<(Address, Bytes)>) -> Bytes {
+    let calls = calls
+        .into_iter()
+        .map(|(target, data)| Token::Tuple(vec![Token::Address(target), Token::Bytes(data.0)]))
+        .collect();
+    let mut data = signing::keccak256(TRY_AGGREGATE.as_bytes())[..4].to_vec();
+    data.extend(ethabi::encode(&[Token::Bool(false), Token::Array(calls)]));
+    data.into()
+}
+
+fn decode_try_aggregate(output: &[u8]) -> Result<Vec<Result<Bytes>>> {
+    let result_type = ParamType::Array(Box::new(ParamType::Tuple(vec![ParamType::Bool, ParamType::Bytes])));
+    let results = match ethabi::decode(&[result_type], output)?.pop() {
+        Some(Token::Array(results)) => results,
+        _ => {
+            return Err(Error::InvalidOutputType(
+                "Expected an array of multicall results".into(),
+            ))
+        }
+    };
+    results
+        .into_iter()
+        .map(|result| match result {
+            Token::Tuple(fields) => match fields.as_slice() {
+                [Token::Bool(true), Token::Bytes(data)] => Ok(Ok(data.clone().into())),
+                [Token::Bool(false), Token::Bytes(data)] => {
+                    Ok(Err(Error::Revert(decode_revert_reason(data).unwrap_or_default())))
+                }
+                _ => Err(Error::InvalidOutputType("Malformed multicall result".into())),
+            },
+            _ => Err(Error::InvalidOutputType("Malformed multicall result".into())),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{multicall, MULTICALL3_ADDRESS};
+    use crate::{
+        api::{Eth, Namespace},
+        contract::Error,
+        rpc,
+        transports::test::TestTransport,
+        types::{Address, Bytes},
+    };
+    use ethabi::Token;
+
+    #[test]
+    fn should_aggregate_calls_in_order() {
+        // given
+        let mut revert = vec![0x08, 0xc3, 0x79, 0xa0];
+        revert.extend(ethabi::encode(&[Token::String("nope".into())]));
+        let response = ethabi::encode(&[Token::Array(vec![
+            Token::Tuple(vec![Token::Bool(true), Token::Bytes(vec![1, 2, 3])]),
+            Token::Tuple(vec![Token::Bool(false), Token::Bytes(revert)]),
+        ])]);
+        let mut transport = TestTransport::default();
+        transport.set_response(rpc::Value::String(format!("0x{}", hex::encode(response))));
+        let eth = Eth::new(&transport);
+
+        // when
+        let results = futures::executor::block_on(multicall(
+            &eth,
+            vec![
+                (Address::from_low_u64_be(1), Bytes(vec![0xaa])),
+                (Address::from_low_u64_be(2), Bytes(vec![0xbb])),
+            ],
+            None,
+        ))
+        .unwrap();
+
+        // then
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap(), &Bytes(vec![1, 2, 3]));
+        match results[1] {
+            Err(Error::Revert(ref reason)) => assert_eq!(reason, "nope"),
+            ref other => panic!("Expected revert, got: {:?}", other),
+        }
+        let calls = ethabi::encode(&[
+            Token::Bool(false),
+            Token::Array(vec![
+                Token::Tuple(vec![
+                    Token::Address(Address::from_low_u64_be(1)),
+                    Token::Bytes(vec![0xaa]),
+                ]),
+                Token::Tuple(vec![
+                    Token::Address(Address::from_low_u64_be(2)),
+                    Token::Bytes(vec![0xbb]),
+                ]),
+            ]),
+        ]);
+        transport.assert_request(
+            "eth_call",
+            &[
+                format!(
+                    r#"{{"data":"0xbce38bd7{}","to":"{:?}"}}"#,
+                    hex::encode(calls),
+                    MULTICALL3_ADDRESS
+                ),
+                r#""latest""#.into(),
+            ],
+        );
+        transport.assert_no_more_requests();
+    }
+}
@@ -19,7 +19,8 @@ impl<T: Tokenizable> Detokenize for T {
     fn from_tokens(mut tokens: Vec<Token>) -> Result<Self, Error> {
         if tokens.len() != 1 {
             Err(Error::InvalidOutputType(format!(
-                "Expected single element, got a list: {:?}",
+                "Expected single element, got a list of {}: {:?}",
+                tokens.len(),
                 tokens
             )))
         } else {
@@ -491,6 +492,17 @@ mod tests {
         assert_eq!(data[7][0], 8);
     }
 
+    #[test]
+    fn should_decode_single_output() {
+        let value: U256 = Detokenize::from_tokens(vec![Token::Uint(5.into())]).unwrap();
+        assert_eq!(value, 5.into());
+
+        let too_many: Result<U256, _> = Detokenize::from_tokens(vec![Token::Uint(5.into()), Token::Bool(true)]);
+        let none: Result<U256, _> = Detokenize::from_tokens(vec![]);
+        assert!(too_many.is_err());
+        assert!(none.is_err());
+    }
+
     #[test]
     fn should_decode_array_of_bytes() {
         let token = Token::Array(vec![Token::Uint(Uint::from(0)), Token::Uint(Uint::from(1))]);
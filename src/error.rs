@@ -1,5 +1,9 @@
 //! Web3 Error
-use crate::rpc::error::Error as RPCError;
+use crate::{
+    rpc::error::Error as RPCError,
+    types::{H256, U256},
+    RequestId,
+};
 use derive_more::{Display, From};
 use serde_json::Error as SerdeError;
 use std::io::Error as IoError;
@@ -47,16 +51,104 @@ pub enum Error {
     /// web3 internal error
     #[display(fmt = "Internal Web3 error")]
     Internal,
+    /// operation cancelled by the caller
+    #[display(fmt = "Operation cancelled")]
+    Cancelled,
+    /// the request did not complete in time
+    #[display(fmt = "Request timed out")]
+    Timeout,
+    /// the transaction to send has no sender (`from` is the zero address)
+    #[display(fmt = "Transaction is missing the `from` address")]
+    MissingFrom,
+    /// the transaction was seen pending but then disappeared from the node, e.g. it got replaced or evicted
+    #[display(fmt = "Transaction {:?} was dropped from the mempool", _0)]
+    #[from(ignore)]
+    TransactionDropped(H256),
+    /// the receipt of a transaction expected to be mined is not available, e.g. after a reorg
+    #[display(fmt = "Receipt of transaction {:?} is unavailable", _0)]
+    #[from(ignore)]
+    ReceiptUnavailable(H256),
+    /// the transaction would revert with given reason if it was sent
+    #[display(fmt = "Transaction would revert: {}", _0)]
+    #[from(ignore)]
+    Revert(String),
+    /// the deadline elapsed before the transaction got confirmed
+    #[display(fmt = "Deadline exceeded, last sent transaction: {:?}", last_hash)]
+    #[from(ignore)]
+    DeadlineExceeded {
+        /// hash of the last submitted transaction
+        last_hash: H256,
+    },
+    /// the transaction needs more gas than allowed
+    #[display(fmt = "Transaction needs {} gas, more than the maximum of {}", gas, max_gas)]
+    #[from(ignore)]
+    GasLimitExceeded {
+        /// gas needed by the transaction
+        gas: U256,
+        /// maximum gas allowed
+        max_gas: U256,
+    },
+    /// polling the node failed too many times in a row, see `api::PollBackoff`
+    #[display(fmt = "Node is unhealthy, {} polls failed in a row, last with: {}", failures, error)]
+    #[from(ignore)]
+    NodeUnhealthy {
+        /// number of consecutive failed polls
+        failures: u32,
+        /// the error the last poll failed with
+        error: Box<Error>,
+    },
+    /// the request with given id failed, see `transports::Correlated`
+    #[display(fmt = "Request {} failed: {}", id, error)]
+    #[from(ignore)]
+    Request {
+        /// id assigned to the request by the transport
+        id: RequestId,
+        /// the error the request failed with
+        error: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Returns the id of the failed request, if known.
+    pub fn request_id(&self) -> Option<RequestId> {
+        match *self {
+            Error::Request { id, .. } => Some(id),
+            _ => None,
+        }
+    }
+
+    /// Returns the error the request failed with, unwrapping `Error::Request`.
+    ///
+    /// Use it to inspect the original error of transports wrapped in `Correlated`.
+    pub fn root(&self) -> &Error {
+        match self {
+            Error::Request { error, .. } => error.root(),
+            error => error,
+        }
+    }
 }
 
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         use self::Error::*;
         match *self {
-            Unreachable | Decoder(_) | InvalidResponse(_) | Transport { .. } | Internal => None,
+            Unreachable
+            | Decoder(_)
+            | InvalidResponse(_)
+            | Transport { .. }
+            | Internal
+            | Cancelled
+            | Timeout
+            | MissingFrom
+            | TransactionDropped(_)
+            | ReceiptUnavailable(_)
+            | Revert(_)
+            | DeadlineExceeded { .. }
+            | GasLimitExceeded { .. } => None,
             Rpc(ref e) => Some(e),
             Io(ref e) => Some(e),
             Recovery(ref e) => Some(e),
+            NodeUnhealthy { ref error, .. } | Request { ref error, .. } => Some(&**error),
         }
     }
 }
@@ -79,6 +171,25 @@ impl Clone for Error {
             Io(e) => Io(IoError::from(e.kind())),
             Recovery(e) => Recovery(e.clone()),
             Internal => Internal,
+            Cancelled => Cancelled,
+            Timeout => Timeout,
+            MissingFrom => MissingFrom,
+            TransactionDropped(hash) => TransactionDropped(*hash),
+            ReceiptUnavailable(hash) => ReceiptUnavailable(*hash),
+            Revert(reason) => Revert(reason.clone()),
+            DeadlineExceeded { last_hash } => DeadlineExceeded { last_hash: *last_hash },
+            GasLimitExceeded { gas, max_gas } => GasLimitExceeded {
+                gas: *gas,
+                max_gas: *max_gas,
+            },
+            NodeUnhealthy { failures, error } => NodeUnhealthy {
+                failures: *failures,
+                error: error.clone(),
+            },
+            Request { id, error } => Request {
+                id: *id,
+                error: error.clone(),
+            },
         }
     }
 }
@@ -88,12 +199,21 @@ impl PartialEq for Error {
     fn eq(&self, other: &Self) -> bool {
         use self::Error::*;
         match (self, other) {
-            (Unreachable, Unreachable) | (Internal, Internal) => true,
-            (Decoder(a), Decoder(b)) | (InvalidResponse(a), InvalidResponse(b)) => a == b,
+            (Unreachable, Unreachable)
+            | (Internal, Internal)
+            | (Cancelled, Cancelled)
+            | (Timeout, Timeout)
+            | (MissingFrom, MissingFrom) => true,
+            (Decoder(a), Decoder(b)) | (InvalidResponse(a), InvalidResponse(b)) | (Revert(a), Revert(b)) => a == b,
             (Transport(a), Transport(b)) => a == b,
             (Rpc(a), Rpc(b)) => a == b,
             (Io(a), Io(b)) => a.kind() == b.kind(),
             (Recovery(a), Recovery(b)) => a == b,
+            (TransactionDropped(a), TransactionDropped(b)) | (ReceiptUnavailable(a), ReceiptUnavailable(b)) => a == b,
+            (DeadlineExceeded { last_hash: a }, DeadlineExceeded { last_hash: b }) => a == b,
+            (GasLimitExceeded { gas: a, max_gas: c }, GasLimitExceeded { gas: b, max_gas: d }) => a == b && c == d,
+            (NodeUnhealthy { failures: a, error: e }, NodeUnhealthy { failures: b, error: f }) => a == b && e == f,
+            (Request { id: a, error: e }, Request { id: b, error: f }) => a == b && e == f,
             _ => false,
         }
     }
@@ -48,6 +48,82 @@ where
     }
 }
 
+impl<T, F> CallFuture<T, F>
+where
+    T: serde::de::DeserializeOwned,
+    F: Future<Output = error::Result<rpc::Value>>,
+{
+    /// Maps the decoded result using given function.
+    pub fn map_result<U, M>(self, f: M) -> MapResult<Self, M>
+    where
+        M: FnOnce(T) -> U,
+    {
+        MapResult {
+            inner: self,
+            f: Some(f),
+        }
+    }
+
+    /// Chains a fallible transformation of the decoded result.
+    pub fn and_then_result<U, M>(self, f: M) -> AndThenResult<Self, M>
+    where
+        M: FnOnce(T) -> error::Result<U>,
+    {
+        AndThenResult {
+            inner: self,
+            f: Some(f),
+        }
+    }
+}
+
+/// Maps the successful result of the wrapped future, see `CallFuture::map_result`.
+#[pin_project]
+#[derive(Debug)]
+pub struct MapResult<Fut, M> {
+    #[pin]
+    inner: Fut,
+    f: Option<M>,
+}
+
+impl<T, U, Fut, M> Future for MapResult<Fut, M>
+where
+    Fut: Future<Output = error::Result<T>>,
+    M: FnOnce(T) -> U,
+{
+    type Output = error::Result<U>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        let x = ready!(this.inner.poll(ctx));
+        let f = this.f.take().expect("MapResult polled after completion");
+        Poll::Ready(x.map(f))
+    }
+}
+
+/// Applies a fallible function to the successful result of the wrapped future, see `CallFuture::and_then_result`.
+#[pin_project]
+#[derive(Debug)]
+pub struct AndThenResult<Fut, M> {
+    #[pin]
+    inner: Fut,
+    f: Option<M>,
+}
+
+impl<T, U, Fut, M> Future for AndThenResult<Fut, M>
+where
+    Fut: Future<Output = error::Result<T>>,
+    M: FnOnce(T) -> error::Result<U>,
+{
+    type Output = error::Result<U>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        let x = ready!(this.inner.poll(ctx));
+        let f = this.f.take().expect("AndThenResult polled after completion");
+        Poll::Ready(x.and_then(f))
+    }
+}
+
 /// Serialize a type. Panics if the type is returns error during serialization.
 pub fn serialize<T: serde::Serialize>(t: &T) -> rpc::Value {
     serde_json::to_value(t).expect("Types never fail to serialize.")
@@ -180,4 +256,28 @@ pub mod tests {
       );
     }
   }
+
+    use super::CallFuture;
+    use crate::{error::Error, rpc::Value, types::U256};
+    use futures::future;
+
+    #[test]
+    fn should_map_decoded_result() {
+        let call = CallFuture::<U256, _>::new(future::ready(Ok(Value::String("0x10".into()))));
+        let result = futures::executor::block_on(call.map_result(|value| value.low_u64() * 2));
+        assert_eq!(result, Ok(32));
+    }
+
+    #[test]
+    fn should_chain_fallible_transformation() {
+        let call = CallFuture::<U256, _>::new(future::ready(Ok(Value::String("0x10".into()))));
+        let result = futures::executor::block_on(
+            call.and_then_result(|value| -> Result<u64, Error> { Err(Error::Decoder(format!("{}", value))) }),
+        );
+        assert_eq!(result, Err(Error::Decoder("16".into())));
+
+        let call = CallFuture::<U256, _>::new(future::ready(Err(Error::Unreachable)));
+        let result = futures::executor::block_on(call.and_then_result(|value| Ok(value.low_u64())));
+        assert_eq!(result, Err(Error::Unreachable));
+    }
 }
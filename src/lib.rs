@@ -19,6 +19,8 @@ pub extern crate futures;
 pub use futures::executor::{block_on, block_on_stream};
 
 pub use ethabi;
+/// Re-export of the `rlp` crate, e.g. to encode or decode raw transactions.
+pub use rlp;
 
 // it needs to be before other modules
 // otherwise the macro for tests is not available.
@@ -30,6 +32,7 @@ pub mod confirm;
 pub mod contract;
 pub mod error;
 pub mod signing;
+pub mod time;
 pub mod transports;
 pub mod types;
 
@@ -59,6 +62,24 @@ pub trait Transport: std::fmt::Debug + Clone {
         let (id, request) = self.prepare(method, params);
         self.send(id, request)
     }
+
+    /// Erase the concrete type of this transport.
+    fn boxed(self) -> transports::BoxedTransport
+    where
+        Self: Sized + Send + Sync + 'static,
+        Self::Out: Send + 'static,
+    {
+        transports::BoxedTransport::new(self)
+    }
+
+    /// Wrap this transport with the given layer.
+    fn layer<L>(self, layer: L) -> L::Transport
+    where
+        Self: Sized,
+        L: transports::TransportLayer<Self>,
+    {
+        layer.layer(self)
+    }
 }
 
 /// A transport implementation supporting batch requests.
@@ -82,6 +103,15 @@ pub trait DuplexTransport: Transport {
 
     /// Remove a subscription from this transport
     fn unsubscribe(&self, id: api::SubscriptionId) -> error::Result<()>;
+
+    /// Send a request without waiting for its response.
+    ///
+    /// Duplex transports write requests as soon as they are sent rather than when the response is polled,
+    /// so by default the response future is just dropped.
+    fn send_and_forget(&self, method: &str, params: Vec<rpc::Value>) {
+        let (id, request) = self.prepare(method, params);
+        drop(self.send(id, request));
+    }
 }
 
 impl<X, T> Transport for X
@@ -148,6 +148,30 @@ mod feature_gated {
         Ok(public_key_address(&public_key))
     }
 
+    /// Sign a message according to EIP-191, i.e. the way `personal_sign` does on the node.
+    ///
+    /// The message is prefixed and hashed with `hash_message`, the V-value of the signature is in 'Electrum'
+    /// notation.
+    pub fn sign_message(message: &[u8], key: impl Key) -> Signature {
+        key.sign(hash_message(message).as_bytes(), None)
+            .expect("hash is non-zero 32-bytes; qed")
+    }
+
+    /// Recover the signer of an EIP-191 message, e.g. one signed with `sign_message`.
+    ///
+    /// The V-value of the signature is accepted both in 'Electrum' notation (27 or 28) and as a raw recovery id.
+    pub fn recover_signer(message: &[u8], signature: &Signature) -> Result<Address, RecoveryError> {
+        let recovery_id = match signature.v {
+            0 | 1 => signature.v,
+            27 | 28 => signature.v - 27,
+            _ => return Err(RecoveryError::InvalidSignature),
+        };
+        let mut compact = [0u8; 64];
+        compact[..32].copy_from_slice(signature.r.as_bytes());
+        compact[32..].copy_from_slice(signature.s.as_bytes());
+        recover(hash_message(message).as_bytes(), &compact, recovery_id as i32)
+    }
+
     /// Gets the address of a public key.
     ///
     /// The public address is defined as the low 20 bytes of the keccak hash of
@@ -279,4 +303,37 @@ mod tests {
 
         assert_eq!(expected, result);
     }
+
+    #[test]
+    #[cfg(feature = "signing")]
+    fn sign_message_and_recover_signer() {
+        use secp256k1::SecretKey;
+
+        // test vector taken from:
+        // https://web3js.readthedocs.io/en/v1.2.2/web3-eth-accounts.html#sign
+        let key = SecretKey::from_slice(&hex_literal::hex!(
+            "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+        ))
+        .unwrap();
+
+        let signature = sign_message(b"Some data", SecretKeyRef::new(&key));
+
+        assert_eq!(signature.v, 28);
+        assert_eq!(
+            signature.r,
+            hex_literal::hex!("b91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd").into()
+        );
+        assert_eq!(
+            signature.s,
+            hex_literal::hex!("6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a029").into()
+        );
+        assert_eq!(
+            recover_signer(b"Some data", &signature),
+            Ok(hex_literal::hex!("2c7536E3605D9C16a7a3D7b1898e529396a65c23").into())
+        );
+        assert_ne!(
+            recover_signer(b"Other data", &signature),
+            Ok(hex_literal::hex!("2c7536E3605D9C16a7a3D7b1898e529396a65c23").into())
+        );
+    }
 }
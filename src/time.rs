@@ -0,0 +1,70 @@
+//! Measurement of time working on all supported targets.
+//!
+//! `std::time::Instant::now` panics on `wasm32-unknown-unknown`, with the `wasm` feature the time is read from
+//! JavaScript's `Date.now()` there instead.
+
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm")))]
+pub use std::time::Instant;
+
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+pub use self::wasm::Instant;
+
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+mod wasm {
+    use std::{
+        ops::{Add, Sub},
+        time::Duration,
+    };
+
+    /// A measurement of the system clock, mirroring the API of `std::time::Instant`.
+    ///
+    /// Unlike the standard one it is not guaranteed to be monotonic, durations between instants saturate at zero.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct Instant(Duration);
+
+    impl Instant {
+        /// Returns the current instant.
+        pub fn now() -> Self {
+            Instant(Duration::from_secs_f64(js_sys::Date::now().max(0.0) / 1000.0))
+        }
+
+        /// Returns the amount of time elapsed since `earlier`, or zero if it's later than this one.
+        pub fn duration_since(&self, earlier: Instant) -> Duration {
+            self.saturating_duration_since(earlier)
+        }
+
+        /// Returns the amount of time elapsed since `earlier`, or zero if it's later than this one.
+        pub fn saturating_duration_since(&self, earlier: Instant) -> Duration {
+            self.0.checked_sub(earlier.0).unwrap_or_default()
+        }
+
+        /// Returns the amount of time elapsed since this instant.
+        pub fn elapsed(&self) -> Duration {
+            Instant::now().saturating_duration_since(*self)
+        }
+    }
+
+    impl Add<Duration> for Instant {
+        type Output = Instant;
+
+        fn add(self, duration: Duration) -> Instant {
+            Instant(self.0 + duration)
+        }
+    }
+
+    impl Sub<Duration> for Instant {
+        type Output = Instant;
+
+        fn sub(self, duration: Duration) -> Instant {
+            Instant(self.0.checked_sub(duration).unwrap_or_default())
+        }
+    }
+
+    impl Sub<Instant> for Instant {
+        type Output = Duration;
+
+        fn sub(self, earlier: Instant) -> Duration {
+            self.saturating_duration_since(earlier)
+        }
+    }
+}
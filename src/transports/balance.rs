@@ -0,0 +1,330 @@
+//! Transport balancing requests across multiple endpoints.
+
+use crate::{api::Web3, error, rpc, time::Instant, types::NodeHealth, BatchTransport, Error, RequestId, Transport};
+use futures::{
+    future::join_all,
+    task::{Context, Poll},
+    Future,
+};
+use parking_lot::Mutex;
+use pin_project::pin_project;
+use std::{
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// A transport spreading calls (and batches) across multiple endpoints in weighted round-robin order.
+///
+/// An endpoint failing at transport level (unreachable, timed out, IO or transport error) is considered unhealthy
+/// and skipped for a cooldown period, unless all endpoints are unhealthy. RPC errors returned by a healthy node
+/// don't affect its health. The failed call itself is not retried.
+#[derive(Debug, Clone)]
+pub struct RoundRobin<T> {
+    transports: Arc<Vec<T>>,
+    state: Arc<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    schedule: Vec<usize>,
+    next: AtomicUsize,
+    cooldown: Duration,
+    unhealthy_until: Mutex<Vec<Option<Instant>>>,
+}
+
+impl State {
+    fn new(weights: &[usize], cooldown: Duration) -> Self {
+        let schedule = weights
+            .iter()
+            .enumerate()
+            .flat_map(|(index, weight)| std::iter::repeat_n(index, *weight))
+            .collect::<Vec<_>>();
+        assert!(
+            !schedule.is_empty(),
+            "At least one endpoint with positive weight is required."
+        );
+        State {
+            schedule,
+            next: AtomicUsize::new(0),
+            cooldown,
+            unhealthy_until: Mutex::new(vec![None; weights.len()]),
+        }
+    }
+
+    fn select(&self) -> usize {
+        let now = Instant::now();
+        let unhealthy_until = self.unhealthy_until.lock();
+        let first = self.schedule[self.next.fetch_add(1, Ordering::SeqCst) % self.schedule.len()];
+        if !is_unhealthy(&unhealthy_until, first, now) {
+            return first;
+        }
+        for _ in 1..self.schedule.len() {
+            let index = self.schedule[self.next.fetch_add(1, Ordering::SeqCst) % self.schedule.len()];
+            if !is_unhealthy(&unhealthy_until, index, now) {
+                return index;
+            }
+        }
+        first
+    }
+
+    fn fail(&self, index: usize) {
+        log::warn!("Endpoint {} failed, skipping it for {:?}", index, self.cooldown);
+        self.unhealthy_until.lock()[index] = Some(Instant::now() + self.cooldown);
+    }
+
+    fn succeed(&self, index: usize) {
+        self.unhealthy_until.lock()[index] = None;
+    }
+}
+
+fn is_unhealthy(unhealthy_until: &[Option<Instant>], index: usize, now: Instant) -> bool {
+    matches!(unhealthy_until[index], Some(until) if until > now)
+}
+
+fn is_endpoint_failure(err: &Error) -> bool {
+    matches!(
+        err.root(),
+        Error::Unreachable | Error::Timeout | Error::Transport(_) | Error::Io(_)
+    )
+}
+
+impl<T: Transport> RoundRobin<T> {
+    /// Creates a new transport using given endpoints with equal weights.
+    ///
+    /// Panics if `transports` is empty.
+    pub fn new(transports: Vec<T>) -> Self {
+        Self::with_weights(transports.into_iter().map(|transport| (transport, 1)).collect())
+    }
+
+    /// Creates a new transport using given endpoints, each receiving a share of calls proportional to its weight.
+    ///
+    /// Panics if no endpoint has a positive weight.
+    pub fn with_weights(transports: Vec<(T, usize)>) -> Self {
+        let (transports, weights): (Vec<_>, Vec<_>) = transports.into_iter().unzip();
+        RoundRobin {
+            state: Arc::new(State::new(&weights, Duration::from_secs(30))),
+            transports: Arc::new(transports),
+        }
+    }
+
+    /// Skip a failed endpoint for `cooldown` (30 seconds by default).
+    pub fn with_cooldown(self, cooldown: Duration) -> Self {
+        let state = State {
+            schedule: self.state.schedule.clone(),
+            next: AtomicUsize::new(self.state.next.load(Ordering::SeqCst)),
+            cooldown,
+            unhealthy_until: Mutex::new(self.state.unhealthy_until.lock().clone()),
+        };
+        RoundRobin {
+            transports: self.transports,
+            state: Arc::new(state),
+        }
+    }
+
+    /// Returns whether the endpoint with given index is currently considered healthy.
+    pub fn is_healthy(&self, index: usize) -> bool {
+        !is_unhealthy(&self.state.unhealthy_until.lock(), index, Instant::now())
+    }
+
+    /// Probes all endpoints with `Web3::node_status`, skipping the ones which fail or aren't synced for the cooldown
+    /// period and restoring the others.
+    ///
+    /// Meant to be called periodically, returns the status of every endpoint.
+    pub async fn check_health(&self) -> Vec<error::Result<NodeHealth>> {
+        let statuses = join_all(
+            self.transports
+                .iter()
+                .map(|transport| async move { Web3::new(transport).node_status().await }),
+        )
+        .await;
+        for (index, status) in statuses.iter().enumerate() {
+            match status {
+                Ok(health) if health.is_synced() => self.state.succeed(index),
+                _ => self.state.fail(index),
+            }
+        }
+        statuses
+    }
+}
+
+impl<T: Transport> Transport for RoundRobin<T> {
+    type Out = BalancedResponse<T::Out>;
+
+    fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+        // Ids are always generated by the first endpoint, so that they are unique across all of them.
+        self.transports[0].prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: rpc::Call) -> Self::Out {
+        let index = self.state.select();
+        BalancedResponse {
+            inner: self.transports[index].send(id, request),
+            index,
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<T: BatchTransport> BatchTransport for RoundRobin<T> {
+    type Batch = BalancedResponse<T::Batch>;
+
+    fn send_batch<I>(&self, requests: I) -> Self::Batch
+    where
+        I: IntoIterator<Item = (RequestId, rpc::Call)>,
+    {
+        let index = self.state.select();
+        BalancedResponse {
+            inner: self.transports[index].send_batch(requests),
+            index,
+            state: self.state.clone(),
+        }
+    }
+}
+
+/// Response of `RoundRobin`, tracking the health of the endpoint it was sent to.
+#[pin_project]
+#[derive(Debug)]
+pub struct BalancedResponse<F> {
+    #[pin]
+    inner: F,
+    index: usize,
+    state: Arc<State>,
+}
+
+impl<F, O> Future for BalancedResponse<F>
+where
+    F: Future<Output = error::Result<O>>,
+{
+    type Output = error::Result<O>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        let result = ready!(this.inner.poll(ctx));
+        match result {
+            Err(ref err) if is_endpoint_failure(err) => this.state.fail(*this.index),
+            _ => this.state.succeed(*this.index),
+        }
+        Poll::Ready(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RoundRobin;
+    use crate::{
+        rpc::Value,
+        transports::{test::TestTransport, Correlated},
+        Error, Transport,
+    };
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn endpoint(responses: &[&str]) -> TestTransport {
+        let mut transport = TestTransport::default();
+        for response in responses {
+            transport.add_response(Value::String(response.to_string()));
+        }
+        transport
+    }
+
+    fn execute(transport: &RoundRobin<TestTransport>) -> crate::error::Result<Value> {
+        futures::executor::block_on(transport.execute("eth_test", vec![]))
+    }
+
+    #[test]
+    fn should_spread_calls_across_endpoints() {
+        // given
+        let transport = RoundRobin::new(vec![endpoint(&["a1", "a2"]), endpoint(&["b1", "b2"])]);
+
+        // when
+        let results = (0..4).map(|_| execute(&transport)).collect::<Vec<_>>();
+
+        // then
+        let expected = ["a1", "b1", "a2", "b2"]
+            .iter()
+            .map(|value| Ok(Value::String(value.to_string())))
+            .collect::<Vec<_>>();
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn should_respect_weights() {
+        // given
+        let transport = RoundRobin::with_weights(vec![(endpoint(&["a1", "a2"]), 2), (endpoint(&["b1"]), 1)]);
+
+        // when
+        let results = (0..3).map(|_| execute(&transport)).collect::<Vec<_>>();
+
+        // then
+        let expected = ["a1", "a2", "b1"]
+            .iter()
+            .map(|value| Ok(Value::String(value.to_string())))
+            .collect::<Vec<_>>();
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn should_skip_unhealthy_endpoint() {
+        // given
+        let transport =
+            RoundRobin::new(vec![endpoint(&[]), endpoint(&["b1", "b2"])]).with_cooldown(Duration::from_secs(60));
+
+        // when
+        let first = execute(&transport);
+        let second = execute(&transport);
+        let third = execute(&transport);
+
+        // then
+        assert_eq!(first, Err(Error::Unreachable));
+        assert!(!transport.is_healthy(0));
+        assert_eq!(second, Ok(Value::String("b1".into())));
+        assert_eq!(third, Ok(Value::String("b2".into())));
+    }
+
+    #[test]
+    fn should_skip_unhealthy_endpoint_failing_with_request_id() {
+        // given
+        let transport = RoundRobin::new(vec![Correlated::new(endpoint(&[])), Correlated::new(endpoint(&["b1"]))]);
+
+        // when
+        let first = futures::executor::block_on(transport.execute("eth_test", vec![]));
+        let second = futures::executor::block_on(transport.execute("eth_test", vec![]));
+
+        // then
+        assert_eq!(first.unwrap_err().root(), &Error::Unreachable);
+        assert!(!transport.is_healthy(0));
+        assert_eq!(second, Ok(Value::String("b1".into())));
+    }
+
+    #[test]
+    fn should_skip_endpoints_failing_health_check() {
+        // given
+        let mut syncing = TestTransport::default();
+        syncing.add_response(json!("1"));
+        syncing.add_response(json!({ "startingBlock": "0x0", "currentBlock": "0x1", "highestBlock": "0x2" }));
+        syncing.add_response(json!("0x1"));
+        let mut synced = TestTransport::default();
+        synced.add_response(json!("1"));
+        synced.add_response(json!(false));
+        synced.add_response(json!("0x2"));
+        let transport = RoundRobin::new(vec![TestTransport::default(), syncing, synced.clone()]);
+
+        // when
+        let statuses = futures::executor::block_on(transport.check_health());
+        synced.add_response(Value::String("c1".into()));
+        let result = execute(&transport);
+
+        // then
+        assert_eq!(statuses[0], Err(Error::Unreachable));
+        assert!(!statuses[1].as_ref().unwrap().is_synced());
+        assert!(statuses[2].as_ref().unwrap().is_synced());
+        assert!(!transport.is_healthy(0));
+        assert!(!transport.is_healthy(1));
+        assert!(transport.is_healthy(2));
+        assert_eq!(result, Ok(Value::String("c1".into())));
+    }
+}
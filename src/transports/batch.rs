@@ -2,25 +2,34 @@
 
 use crate::{
     error::{self, Error},
-    rpc, BatchTransport, RequestId, Transport,
+    rpc,
+    time::Instant,
+    BatchTransport, RequestId, Transport,
 };
 use futures::{
     channel::oneshot,
+    future::{self, BoxFuture, Shared},
     task::{Context, Poll},
     Future, FutureExt,
 };
+use futures_timer::Delay;
 use parking_lot::Mutex;
-use std::{collections::BTreeMap, pin::Pin, sync::Arc};
+use std::{collections::BTreeMap, fmt, pin::Pin, sync::Arc, time::Duration};
 
 type Pending = oneshot::Sender<error::Result<rpc::Value>>;
 type PendingRequests = Arc<Mutex<BTreeMap<RequestId, Pending>>>;
+type Flush = Shared<BoxFuture<'static, ()>>;
 
 /// Transport allowing to batch queries together.
+///
+/// By default queued requests are sent only when calling `submit_batch`. With `with_auto_flush` they are sent
+/// automatically, which makes batching transparent to code just awaiting the results.
 #[derive(Debug, Clone)]
 pub struct Batch<T> {
     transport: T,
     pending: PendingRequests,
     batch: Arc<Mutex<Vec<(RequestId, rpc::Call)>>>,
+    auto_flush: Option<Arc<AutoFlush>>,
 }
 
 impl<T> Batch<T>
@@ -33,6 +42,38 @@ where
             transport,
             pending: Default::default(),
             batch: Default::default(),
+            auto_flush: None,
+        }
+    }
+
+    /// Send queued requests automatically as soon as `max_size` requests are queued or `max_delay` passed since
+    /// the first of them was queued.
+    ///
+    /// The batch is sent by whichever of its results is polled, so results need to be awaited to make progress.
+    /// Panics if `max_size` is zero.
+    pub fn with_auto_flush(self, max_size: usize, max_delay: Duration) -> Self
+    where
+        T: Send + Sync + 'static,
+        T::Batch: Send + 'static,
+    {
+        assert!(max_size > 0, "Batches need to contain at least one request.");
+        let batch = Batch {
+            auto_flush: None,
+            ..self.clone()
+        };
+        Batch {
+            auto_flush: Some(Arc::new(AutoFlush {
+                max_size,
+                max_delay,
+                window: Mutex::new(Arc::new(Window::new())),
+                submit: Box::new(move || {
+                    if batch.batch.lock().is_empty() {
+                        return future::ready(()).boxed().shared();
+                    }
+                    batch.submit_batch().map(|_| ()).boxed().shared()
+                }),
+            })),
+            ..self
         }
     }
 
@@ -75,20 +116,185 @@ where
     fn send(&self, id: RequestId, request: rpc::Call) -> Self::Out {
         let (tx, rx) = oneshot::channel();
         self.pending.lock().insert(id, tx);
-        self.batch.lock().push((id, request));
 
-        SingleResult(rx)
+        let auto_flush = match self.auto_flush {
+            Some(ref auto_flush) => auto_flush,
+            None => {
+                self.batch.lock().push((id, request));
+                return SingleResult { rx, flushing: None };
+            }
+        };
+
+        // The window is locked first so that the request can't end up in a batch flushed concurrently.
+        let mut current = auto_flush.window.lock();
+        let full = {
+            let mut batch = self.batch.lock();
+            if batch.is_empty() {
+                // The delay counts from the first request of the batch rather than from the previous flush.
+                *current = Arc::new(Window::new());
+            }
+            batch.push((id, request));
+            batch.len() >= auto_flush.max_size
+        };
+        let window = current.clone();
+        if full {
+            auto_flush.flush(&mut current);
+        }
+        let deadline = window.opened + auto_flush.max_delay;
+        SingleResult {
+            rx,
+            flushing: Some(Flushing {
+                auto_flush: auto_flush.clone(),
+                window,
+                delay: Delay::new(deadline.saturating_duration_since(Instant::now())),
+                flush: None,
+            }),
+        }
+    }
+}
+
+// Requests of the next batch, opened when the first of them is queued.
+struct Window {
+    opened: Instant,
+    flushed: Mutex<Option<Flush>>,
+}
+
+impl Window {
+    fn new() -> Self {
+        Window {
+            opened: Instant::now(),
+            flushed: Mutex::new(None),
+        }
+    }
+}
+
+struct AutoFlush {
+    max_size: usize,
+    max_delay: Duration,
+    window: Mutex<Arc<Window>>,
+    submit: Box<dyn Fn() -> Flush + Send + Sync>,
+}
+
+impl fmt::Debug for AutoFlush {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AutoFlush")
+            .field("max_size", &self.max_size)
+            .field("max_delay", &self.max_delay)
+            .finish()
+    }
+}
+
+impl AutoFlush {
+    // Submits the requests of the `current` window and opens a new one.
+    fn flush(&self, current: &mut Arc<Window>) {
+        let window = std::mem::replace(current, Arc::new(Window::new()));
+        *window.flushed.lock() = Some((self.submit)());
+    }
+
+    fn flush_expired(&self, window: &Arc<Window>) {
+        let mut current = self.window.lock();
+        if Arc::ptr_eq(&current, window) {
+            self.flush(&mut current);
+        }
+    }
+}
+
+struct Flushing {
+    auto_flush: Arc<AutoFlush>,
+    window: Arc<Window>,
+    delay: Delay,
+    flush: Option<Flush>,
+}
+
+impl Flushing {
+    // Drives the batch containing the request, flushing it once the delay passes.
+    fn poll(&mut self, ctx: &mut Context) {
+        if self.flush.is_none() {
+            if self.window.flushed.lock().is_none() && self.delay.poll_unpin(ctx).is_pending() {
+                return;
+            }
+            self.auto_flush.flush_expired(&self.window);
+            self.flush = self.window.flushed.lock().clone();
+        }
+        if let Some(ref mut flush) = self.flush {
+            let _ = flush.poll_unpin(ctx);
+        }
     }
 }
 
 /// Result of calling a single method that will be part of the batch.
 /// Converts `oneshot::Receiver` error into `Error::Internal`
-pub struct SingleResult(oneshot::Receiver<error::Result<rpc::Value>>);
+pub struct SingleResult {
+    rx: oneshot::Receiver<error::Result<rpc::Value>>,
+    flushing: Option<Flushing>,
+}
 
 impl Future for SingleResult {
     type Output = error::Result<rpc::Value>;
 
     fn poll(mut self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
-        Poll::Ready(ready!(self.0.poll_unpin(ctx)).map_err(|_| Error::Internal)?)
+        if let Some(ref mut flushing) = self.flushing {
+            flushing.poll(ctx);
+        }
+        Poll::Ready(ready!(self.rx.poll_unpin(ctx)).map_err(|_| Error::Internal)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Batch;
+    use crate::{rpc, transports::test::EchoTransport, Transport};
+    use futures::future;
+    use std::time::Duration;
+
+    #[test]
+    fn should_flush_when_batch_is_full() {
+        // given
+        let inner = EchoTransport::default();
+        let transport = Batch::new(inner.clone()).with_auto_flush(2, Duration::from_secs(3600));
+
+        // when
+        let (first, second) = futures::executor::block_on(future::join(
+            transport.execute("eth_blockNumber", vec![]),
+            transport.execute("eth_chainId", vec![]),
+        ));
+
+        // then
+        assert_eq!(first, Ok(rpc::Value::String("eth_blockNumber".into())));
+        assert_eq!(second, Ok(rpc::Value::String("eth_chainId".into())));
+        assert_eq!(inner.batches(), vec![2]);
+    }
+
+    #[test]
+    fn should_flush_after_delay() {
+        // given
+        let inner = EchoTransport::default();
+        let transport = Batch::new(inner.clone()).with_auto_flush(10, Duration::from_millis(10));
+
+        // when
+        let result = futures::executor::block_on(transport.execute("eth_blockNumber", vec![]));
+
+        // then
+        assert_eq!(result, Ok(rpc::Value::String("eth_blockNumber".into())));
+        assert_eq!(inner.batches(), vec![1]);
+    }
+
+    #[test]
+    fn should_count_delay_from_first_queued_request() {
+        // given
+        let inner = EchoTransport::default();
+        let transport = Batch::new(inner.clone()).with_auto_flush(10, Duration::from_millis(50));
+        std::thread::sleep(Duration::from_millis(100));
+
+        // when
+        let (first, second) = futures::executor::block_on(future::join(
+            transport.execute("eth_blockNumber", vec![]),
+            transport.execute("eth_chainId", vec![]),
+        ));
+
+        // then
+        assert_eq!(first, Ok(rpc::Value::String("eth_blockNumber".into())));
+        assert_eq!(second, Ok(rpc::Value::String("eth_chainId".into())));
+        assert_eq!(inner.batches(), vec![2]);
     }
 }
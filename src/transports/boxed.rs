@@ -0,0 +1,165 @@
+//! Type-erased transport.
+
+use crate::{error, rpc, BatchTransport, RequestId, Transport};
+use futures::future::{self, BoxFuture, FutureExt};
+use std::{fmt, sync::Arc};
+
+type BatchResult = error::Result<Vec<error::Result<rpc::Value>>>;
+
+/// Object-safe subset of `BatchTransport` used for type erasure.
+trait DynTransport: fmt::Debug + Send + Sync {
+    fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call);
+
+    fn send(&self, id: RequestId, request: rpc::Call) -> BoxFuture<'static, error::Result<rpc::Value>>;
+
+    fn send_batch(&self, requests: Vec<(RequestId, rpc::Call)>) -> BoxFuture<'static, BatchResult>;
+}
+
+/// Transport without batch support, sending batched requests one by one.
+#[derive(Debug)]
+struct Single<T>(T);
+
+impl<T> DynTransport for Single<T>
+where
+    T: Transport + Send + Sync,
+    T::Out: Send + 'static,
+{
+    fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+        self.0.prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: rpc::Call) -> BoxFuture<'static, error::Result<rpc::Value>> {
+        self.0.send(id, request).boxed()
+    }
+
+    fn send_batch(&self, requests: Vec<(RequestId, rpc::Call)>) -> BoxFuture<'static, BatchResult> {
+        let responses = requests
+            .into_iter()
+            .map(|(id, request)| self.0.send(id, request))
+            .collect::<Vec<_>>();
+        future::join_all(responses).map(Ok).boxed()
+    }
+}
+
+/// Transport with native batch support.
+#[derive(Debug)]
+struct Batched<T>(T);
+
+impl<T> DynTransport for Batched<T>
+where
+    T: BatchTransport + Send + Sync,
+    T::Out: Send + 'static,
+    T::Batch: Send + 'static,
+{
+    fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+        self.0.prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: rpc::Call) -> BoxFuture<'static, error::Result<rpc::Value>> {
+        self.0.send(id, request).boxed()
+    }
+
+    fn send_batch(&self, requests: Vec<(RequestId, rpc::Call)>) -> BoxFuture<'static, BatchResult> {
+        self.0.send_batch(requests).boxed()
+    }
+}
+
+/// A transport with its concrete type erased.
+///
+/// Allows storing different transports behind a single type without
+/// making the containing code generic, e.g. to pick HTTP, WebSocket or IPC at runtime from configuration.
+/// Use `Transport::boxed` to create one.
+///
+/// Batches are sent natively by transports boxed with `BoxedTransport::new_batch`, and as separate
+/// requests otherwise.
+#[derive(Debug, Clone)]
+pub struct BoxedTransport(Arc<dyn DynTransport>);
+
+impl BoxedTransport {
+    /// Wraps given transport.
+    pub fn new<T>(transport: T) -> Self
+    where
+        T: Transport + Send + Sync + 'static,
+        T::Out: Send + 'static,
+    {
+        BoxedTransport(Arc::new(Single(transport)))
+    }
+
+    /// Wraps given transport, preserving its support for batches.
+    pub fn new_batch<T>(transport: T) -> Self
+    where
+        T: BatchTransport + Send + Sync + 'static,
+        T::Out: Send + 'static,
+        T::Batch: Send + 'static,
+    {
+        BoxedTransport(Arc::new(Batched(transport)))
+    }
+}
+
+impl Transport for BoxedTransport {
+    type Out = BoxFuture<'static, error::Result<rpc::Value>>;
+
+    fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+        self.0.prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: rpc::Call) -> Self::Out {
+        self.0.send(id, request)
+    }
+}
+
+impl BatchTransport for BoxedTransport {
+    type Batch = BoxFuture<'static, BatchResult>;
+
+    fn send_batch<T>(&self, requests: T) -> Self::Batch
+    where
+        T: IntoIterator<Item = (RequestId, rpc::Call)>,
+    {
+        self.0.send_batch(requests.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoxedTransport;
+    use crate::{rpc, transports::test::EchoTransport, BatchTransport, Transport};
+
+    #[test]
+    fn should_forward_calls_to_inner_transport() {
+        let transports: Vec<BoxedTransport> = vec![
+            EchoTransport::default().boxed(),
+            BoxedTransport::new(EchoTransport::default()),
+        ];
+
+        for transport in transports {
+            let response = futures::executor::block_on(transport.execute("eth_accounts", vec![]));
+            assert_eq!(response, Ok(rpc::Value::String("eth_accounts".into())));
+        }
+    }
+
+    #[test]
+    fn should_send_batches_natively_or_one_by_one() {
+        // given
+        let inner = EchoTransport::default();
+        let single = inner.clone().boxed();
+        let batched = BoxedTransport::new_batch(inner.clone());
+        let requests = || {
+            vec![
+                single.prepare("eth_accounts", vec![]),
+                single.prepare("eth_chainId", vec![]),
+            ]
+        };
+        let expected = Ok(vec![Ok("eth_accounts".into()), Ok("eth_chainId".into())]);
+
+        // when
+        let one_by_one = futures::executor::block_on(single.send_batch(requests()));
+        let sent_one_by_one = inner.batches();
+        let native = futures::executor::block_on(batched.send_batch(requests()));
+
+        // then
+        assert_eq!(one_by_one, expected);
+        assert_eq!(sent_one_by_one, Vec::<usize>::new());
+        assert_eq!(native, expected);
+        assert_eq!(inner.batches(), vec![2]);
+    }
+}
@@ -0,0 +1,382 @@
+//! Transport caching `eth_blockNumber` responses and responses of immutable queries.
+
+use crate::{error, rpc, time::Instant, RequestId, Transport};
+use futures::{
+    task::{Context, Poll},
+    Future,
+};
+use parking_lot::Mutex;
+use pin_project::pin_project;
+use std::{
+    collections::{HashMap, VecDeque},
+    pin::Pin,
+    sync::Arc,
+    time::Duration,
+};
+
+/// A transport answering `eth_blockNumber` from a cache for a short time after the last response.
+///
+/// Rapid-fire block number reads (e.g. in polling loops) are coalesced, which saves requests on providers
+/// charging per call. Note that the returned block number may be stale by up to the configured TTL,
+/// which delays logic relying on it (like confirmations) accordingly. The cache is dropped whenever
+/// `eth_getFilterChanges` reports new items, so a new block observed through a filter is never
+/// followed by a cached block number.
+///
+/// With `with_immutable_queries` responses of queries which never change are memoized as well, see there.
+/// All other calls are passed through unchanged.
+#[derive(Debug, Clone)]
+pub struct CachingTransport<T> {
+    inner: T,
+    cache: Arc<Cache>,
+}
+
+/// Methods whose responses never change, either globally or because the block hash they take pins the block.
+///
+/// Lookups by transaction hash are not included, a reorg can move a transaction to another block.
+const IMMUTABLE_METHODS: &[&str] = &[
+    "eth_chainId",
+    "eth_getBlockByHash",
+    "eth_getBlockTransactionCountByHash",
+    "eth_getTransactionByBlockHashAndIndex",
+    "eth_getUncleByBlockHashAndIndex",
+];
+
+#[derive(Debug)]
+struct Cache {
+    ttl: Duration,
+    block_number: Mutex<Option<(Instant, rpc::Value)>>,
+    responses: Option<Responses>,
+}
+
+impl Cache {
+    fn get(&self) -> Option<rpc::Value> {
+        match *self.block_number.lock() {
+            Some((at, ref block_number)) if at.elapsed() < self.ttl => Some(block_number.clone()),
+            _ => None,
+        }
+    }
+
+    fn set(&self, block_number: rpc::Value) {
+        *self.block_number.lock() = Some((Instant::now(), block_number));
+    }
+
+    fn invalidate(&self) {
+        *self.block_number.lock() = None;
+    }
+}
+
+/// Responses of immutable queries, evicted in insertion order once over capacity.
+#[derive(Debug)]
+struct Responses {
+    capacity: usize,
+    state: Mutex<ResponsesState>,
+}
+
+#[derive(Debug, Default)]
+struct ResponsesState {
+    values: HashMap<String, rpc::Value>,
+    // Keys in insertion order, for eviction.
+    order: VecDeque<String>,
+}
+
+impl Responses {
+    fn get(&self, key: &str) -> Option<rpc::Value> {
+        self.state.lock().values.get(key).cloned()
+    }
+
+    fn set(&self, key: String, value: rpc::Value) {
+        let mut state = self.state.lock();
+        if state.values.insert(key.clone(), value).is_some() {
+            state.order.retain(|queued| *queued != key);
+        }
+        state.order.push_back(key);
+        while state.order.len() > self.capacity {
+            if let Some(oldest) = state.order.pop_front() {
+                state.values.remove(&oldest);
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.state.lock().values.len()
+    }
+}
+
+impl<T: Transport> CachingTransport<T> {
+    /// Wraps the `inner` transport, caching block numbers for `ttl`.
+    pub fn new(inner: T, ttl: Duration) -> Self {
+        CachingTransport {
+            inner,
+            cache: Arc::new(Cache {
+                ttl,
+                block_number: Mutex::new(None),
+                responses: None,
+            }),
+        }
+    }
+
+    /// Memoize up to `capacity` responses of queries which never change, evicting the oldest ones first.
+    ///
+    /// These are `eth_chainId` and the lookups by block hash, like `eth_getBlockByHash`. A block hash pins the
+    /// contents of the block, so unlike lookups by transaction hash the responses stay valid across reorgs. Empty
+    /// responses (e.g. of a block the node doesn't know yet) are never cached.
+    pub fn with_immutable_queries(self, capacity: usize) -> Self {
+        CachingTransport {
+            inner: self.inner,
+            cache: Arc::new(Cache {
+                ttl: self.cache.ttl,
+                block_number: Mutex::new(self.cache.block_number.lock().clone()),
+                responses: Some(Responses {
+                    capacity,
+                    state: Default::default(),
+                }),
+            }),
+        }
+    }
+
+    /// Drops the cached block number, so that the next read is sent to the node.
+    pub fn invalidate(&self) {
+        self.cache.invalidate();
+    }
+
+    /// Returns the number of memoized responses of immutable queries.
+    pub fn cached_responses(&self) -> usize {
+        self.cache.responses.as_ref().map_or(0, Responses::len)
+    }
+
+    /// Borrows the inner transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+#[derive(Debug)]
+enum Intercept {
+    BlockNumber,
+    FilterChanges,
+    Immutable(String),
+    None,
+}
+
+// Returns the key of an immutable query, i.e. its method and serialized parameters.
+fn immutable_key(call: &rpc::MethodCall) -> Option<String> {
+    if !IMMUTABLE_METHODS.contains(&call.method.as_str()) {
+        return None;
+    }
+    let params = serde_json::to_string(&call.params).ok()?;
+    Some(format!("{}:{}", call.method, params))
+}
+
+impl<T: Transport> Transport for CachingTransport<T> {
+    type Out = CachingResponse<T::Out>;
+
+    fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+        self.inner.prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: rpc::Call) -> Self::Out {
+        let intercept = match request {
+            rpc::Call::MethodCall(ref call) if call.method == "eth_blockNumber" => Intercept::BlockNumber,
+            rpc::Call::MethodCall(ref call) if call.method == "eth_getFilterChanges" => Intercept::FilterChanges,
+            rpc::Call::MethodCall(ref call) if self.cache.responses.is_some() => {
+                immutable_key(call).map_or(Intercept::None, Intercept::Immutable)
+            }
+            _ => Intercept::None,
+        };
+
+        let cached = match (&intercept, &self.cache.responses) {
+            (Intercept::BlockNumber, _) => self.cache.get(),
+            (Intercept::Immutable(key), Some(responses)) => responses.get(key),
+            _ => None,
+        };
+        if let Some(cached) = cached {
+            log::trace!("[id:{}] returning cached response", id);
+            return CachingResponse {
+                inner: None,
+                cached: Some(cached),
+                intercept: Intercept::None,
+                cache: self.cache.clone(),
+            };
+        }
+
+        CachingResponse {
+            inner: Some(self.inner.send(id, request)),
+            cached: None,
+            intercept,
+            cache: self.cache.clone(),
+        }
+    }
+}
+
+/// Response of `CachingTransport`, either resolved from the cache or updating it once resolved.
+#[pin_project]
+#[derive(Debug)]
+pub struct CachingResponse<F> {
+    #[pin]
+    inner: Option<F>,
+    cached: Option<rpc::Value>,
+    intercept: Intercept,
+    cache: Arc<Cache>,
+}
+
+impl<F> Future for CachingResponse<F>
+where
+    F: Future<Output = error::Result<rpc::Value>>,
+{
+    type Output = error::Result<rpc::Value>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        let inner = match this.inner.as_pin_mut() {
+            Some(inner) => inner,
+            None => {
+                let cached = this.cached.take().expect("CachingResponse polled after completion");
+                return Poll::Ready(Ok(cached));
+            }
+        };
+        let result = ready!(inner.poll(ctx));
+        match (std::mem::replace(this.intercept, Intercept::None), &result) {
+            (Intercept::BlockNumber, Ok(block_number)) => this.cache.set(block_number.clone()),
+            (Intercept::FilterChanges, Ok(rpc::Value::Array(changes))) if !changes.is_empty() => {
+                this.cache.invalidate()
+            }
+            (Intercept::Immutable(key), Ok(value)) if !value.is_null() => {
+                if let Some(responses) = &this.cache.responses {
+                    responses.set(key, value.clone());
+                }
+            }
+            _ => {}
+        }
+        Poll::Ready(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CachingTransport;
+    use crate::{
+        api::{Eth, EthFilter, Namespace},
+        rpc::Value,
+        transports::test::TestTransport,
+        types::U64,
+        Transport,
+    };
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[test]
+    fn should_coalesce_block_number_reads() {
+        // given
+        let mut inner = TestTransport::default();
+        inner.add_response(Value::String("0x1".into()));
+        inner.add_response(Value::String("0x2".into()));
+        let transport = CachingTransport::new(inner.clone(), Duration::from_secs(60));
+        let eth = Eth::new(&transport);
+
+        // when
+        let first = futures::executor::block_on(eth.block_number());
+        let second = futures::executor::block_on(eth.block_number());
+        transport.invalidate();
+        let third = futures::executor::block_on(eth.block_number());
+
+        // then
+        assert_eq!(first, Ok(U64::from(1)));
+        assert_eq!(second, Ok(U64::from(1)));
+        assert_eq!(third, Ok(U64::from(2)));
+        inner.assert_request("eth_blockNumber", &[]);
+        inner.assert_request("eth_blockNumber", &[]);
+        inner.assert_request("eth_blockNumber", &[]);
+        inner.assert_no_more_requests();
+    }
+
+    #[test]
+    fn should_invalidate_when_filter_reports_new_block() {
+        // given
+        let mut inner = TestTransport::default();
+        inner.add_response(Value::String("0x1".into()));
+        inner.add_response(Value::String("0x123".into()));
+        inner.add_response(Value::Array(vec![]));
+        inner.add_response(Value::Array(vec![Value::String(
+            "0x0000000000000000000000000000000000000000000000000000000000000456".into(),
+        )]));
+        inner.add_response(Value::String("0x2".into()));
+        let transport = CachingTransport::new(inner.clone(), Duration::from_secs(60));
+        let eth = Eth::new(&transport);
+
+        // when
+        let before = futures::executor::block_on(eth.block_number());
+        let filter = futures::executor::block_on(EthFilter::new(&transport).create_blocks_filter()).unwrap();
+        futures::executor::block_on(filter.poll()).unwrap();
+        let unchanged = futures::executor::block_on(eth.block_number());
+        futures::executor::block_on(filter.poll()).unwrap();
+        let after = futures::executor::block_on(eth.block_number());
+
+        // then
+        assert_eq!(before, Ok(U64::from(1)));
+        assert_eq!(unchanged, Ok(U64::from(1)));
+        assert_eq!(after, Ok(U64::from(2)));
+        inner.assert_request("eth_blockNumber", &[]);
+        inner.assert_request("eth_newBlockFilter", &[]);
+        inner.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        inner.assert_request("eth_blockNumber", &[]);
+        inner.assert_request("eth_getFilterChanges", &[r#""0x123""#.into()]);
+        inner.assert_request("eth_blockNumber", &[]);
+        inner.assert_no_more_requests();
+    }
+
+    #[test]
+    fn should_memoize_immutable_queries() {
+        // given
+        let mut inner = TestTransport::default();
+        inner.add_response(Value::String("0x1".into()));
+        inner.add_response(Value::Null);
+        inner.add_response(json!({ "hash": "0x02" }));
+        inner.add_response(json!({ "hash": "0x03", "blockHash": "0x02" }));
+        inner.add_response(json!({ "hash": "0x03", "blockHash": "0x04" }));
+        let transport = CachingTransport::new(inner, Duration::from_secs(60)).with_immutable_queries(10);
+        let execute =
+            |method: &str, params: Vec<Value>| futures::executor::block_on(transport.execute(method, params)).unwrap();
+        let block = || vec![Value::String("0x02".into()), Value::Bool(false)];
+        let tx = || vec![Value::String("0x03".into())];
+
+        // when
+        let chain_id = execute("eth_chainId", vec![]);
+        let unknown_block = execute("eth_getBlockByHash", block());
+        let known_block = execute("eth_getBlockByHash", block());
+        let tx_before_reorg = execute("eth_getTransactionByHash", tx());
+        let tx_after_reorg = execute("eth_getTransactionByHash", tx());
+
+        // then
+        assert_eq!(execute("eth_chainId", vec![]), chain_id);
+        assert_eq!(unknown_block, Value::Null);
+        assert_eq!(execute("eth_getBlockByHash", block()), known_block);
+        assert_eq!(tx_before_reorg["blockHash"], json!("0x02"));
+        assert_eq!(tx_after_reorg["blockHash"], json!("0x04"));
+        assert_eq!(transport.cached_responses(), 2);
+    }
+
+    #[test]
+    fn should_evict_oldest_responses_over_capacity() {
+        // given
+        let mut inner = TestTransport::default();
+        for block in &["0x1", "0x2", "0x3", "0x4"] {
+            inner.add_response(json!({ "hash": block }));
+        }
+        let transport = CachingTransport::new(inner, Duration::from_secs(60)).with_immutable_queries(2);
+        let execute = |hash: &str| {
+            let params = vec![Value::String(hash.into()), Value::Bool(false)];
+            futures::executor::block_on(transport.execute("eth_getBlockByHash", params)).unwrap()
+        };
+
+        // when
+        execute("0x1");
+        execute("0x2");
+        execute("0x3");
+        let evicted = execute("0x1");
+
+        // then
+        assert_eq!(evicted, json!({ "hash": "0x4" }));
+        assert_eq!(execute("0x3"), json!({ "hash": "0x3" }));
+        assert_eq!(transport.cached_responses(), 2);
+    }
+}
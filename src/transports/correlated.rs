@@ -0,0 +1,104 @@
+//! Transport attaching request ids to errors.
+
+use crate::{error, rpc, Error, RequestId, Transport};
+use futures::{
+    task::{Context, Poll},
+    Future,
+};
+use pin_project::pin_project;
+use std::pin::Pin;
+
+/// A transport wrapping errors of failed requests in `Error::Request`, carrying the id assigned to the request.
+///
+/// Allows correlating failures with provider-side logs, especially combined with a custom `RequestIdGenerator`.
+/// The original error is available through `std::error::Error::source` or by matching the variant.
+#[derive(Debug, Clone)]
+pub struct Correlated<T> {
+    inner: T,
+}
+
+impl<T: Transport> Correlated<T> {
+    /// Wraps the `inner` transport.
+    pub fn new(inner: T) -> Self {
+        Correlated { inner }
+    }
+
+    /// Borrows the inner transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: Transport> Transport for Correlated<T> {
+    type Out = CorrelatedResponse<T::Out>;
+
+    fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+        self.inner.prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: rpc::Call) -> Self::Out {
+        CorrelatedResponse {
+            inner: self.inner.send(id, request),
+            id,
+        }
+    }
+}
+
+/// Response of `Correlated`, attaching the id of the request to its error.
+#[pin_project]
+#[derive(Debug)]
+pub struct CorrelatedResponse<F> {
+    #[pin]
+    inner: F,
+    id: RequestId,
+}
+
+impl<F> Future for CorrelatedResponse<F>
+where
+    F: Future<Output = error::Result<rpc::Value>>,
+{
+    type Output = error::Result<rpc::Value>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        let id = *this.id;
+        Poll::Ready(ready!(this.inner.poll(ctx)).map_err(|error| match error {
+            Error::Request { .. } => error,
+            error => Error::Request {
+                id,
+                error: Box::new(error),
+            },
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Correlated;
+    use crate::{rpc::Value, transports::test::TestTransport, Error, Transport};
+
+    #[test]
+    fn should_attach_request_id_to_errors() {
+        // given
+        let mut inner = TestTransport::default();
+        inner.add_response(Value::String("0x1".into()));
+        let transport = Correlated::new(inner);
+
+        // when
+        let ok = futures::executor::block_on(transport.execute("eth_blockNumber", vec![]));
+        let failed = futures::executor::block_on(transport.execute("eth_blockNumber", vec![]));
+
+        // then
+        assert_eq!(ok, Ok(Value::String("0x1".into())));
+        let failed = failed.unwrap_err();
+        assert_eq!(failed.request_id(), Some(2));
+        assert_eq!(
+            failed,
+            Error::Request {
+                id: 2,
+                error: Box::new(Error::Unreachable)
+            }
+        );
+        assert_eq!(failed.to_string(), "Request 2 failed: Server is unreachable");
+    }
+}
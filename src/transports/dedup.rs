@@ -0,0 +1,261 @@
+//! Transport coalescing identical in-flight requests.
+
+use crate::{error, rpc, RequestId, Transport};
+use futures::{
+    future::{FutureExt, Shared},
+    task::{Context, Poll},
+    Future,
+};
+use parking_lot::Mutex;
+use pin_project::{pin_project, pinned_drop};
+use std::{collections::HashMap, fmt, pin::Pin, sync::Arc};
+
+/// Methods with side effects, which are never coalesced.
+const NON_IDEMPOTENT_METHODS: &[&str] = &[
+    "eth_getFilterChanges",
+    "eth_newBlockFilter",
+    "eth_newFilter",
+    "eth_newPendingTransactionFilter",
+    "eth_sendTransaction",
+    "eth_subscribe",
+    "personal_sendTransaction",
+];
+
+/// A transport sharing a single call to the inner transport among all identical requests (same method and params)
+/// in flight at the same time.
+///
+/// Useful when many futures poll the same data concurrently, like confirmations all reading `eth_blockNumber`.
+/// Only requests sent while the first one is pending are coalesced, nothing is cached once it resolves.
+/// Methods with side effects (like `eth_sendTransaction` or filter creation) are always passed through.
+pub struct Dedup<T: Transport> {
+    inner: T,
+    in_flight: Arc<InFlight<T::Out>>,
+}
+
+impl<T: Transport> Clone for Dedup<T> {
+    fn clone(&self) -> Self {
+        Dedup {
+            inner: self.inner.clone(),
+            in_flight: Arc::clone(&self.in_flight),
+        }
+    }
+}
+
+struct InFlight<F: Future> {
+    next: Mutex<u64>,
+    requests: Mutex<HashMap<String, (u64, Shared<F>)>>,
+}
+
+impl<F: Future> InFlight<F>
+where
+    F::Output: Clone,
+{
+    // Returns the in-flight request with given key, or registers the new one.
+    fn join(&self, key: String, send: impl FnOnce() -> F) -> (u64, Shared<F>) {
+        let mut requests = self.requests.lock();
+        if let Some((generation, shared)) = requests.get(&key) {
+            return (*generation, shared.clone());
+        }
+        let generation = {
+            let mut next = self.next.lock();
+            *next += 1;
+            *next
+        };
+        let shared = send().shared();
+        requests.insert(key, (generation, shared.clone()));
+        (generation, shared)
+    }
+}
+
+impl<F: Future> InFlight<F> {
+    fn finish(&self, key: &str, generation: u64) {
+        let mut requests = self.requests.lock();
+        if matches!(requests.get(key), Some((registered, _)) if *registered == generation) {
+            requests.remove(key);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.requests.lock().len()
+    }
+}
+
+// Returns the key identifying identical requests, if the request may be coalesced.
+fn dedup_key(request: &rpc::Call) -> Option<String> {
+    let call = match request {
+        rpc::Call::MethodCall(call) => call,
+        _ => return None,
+    };
+    if NON_IDEMPOTENT_METHODS.contains(&call.method.as_str()) {
+        return None;
+    }
+    let params = serde_json::to_string(&call.params).ok()?;
+    Some(format!("{}:{}", call.method, params))
+}
+
+impl<T: Transport> fmt::Debug for Dedup<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Dedup")
+            .field("inner", &self.inner)
+            .field("in_flight", &self.in_flight.len())
+            .finish()
+    }
+}
+
+impl<T: Transport> Dedup<T> {
+    /// Wraps the `inner` transport, coalescing its identical in-flight requests.
+    pub fn new(inner: T) -> Self {
+        Dedup {
+            inner,
+            in_flight: Arc::new(InFlight {
+                next: Mutex::new(0),
+                requests: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// Returns the number of distinct requests currently in flight.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    /// Borrows the inner transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: Transport> Transport for Dedup<T> {
+    type Out = DedupResponse<T::Out>;
+
+    fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+        self.inner.prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: rpc::Call) -> Self::Out {
+        let key = match dedup_key(&request) {
+            Some(key) => key,
+            None => {
+                return DedupResponse {
+                    inner: self.inner.send(id, request).shared(),
+                    key: None,
+                    in_flight: self.in_flight.clone(),
+                }
+            }
+        };
+        let (generation, inner) = self.in_flight.join(key.clone(), || self.inner.send(id, request));
+        DedupResponse {
+            inner,
+            key: Some((key, generation)),
+            in_flight: self.in_flight.clone(),
+        }
+    }
+}
+
+/// Response of `Dedup`, shared by all identical requests.
+#[pin_project(PinnedDrop)]
+pub struct DedupResponse<F: Future> {
+    #[pin]
+    inner: Shared<F>,
+    key: Option<(String, u64)>,
+    in_flight: Arc<InFlight<F>>,
+}
+
+impl<F: Future> fmt::Debug for DedupResponse<F> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("DedupResponse").field("key", &self.key).finish()
+    }
+}
+
+impl<F> Future for DedupResponse<F>
+where
+    F: Future<Output = error::Result<rpc::Value>>,
+{
+    type Output = error::Result<rpc::Value>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        let result = ready!(this.inner.poll(ctx));
+        if let Some((key, generation)) = this.key.take() {
+            this.in_flight.finish(&key, generation);
+        }
+        Poll::Ready(result)
+    }
+}
+
+// Stop offering the shared request to new callers once any caller is done with it.
+#[pinned_drop]
+impl<F: Future> PinnedDrop for DedupResponse<F> {
+    fn drop(self: Pin<&mut Self>) {
+        let this = self.project();
+        if let Some((key, generation)) = this.key.take() {
+            this.in_flight.finish(&key, generation);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Dedup;
+    use crate::{rpc::Value, transports::test::TestTransport, Transport};
+    use futures::future::join;
+
+    #[test]
+    fn should_coalesce_identical_in_flight_requests() {
+        // given
+        let mut inner = TestTransport::default();
+        inner.add_response(Value::String("0x1".into()));
+        inner.add_response(Value::String("0x2".into()));
+        inner.add_response(Value::String("0x3".into()));
+        let transport = Dedup::new(inner.clone());
+
+        // when
+        let first = transport.execute("eth_blockNumber", vec![]);
+        let second = transport.execute("eth_blockNumber", vec![]);
+        let in_flight = transport.in_flight();
+        let (first, second) = futures::executor::block_on(join(first, second));
+        let after = futures::executor::block_on(transport.execute("eth_blockNumber", vec![]));
+
+        // then
+        assert_eq!(in_flight, 1);
+        assert_eq!(first, Ok(Value::String("0x1".into())));
+        assert_eq!(second, Ok(Value::String("0x1".into())));
+        assert_eq!(after, Ok(Value::String("0x2".into())));
+        assert_eq!(transport.in_flight(), 0);
+        inner.assert_request("eth_blockNumber", &[]);
+        inner.assert_request("eth_blockNumber", &[]);
+        inner.assert_request("eth_blockNumber", &[]);
+        inner.assert_no_more_requests();
+    }
+
+    #[test]
+    fn should_not_coalesce_different_or_non_idempotent_requests() {
+        // given
+        let mut inner = TestTransport::default();
+        inner.add_response(Value::String("0x1".into()));
+        inner.add_response(Value::String("0x2".into()));
+        inner.add_response(Value::String("0x3".into()));
+        inner.add_response(Value::String("0x4".into()));
+        let transport = Dedup::new(inner.clone());
+        let latest = || vec![Value::String("latest".into())];
+
+        // when
+        let balance = transport.execute("eth_getBalance", latest());
+        let code = transport.execute("eth_getCode", latest());
+        let first_filter = transport.execute("eth_newBlockFilter", vec![]);
+        let second_filter = transport.execute("eth_newBlockFilter", vec![]);
+        let (balance, code) = futures::executor::block_on(join(balance, code));
+        let (first_filter, second_filter) = futures::executor::block_on(join(first_filter, second_filter));
+
+        // then
+        assert_eq!(balance, Ok(Value::String("0x1".into())));
+        assert_eq!(code, Ok(Value::String("0x2".into())));
+        assert_eq!(first_filter, Ok(Value::String("0x3".into())));
+        assert_eq!(second_filter, Ok(Value::String("0x4".into())));
+        inner.assert_request("eth_getBalance", &[r#""latest""#.into()]);
+        inner.assert_request("eth_getCode", &[r#""latest""#.into()]);
+        inner.assert_request("eth_newBlockFilter", &[]);
+        inner.assert_request("eth_newBlockFilter", &[]);
+        inner.assert_no_more_requests();
+    }
+}
@@ -0,0 +1,469 @@
+//! Transport failing over between multiple endpoints.
+
+use crate::{api::Web3, error, rpc, time::Instant, types::NodeHealth, Error, RequestId, Transport};
+use futures::{
+    future::join_all,
+    task::{Context, Poll},
+    Future,
+};
+use parking_lot::Mutex;
+use pin_project::pin_project;
+use std::{
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// A transport switching to the next endpoint whenever the current one fails.
+///
+/// Only transport-level failures (the endpoint is unreachable or timed out, an IO or transport error) cause a
+/// failover, RPC errors returned by a healthy node are passed through. The failed call itself is not
+/// retried, subsequent calls are sent to the next endpoint in order.
+#[derive(Debug, Clone)]
+pub struct FailoverTransport<T> {
+    transports: Arc<Vec<T>>,
+    state: Arc<State>,
+}
+
+/// Alias of `FailoverTransport`.
+pub type Failover<T> = FailoverTransport<T>;
+
+#[derive(Debug)]
+struct State {
+    len: usize,
+    current: AtomicUsize,
+    failed_over_at: Mutex<Option<Instant>>,
+    retry_primary_after: Option<Duration>,
+}
+
+impl State {
+    fn current(&self) -> usize {
+        self.current.load(Ordering::SeqCst)
+    }
+
+    // Returns whether the primary endpoint should be probed, at most once every `retry_primary_after`.
+    fn probe_primary(&self, index: usize) -> bool {
+        if index == 0 {
+            return false;
+        }
+        let mut failed_over_at = self.failed_over_at.lock();
+        match (self.retry_primary_after, *failed_over_at) {
+            (Some(retry_after), Some(at)) if at.elapsed() >= retry_after => {
+                log::debug!("Probing primary endpoint");
+                *failed_over_at = Some(Instant::now());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn recover(&self, index: usize) {
+        if self
+            .current
+            .compare_exchange(index, 0, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+        {
+            log::info!("Primary endpoint is healthy, switching back from endpoint {}", index);
+            *self.failed_over_at.lock() = None;
+        }
+    }
+
+    fn switch_to(&self, index: usize) {
+        let previous = self.current.swap(index, Ordering::SeqCst);
+        if previous != index {
+            log::info!("Switching from endpoint {} to healthy endpoint {}", previous, index);
+            *self.failed_over_at.lock() = if index == 0 { None } else { Some(Instant::now()) };
+        }
+    }
+
+    fn fail(&self, index: usize) {
+        let next = (index + 1) % self.len;
+        if self
+            .current
+            .compare_exchange(index, next, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+        {
+            log::warn!("Endpoint {} failed, switching to endpoint {}", index, next);
+            *self.failed_over_at.lock() = Some(Instant::now());
+        }
+    }
+}
+
+impl<T: Transport> FailoverTransport<T> {
+    /// Creates a new transport using given endpoints, in order of preference.
+    ///
+    /// Panics if `transports` is empty.
+    pub fn new(transports: Vec<T>) -> Self {
+        assert!(!transports.is_empty(), "At least one transport is required.");
+        let len = transports.len();
+        FailoverTransport {
+            transports: Arc::new(transports),
+            state: Arc::new(State {
+                len,
+                current: AtomicUsize::new(0),
+                failed_over_at: Mutex::new(None),
+                retry_primary_after: None,
+            }),
+        }
+    }
+
+    /// Switch back to the first (primary) endpoint once it is healthy again.
+    ///
+    /// Once `duration` elapses after a failover, the primary endpoint is probed with `eth_blockNumber` alongside the
+    /// next call, which is still sent to the current endpoint without waiting for the probe. If the primary responds
+    /// before the call completes, subsequent calls go to it. Otherwise it is probed again after another `duration`.
+    pub fn retry_primary_after(self, duration: Duration) -> Self {
+        let state = State {
+            len: self.state.len,
+            current: AtomicUsize::new(self.state.current.load(Ordering::SeqCst)),
+            failed_over_at: Mutex::new(*self.state.failed_over_at.lock()),
+            retry_primary_after: Some(duration),
+        };
+        FailoverTransport {
+            transports: self.transports,
+            state: Arc::new(state),
+        }
+    }
+
+    /// Returns the index of the endpoint used for the next call.
+    pub fn current(&self) -> usize {
+        self.state.current()
+    }
+
+    /// Probes all endpoints with `Web3::node_status` and switches to the first one which responds and is synced.
+    ///
+    /// The current endpoint is kept if none of them is healthy. Meant to be called periodically, returns the status
+    /// of every endpoint.
+    pub async fn check_health(&self) -> Vec<error::Result<NodeHealth>> {
+        let statuses = join_all(
+            self.transports
+                .iter()
+                .map(|transport| async move { Web3::new(transport).node_status().await }),
+        )
+        .await;
+        match statuses
+            .iter()
+            .position(|status| matches!(status, Ok(health) if health.is_synced()))
+        {
+            Some(index) => self.state.switch_to(index),
+            None => log::warn!(
+                "None of the endpoints is healthy, staying on endpoint {}",
+                self.current()
+            ),
+        }
+        statuses
+    }
+}
+
+impl<T: Transport> Transport for FailoverTransport<T> {
+    type Out = FailoverResponse<T::Out>;
+
+    fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+        // Ids are always generated by the first endpoint, so that they are unique across all of them.
+        self.transports[0].prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: rpc::Call) -> Self::Out {
+        let index = self.state.current();
+        let probe = if self.state.probe_primary(index) {
+            Some(self.transports[0].execute("eth_blockNumber", vec![]))
+        } else {
+            None
+        };
+        FailoverResponse {
+            inner: self.transports[index].send(id, request),
+            probe,
+            index,
+            state: self.state.clone(),
+        }
+    }
+}
+
+/// Response of `FailoverTransport`, switching endpoints when the call fails.
+///
+/// A probe of the primary endpoint is polled along with the call and abandoned if it's still pending once the call
+/// completes.
+#[pin_project]
+#[derive(Debug)]
+pub struct FailoverResponse<F> {
+    #[pin]
+    inner: F,
+    #[pin]
+    probe: Option<F>,
+    index: usize,
+    state: Arc<State>,
+}
+
+impl<F> Future for FailoverResponse<F>
+where
+    F: Future<Output = error::Result<rpc::Value>>,
+{
+    type Output = error::Result<rpc::Value>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let mut this = self.project();
+        if let Some(probe) = this.probe.as_mut().as_pin_mut() {
+            if let Poll::Ready(result) = probe.poll(ctx) {
+                if result.is_ok() {
+                    this.state.recover(*this.index);
+                }
+                this.probe.set(None);
+            }
+        }
+        let result = ready!(this.inner.poll(ctx));
+        if let Some(Error::Unreachable | Error::Timeout | Error::Transport(_) | Error::Io(_)) =
+            result.as_ref().err().map(Error::root)
+        {
+            this.state.fail(*this.index);
+        }
+        Poll::Ready(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FailoverTransport;
+    use crate::{error, helpers, rpc, transports::Correlated, Error, RequestId, Transport};
+    use futures::future;
+    use serde_json::json;
+    use std::{cell::RefCell, collections::VecDeque, rc::Rc, time::Duration};
+
+    /// Endpoint answering with scripted results (leaving calls pending once they run out) and recording the methods
+    /// sent to it.
+    #[derive(Debug, Clone, Default)]
+    struct Endpoint {
+        results: Rc<RefCell<VecDeque<error::Result<rpc::Value>>>>,
+        sent: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl Endpoint {
+        fn new(results: Vec<error::Result<&str>>) -> Self {
+            let endpoint = Endpoint::default();
+            for result in results {
+                endpoint.push(result);
+            }
+            endpoint
+        }
+
+        fn push(&self, result: error::Result<&str>) {
+            let result = result.map(|value| rpc::Value::String(value.into()));
+            self.results.borrow_mut().push_back(result);
+        }
+
+        // Answers a node status check, reporting the node as synced or not.
+        fn with_status(self, synced: bool) -> Self {
+            let syncing = if synced {
+                json!(false)
+            } else {
+                json!({ "startingBlock": "0x0", "currentBlock": "0x1", "highestBlock": "0x2" })
+            };
+            self.results
+                .borrow_mut()
+                .extend(vec![Ok(json!("1")), Ok(syncing), Ok(json!("0x2"))]);
+            self
+        }
+
+        fn sent(&self) -> Vec<String> {
+            self.sent.borrow().clone()
+        }
+    }
+
+    impl Transport for Endpoint {
+        type Out = future::Either<future::Ready<error::Result<rpc::Value>>, future::Pending<error::Result<rpc::Value>>>;
+
+        fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+            (1, helpers::build_request(1, method, params))
+        }
+
+        fn send(&self, _id: RequestId, request: rpc::Call) -> Self::Out {
+            if let rpc::Call::MethodCall(call) = request {
+                self.sent.borrow_mut().push(call.method);
+            }
+            match self.results.borrow_mut().pop_front() {
+                Some(result) => future::Either::Left(future::ready(result)),
+                None => future::Either::Right(future::pending()),
+            }
+        }
+    }
+
+    fn execute<T: Transport>(transport: &FailoverTransport<T>) -> error::Result<rpc::Value> {
+        futures::executor::block_on(transport.execute("eth_test", vec![]))
+    }
+
+    fn value(value: &str) -> error::Result<rpc::Value> {
+        Ok(rpc::Value::String(value.into()))
+    }
+
+    #[test]
+    fn should_switch_to_next_endpoint_on_failure() {
+        // given
+        let primary = Endpoint::new(vec![Err(Error::Unreachable)]);
+        let secondary = Endpoint::new(vec![Ok("x")]);
+        let transport = FailoverTransport::new(vec![primary.clone(), secondary.clone()]);
+
+        // when
+        let first = execute(&transport);
+        let second = execute(&transport);
+
+        // then
+        assert_eq!(first, Err(Error::Unreachable));
+        assert_eq!(second, value("x"));
+        assert_eq!(transport.current(), 1);
+        assert_eq!(primary.sent(), vec!["eth_test"]);
+        assert_eq!(secondary.sent(), vec!["eth_test"]);
+    }
+
+    #[test]
+    fn should_switch_to_next_endpoint_on_failure_with_request_id() {
+        // given
+        let primary = Endpoint::new(vec![Err(Error::Unreachable)]);
+        let secondary = Endpoint::new(vec![Ok("x")]);
+        let transport = FailoverTransport::new(vec![Correlated::new(primary), Correlated::new(secondary)]);
+
+        // when
+        let first = execute(&transport);
+        let second = execute(&transport);
+
+        // then
+        assert_eq!(first.unwrap_err().root(), &Error::Unreachable);
+        assert_eq!(second, value("x"));
+        assert_eq!(transport.current(), 1);
+    }
+
+    #[test]
+    fn should_stay_on_endpoint_while_it_works() {
+        // given
+        let primary = Endpoint::new(vec![Ok("x"), Ok("y")]);
+        let secondary = Endpoint::default();
+        let transport = FailoverTransport::new(vec![primary.clone(), secondary.clone()]);
+
+        // when
+        let first = execute(&transport);
+        let second = execute(&transport);
+
+        // then
+        assert_eq!(first, value("x"));
+        assert_eq!(second, value("y"));
+        assert_eq!(transport.current(), 0);
+        assert!(secondary.sent().is_empty());
+    }
+
+    #[test]
+    fn should_retry_primary_endpoint_once_healthy() {
+        // given
+        let primary = Endpoint::new(vec![Err(Error::Unreachable)]);
+        let secondary = Endpoint::new(vec![Ok("y")]);
+        let transport = FailoverTransport::new(vec![primary.clone(), secondary.clone()])
+            .retry_primary_after(Duration::from_secs(0));
+
+        // when
+        let first = execute(&transport);
+        primary.push(Ok("0x1"));
+        primary.push(Ok("x"));
+        let second = execute(&transport);
+        let third = execute(&transport);
+
+        // then
+        assert_eq!(first, Err(Error::Unreachable));
+        assert_eq!(second, value("y"));
+        assert_eq!(third, value("x"));
+        assert_eq!(transport.current(), 0);
+        assert_eq!(primary.sent(), vec!["eth_test", "eth_blockNumber", "eth_test"]);
+        assert_eq!(secondary.sent(), vec!["eth_test"]);
+    }
+
+    #[test]
+    fn should_stay_on_next_endpoint_while_primary_is_unhealthy() {
+        // given
+        let primary = Endpoint::new(vec![Err(Error::Unreachable), Err(Error::Unreachable)]);
+        let secondary = Endpoint::new(vec![Ok("y")]);
+        let transport = FailoverTransport::new(vec![primary.clone(), secondary.clone()])
+            .retry_primary_after(Duration::from_secs(0));
+
+        // when
+        let first = execute(&transport);
+        let second = execute(&transport);
+
+        // then
+        assert_eq!(first, Err(Error::Unreachable));
+        assert_eq!(second, value("y"));
+        assert_eq!(transport.current(), 1);
+        assert_eq!(primary.sent(), vec!["eth_test", "eth_blockNumber"]);
+        assert_eq!(secondary.sent(), vec!["eth_test"]);
+    }
+
+    #[test]
+    fn should_not_wait_for_probe_of_primary_endpoint() {
+        // given
+        let primary = Endpoint::new(vec![Err(Error::Unreachable)]);
+        let secondary = Endpoint::new(vec![Ok("y")]);
+        let transport = FailoverTransport::new(vec![primary.clone(), secondary.clone()])
+            .retry_primary_after(Duration::from_secs(0));
+
+        // when
+        let first = execute(&transport);
+        // the probe never gets a response
+        let second = execute(&transport);
+
+        // then
+        assert_eq!(first, Err(Error::Unreachable));
+        assert_eq!(second, value("y"));
+        assert_eq!(transport.current(), 1);
+        assert_eq!(primary.sent(), vec!["eth_test", "eth_blockNumber"]);
+    }
+
+    #[test]
+    fn should_stay_on_next_endpoint_until_retry_duration_elapses() {
+        // given
+        let primary = Endpoint::new(vec![Err(Error::Unreachable)]);
+        let secondary = Endpoint::new(vec![Ok("y")]);
+        let transport = FailoverTransport::new(vec![primary.clone(), secondary.clone()])
+            .retry_primary_after(Duration::from_secs(60));
+
+        // when
+        let first = execute(&transport);
+        primary.push(Ok("x"));
+        let second = execute(&transport);
+
+        // then
+        assert_eq!(first, Err(Error::Unreachable));
+        assert_eq!(second, value("y"));
+        assert_eq!(transport.current(), 1);
+        assert_eq!(primary.sent(), vec!["eth_test"]);
+    }
+
+    #[test]
+    fn should_switch_to_first_healthy_endpoint_on_health_check() {
+        // given
+        let primary = Endpoint::default().with_status(false);
+        let secondary = Endpoint::default().with_status(true);
+        let tertiary = Endpoint::default().with_status(true);
+        let transport = FailoverTransport::new(vec![primary, secondary, tertiary]);
+
+        // when
+        let statuses = futures::executor::block_on(transport.check_health());
+
+        // then
+        assert!(statuses[0].as_ref().map(|health| !health.is_synced()).unwrap());
+        assert!(statuses[1].as_ref().map(|health| health.is_synced()).unwrap());
+        assert_eq!(transport.current(), 1);
+    }
+
+    #[test]
+    fn should_keep_current_endpoint_if_none_is_healthy() {
+        // given
+        let primary = Endpoint::new(vec![Err(Error::Unreachable)]);
+        let secondary = Endpoint::default().with_status(false);
+        let transport = FailoverTransport::new(vec![primary, secondary]);
+
+        // when
+        let statuses = futures::executor::block_on(transport.check_health());
+
+        // then
+        assert_eq!(statuses[0], Err(Error::Unreachable));
+        assert_eq!(transport.current(), 0);
+    }
+}
@@ -0,0 +1,518 @@
+//! GraphQL Transport
+
+use crate::{
+    error::{Error, Result, TransportError},
+    helpers, rpc,
+    transports::{http, Http},
+    types::{Block, BlockId, BlockNumber, Transaction, TransactionReceipt},
+    RequestId, Transport,
+};
+#[cfg(not(feature = "wasm"))]
+use futures::future::BoxFuture;
+#[cfg(feature = "wasm")]
+use futures::future::LocalBoxFuture as BoxFuture;
+use reqwest::{Client, Url};
+use serde_json::{json, Map, Value};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+const BLOCK_FIELDS: &str = "fragment BlockFields on Block {
+  hash number parentHash: parent { hash } sha3Uncles: ommerHash miner { address }
+  stateRoot transactionsRoot receiptsRoot gasUsed gasLimit baseFeePerGas extraData logsBloom
+  timestamp difficulty totalDifficulty mixHash nonce uncles: ommers { hash }
+}";
+
+const TRANSACTION_FIELDS: &str = "fragment TransactionFields on Transaction {
+  hash nonce transactionIndex: index from { address } to { address } value gasPrice gas input: inputData
+  v r s type maxFeePerGas maxPriorityFeePerGas block { hash number }
+}";
+
+const RECEIPT_FIELDS: &str = "fragment ReceiptFields on Transaction {
+  cumulativeGasUsed gasUsed contractAddress: createdContract { address } status effectiveGasPrice
+  logs { ...LogFields }
+}";
+
+const LOG_FIELDS: &str = "fragment LogFields on Log {
+  logIndex: index address: account { address } topics data
+  transaction { transactionHash: hash transactionIndex: index block { hash number } }
+}";
+
+/// Transport translating a subset of the `eth` namespace to queries of geth's GraphQL endpoint (`/graphql`).
+///
+/// Supported methods are `eth_blockNumber`, `eth_getBlockByNumber`, `eth_getBlockByHash`,
+/// `eth_getTransactionReceipt` and `eth_getLogs`, other calls fail. Responses are converted to the shape of
+/// JSON-RPC responses, so the transport can be used with `Web3`. Use `block_with_receipts` to fetch a block
+/// together with the receipts of all its transactions in a single round trip, e.g. during backfills.
+#[derive(Debug, Clone)]
+pub struct GraphQl {
+    http: Http,
+    id: Arc<AtomicUsize>,
+}
+
+impl GraphQl {
+    /// Create new transport querying given GraphQL endpoint, e.g. `http://localhost:8545/graphql`.
+    pub fn new(url: &str) -> Result<Self> {
+        Ok(Self::with_http(Http::new(url)?))
+    }
+
+    /// Like `new` but with a user provided client instance.
+    pub fn with_client(client: Client, url: Url) -> Self {
+        Self::with_http(Http::with_client(client, url))
+    }
+
+    /// Query the GraphQL endpoint at the URL of given HTTP transport, reusing its client and timeout.
+    ///
+    /// Headers, authentication and proxy configured with `HttpBuilder` apply to the queries as well, e.g.
+    /// `GraphQl::with_http(Http::builder().bearer_token(token).build("http://localhost:8545/graphql")?)`.
+    pub fn with_http(http: Http) -> Self {
+        GraphQl {
+            http,
+            id: Default::default(),
+        }
+    }
+
+    /// Sends given GraphQL query, returning its `data`.
+    pub async fn query(&self, query: &str, variables: Value) -> Result<Value> {
+        execute_query(self.http.clone(), query.to_owned(), variables).await
+    }
+
+    /// Fetch a block with all its transactions and their receipts in a single request.
+    pub async fn block_with_receipts(
+        &self,
+        block: BlockId,
+    ) -> Result<Option<(Block<Transaction>, Vec<TransactionReceipt>)>> {
+        let (selector, variables) = block_selector(&block)?;
+        let query = format!(
+            "{} {{ ...BlockFields transactions {{ ...TransactionFields ...ReceiptFields }} }} }} {} {} {} {}",
+            selector, BLOCK_FIELDS, TRANSACTION_FIELDS, RECEIPT_FIELDS, LOG_FIELDS
+        );
+        let mut data = self.query(&query, variables).await?;
+        let block = match to_rpc(data["block"].take()) {
+            Value::Null => return Ok(None),
+            block => block,
+        };
+        let receipts = match block["transactions"] {
+            Value::Array(ref transactions) => transactions.iter().cloned().map(to_receipt).collect(),
+            _ => vec![],
+        };
+        let block = serde_json::from_value(block)?;
+        let receipts = receipts
+            .into_iter()
+            .map(serde_json::from_value)
+            .collect::<std::result::Result<_, _>>()?;
+        Ok(Some((block, receipts)))
+    }
+}
+
+impl Transport for GraphQl {
+    type Out = BoxFuture<'static, Result<Value>>;
+
+    fn prepare(&self, method: &str, params: Vec<Value>) -> (RequestId, rpc::Call) {
+        let id = self.id.fetch_add(1, Ordering::AcqRel);
+        (id, helpers::build_request(id, method, params))
+    }
+
+    fn send(&self, id: RequestId, request: rpc::Call) -> Self::Out {
+        let http = self.http.clone();
+        Box::pin(async move {
+            let (method, params) = match request {
+                rpc::Call::MethodCall(call) => (call.method, call.params),
+                _ => return Err(Error::Internal),
+            };
+            let params = match params {
+                rpc::Params::Array(params) => params,
+                _ => vec![],
+            };
+            log::debug!("[id:{}] translating {} to GraphQL", id, method);
+            let (query, variables, extract) = translate(&method, &params)?;
+            let data = execute_query(http, query, variables).await?;
+            Ok(extract(data))
+        })
+    }
+}
+
+async fn execute_query(http: Http, query: String, variables: Value) -> Result<Value> {
+    let (status, mut response) = http::with_timeout(http.timeout(), async {
+        let response = http
+            .client()
+            .post(http.url().clone())
+            .json(&json!({ "query": query, "variables": variables }))
+            .send()
+            .await
+            .map_err(|err| Error::Transport(TransportError::Message(format!("failed to send request: {}", err))))?;
+        let status = response.status();
+        let response: Value = response.json().await.map_err(|err| {
+            Error::Transport(TransportError::Message(format!(
+                "failed to deserialize response: {}",
+                err
+            )))
+        })?;
+        Ok((status, response))
+    })
+    .await?;
+    if let Some(Value::Array(errors)) = response.get("errors") {
+        if !errors.is_empty() {
+            let messages = errors
+                .iter()
+                .map(|error| error["message"].as_str().unwrap_or_default())
+                .collect::<Vec<_>>();
+            return Err(Error::Rpc(rpc::Error {
+                code: rpc::ErrorCode::ServerError(-32000),
+                message: messages.join("; "),
+                data: None,
+            }));
+        }
+    }
+    if !status.is_success() {
+        return Err(Error::Transport(TransportError::Code(status.as_u16())));
+    }
+    Ok(response["data"].take())
+}
+
+type Extract = fn(Value) -> Value;
+
+// Translates a JSON-RPC call to a GraphQL query, its variables and the conversion of its data to the RPC response.
+fn translate(method: &str, params: &[Value]) -> Result<(String, Value, Extract)> {
+    let unsupported = || {
+        Error::Transport(TransportError::Message(format!(
+            "{} is not supported over GraphQL",
+            method
+        )))
+    };
+    let first = || {
+        params
+            .first()
+            .cloned()
+            .ok_or_else(|| Error::Rpc(rpc::Error::invalid_params(format!("{} expects a parameter", method))))
+    };
+    let block_query = |selector: &str, full: bool| {
+        let transactions = if full { "...TransactionFields" } else { "hash" };
+        let mut query = format!(
+            "{} {{ ...BlockFields transactions {{ {} }} }} }} {}",
+            selector, transactions, BLOCK_FIELDS
+        );
+        if full {
+            query.push_str(TRANSACTION_FIELDS);
+        }
+        query
+    };
+    let full = params.get(1).and_then(Value::as_bool).unwrap_or(false);
+    match method {
+        "eth_blockNumber" => {
+            let extract: Extract = |mut data| to_rpc(data["block"]["number"].take());
+            Ok(("{ block { number } }".into(), json!({}), extract))
+        }
+        "eth_getBlockByNumber" => {
+            let number = first()?;
+            let block = serde_json::from_value::<BlockNumber>(number)?;
+            let (selector, variables) = block_selector(&BlockId::Number(block))?;
+            Ok((block_query(&selector, full), variables, extract_block))
+        }
+        "eth_getBlockByHash" => {
+            let hash = first()?;
+            let (selector, variables) = block_selector(&BlockId::Hash(serde_json::from_value(hash)?))?;
+            Ok((block_query(&selector, full), variables, extract_block))
+        }
+        "eth_getTransactionReceipt" => {
+            let hash = first()?;
+            let query = format!(
+                "query($hash: Bytes32!) {{ transaction(hash: $hash) {{ ...TransactionFields ...ReceiptFields }} }} {} {} {}",
+                TRANSACTION_FIELDS, RECEIPT_FIELDS, LOG_FIELDS
+            );
+            let extract: Extract = |mut data| match to_rpc(data["transaction"].take()) {
+                // Pending transactions have no receipt.
+                transaction if transaction["blockHash"].is_null() => Value::Null,
+                transaction => to_receipt(transaction),
+            };
+            Ok((query, json!({ "hash": hash }), extract))
+        }
+        "eth_getLogs" => {
+            let filter = first()?;
+            let (query, variables) = logs_query(&filter)?;
+            let extract: Extract = |mut data| match data["block"].take() {
+                Value::Null => to_rpc(data["logs"].take()),
+                mut block => to_rpc(block["logs"].take()),
+            };
+            Ok((query, variables, extract))
+        }
+        _ => Err(unsupported()),
+    }
+}
+
+fn extract_block(mut data: Value) -> Value {
+    to_rpc(data["block"].take())
+}
+
+// Returns the beginning of a query selecting given block (up to the opening of its selection set) and its variables.
+fn block_selector(block: &BlockId) -> Result<(String, Value)> {
+    let by_number = "query($number: Long!) { block(number: $number)";
+    match block {
+        BlockId::Hash(hash) => Ok((
+            "query($hash: Bytes32!) { block(hash: $hash)".into(),
+            json!({ "hash": hash }),
+        )),
+        BlockId::Number(BlockNumber::Latest) => Ok(("{ block".into(), json!({}))),
+        BlockId::Number(BlockNumber::Earliest) => Ok((by_number.into(), json!({ "number": 0 }))),
+        BlockId::Number(BlockNumber::Number(number)) => Ok((by_number.into(), json!({ "number": number.as_u64() }))),
+        BlockId::Number(block) => Err(Error::Transport(TransportError::Message(format!(
+            "{:?} block is not supported over GraphQL",
+            block
+        )))),
+    }
+}
+
+// Translates a JSON-RPC log filter to a GraphQL logs query.
+fn logs_query(filter: &Value) -> Result<(String, Value)> {
+    let mut criteria = Map::new();
+    match filter.get("address") {
+        Some(Value::Array(addresses)) => {
+            criteria.insert("addresses".into(), Value::Array(addresses.clone()));
+        }
+        Some(address @ Value::String(_)) => {
+            criteria.insert("addresses".into(), json!([address]));
+        }
+        _ => {}
+    }
+    if let Some(Value::Array(topics)) = filter.get("topics") {
+        let topics = topics
+            .iter()
+            .map(|topic| match topic {
+                Value::Array(alternatives) => Value::Array(alternatives.clone()),
+                Value::Null => json!([]),
+                topic => json!([topic]),
+            })
+            .collect();
+        criteria.insert("topics".into(), Value::Array(topics));
+    }
+
+    if let Some(hash) = filter.get("blockHash") {
+        let query = format!(
+            "query($hash: Bytes32!, $filter: BlockFilterCriteria!) {{ block(hash: $hash) {{ logs(filter: $filter) {{ ...LogFields }} }} }} {}",
+            LOG_FIELDS
+        );
+        return Ok((query, json!({ "hash": hash, "filter": criteria })));
+    }
+    for key in &["fromBlock", "toBlock"] {
+        match filter.get(*key).cloned().map(serde_json::from_value::<BlockNumber>) {
+            Some(Ok(BlockNumber::Number(number))) => {
+                criteria.insert((*key).into(), json!(number.as_u64()));
+            }
+            Some(Ok(BlockNumber::Earliest)) => {
+                criteria.insert((*key).into(), json!(0));
+            }
+            Some(Err(err)) => return Err(err.into()),
+            _ => {}
+        }
+    }
+    let query = format!(
+        "query($filter: FilterCriteria!) {{ logs(filter: $filter) {{ ...LogFields }} }} {}",
+        LOG_FIELDS
+    );
+    Ok((query, json!({ "filter": criteria })))
+}
+
+// Converts GraphQL data to the shape of JSON-RPC responses: numbers become hex quantities, nested accounts and
+// blocks are flattened to addresses and hashes, and references to containing blocks and transactions are inlined.
+fn to_rpc(value: Value) -> Value {
+    match value {
+        Value::Number(number) => match number.as_u64() {
+            Some(number) => Value::String(format!("{:#x}", number)),
+            None => Value::Number(number),
+        },
+        Value::Array(values) => Value::Array(values.into_iter().map(to_rpc).collect()),
+        Value::Object(object) => {
+            let mut object = object
+                .into_iter()
+                .map(|(key, value)| (key, to_rpc(value)))
+                .collect::<Map<_, _>>();
+            if object.len() == 1 {
+                if let Some(value) = object.remove("address").or_else(|| object.remove("hash")) {
+                    return value;
+                }
+            }
+            // The transaction of a log has been flattened to `transactionHash`, `transactionIndex` and its block.
+            if let Some(Value::Object(transaction)) = object.remove("transaction") {
+                object.extend(transaction);
+            }
+            if let Some(Value::Object(mut block)) = object.remove("block") {
+                object.insert("blockHash".into(), block["hash"].take());
+                object.insert("blockNumber".into(), block["number"].take());
+            }
+            Value::Object(object)
+        }
+        value => value,
+    }
+}
+
+// Converts a transaction queried with receipt fields to a receipt.
+fn to_receipt(mut transaction: Value) -> Value {
+    if let Value::Object(ref mut object) = transaction {
+        if let Some(hash) = object.get("hash").cloned() {
+            object.insert("transactionHash".into(), hash);
+        }
+    }
+    transaction
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_convert_graphql_data_to_rpc_shape() {
+        // given
+        let data = json!({
+            "hash": "0x01",
+            "number": 16,
+            "miner": { "address": "0x02" },
+            "uncles": [{ "hash": "0x03" }],
+            "transactions": [{
+                "hash": "0x04",
+                "to": null,
+                "block": { "hash": "0x01", "number": "0x10" },
+                "logs": [{
+                    "logIndex": 0,
+                    "address": { "address": "0x05" },
+                    "transaction": {
+                        "transactionHash": "0x04",
+                        "transactionIndex": 1,
+                        "block": { "hash": "0x01", "number": "0x10" }
+                    }
+                }]
+            }]
+        });
+
+        // when
+        let converted = to_rpc(data);
+
+        // then
+        assert_eq!(
+            converted,
+            json!({
+                "hash": "0x01",
+                "number": "0x10",
+                "miner": "0x02",
+                "uncles": ["0x03"],
+                "transactions": [{
+                    "hash": "0x04",
+                    "to": null,
+                    "blockHash": "0x01",
+                    "blockNumber": "0x10",
+                    "logs": [{
+                        "logIndex": "0x0",
+                        "address": "0x05",
+                        "transactionHash": "0x04",
+                        "transactionIndex": "0x1",
+                        "blockHash": "0x01",
+                        "blockNumber": "0x10"
+                    }]
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn should_translate_log_filters() {
+        // given
+        let filter = json!({
+            "fromBlock": "0x10",
+            "toBlock": "latest",
+            "address": "0x0000000000000000000000000000000000000001",
+            "topics": [null, "0x02", ["0x03", "0x04"]]
+        });
+
+        // when
+        let (query, variables) = logs_query(&filter).unwrap();
+
+        // then
+        assert!(query.starts_with("query($filter: FilterCriteria!) { logs(filter: $filter)"));
+        assert_eq!(
+            variables,
+            json!({
+                "filter": {
+                    "fromBlock": 16,
+                    "addresses": ["0x0000000000000000000000000000000000000001"],
+                    "topics": [[], ["0x02"], ["0x03", "0x04"]]
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn should_reject_unsupported_methods() {
+        // when
+        let result = translate("eth_sendTransaction", &[]);
+
+        // then
+        assert!(matches!(result, Err(Error::Transport(TransportError::Message(_)))));
+    }
+
+    #[test]
+    fn should_reject_missing_params() {
+        // when
+        let result = translate("eth_getBlockByHash", &[]);
+
+        // then
+        assert!(matches!(result, Err(Error::Rpc(ref err)) if err.code == rpc::ErrorCode::InvalidParams));
+    }
+
+    #[tokio::test]
+    async fn should_fetch_block_number() {
+        use hyper::service::{make_service_fn, service_fn};
+
+        // given
+        let addr = "127.0.0.1:3012";
+        let service = make_service_fn(|_| async {
+            Ok::<_, hyper::Error>(service_fn(|req: hyper::Request<hyper::Body>| async move {
+                let body = hyper::body::to_bytes(req.into_body()).await?;
+                let request: Value = serde_json::from_slice(&body).unwrap();
+                assert_eq!(request["query"], "{ block { number } }");
+                let response = r#"{"data":{"block":{"number":"0x10"}}}"#;
+                Ok::<_, hyper::Error>(hyper::Response::<hyper::Body>::new(response.into()))
+            }))
+        });
+        let server = hyper::Server::bind(&addr.parse().unwrap()).serve(service);
+        tokio::spawn(async move {
+            server.await.unwrap();
+        });
+
+        // when
+        let transport = GraphQl::new(&format!("http://{}/graphql", addr)).unwrap();
+        let response = transport.execute("eth_blockNumber", vec![]).await;
+
+        // then
+        assert_eq!(response, Ok(Value::String("0x10".into())));
+    }
+
+    #[tokio::test]
+    async fn should_query_with_settings_of_http_transport() {
+        use hyper::service::{make_service_fn, service_fn};
+
+        // given
+        let addr = "127.0.0.1:3014";
+        let service = make_service_fn(|_| async {
+            Ok::<_, hyper::Error>(service_fn(|req: hyper::Request<hyper::Body>| async move {
+                assert_eq!(req.uri().path(), "/graphql");
+                assert_eq!(req.headers()["authorization"], "Bearer jwt");
+                let response = r#"{"data":{"block":{"number":"0x10"}}}"#;
+                Ok::<_, hyper::Error>(hyper::Response::<hyper::Body>::new(response.into()))
+            }))
+        });
+        let server = hyper::Server::bind(&addr.parse().unwrap()).serve(service);
+        tokio::spawn(async move {
+            server.await.unwrap();
+        });
+
+        // when
+        let http = Http::builder()
+            .bearer_token("jwt")
+            .build(&format!("http://{}/graphql", addr))
+            .unwrap();
+        let response = GraphQl::with_http(http).execute("eth_blockNumber", vec![]).await;
+
+        // then
+        assert_eq!(response, Ok(Value::String("0x10".into())));
+    }
+}
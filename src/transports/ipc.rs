@@ -1,8 +1,11 @@
 //! IPC transport
 
 use crate::{
-    api::SubscriptionId, error::TransportError, helpers, BatchTransport, DuplexTransport, Error, RequestId, Result,
-    Transport,
+    api::SubscriptionId,
+    error::TransportError,
+    helpers,
+    transports::{RequestIdGenerator, SequentialIds},
+    BatchTransport, DuplexTransport, Error, RequestId, Result, Transport,
 };
 use futures::{
     future::{join_all, JoinAll},
@@ -13,52 +16,87 @@ use std::{
     collections::BTreeMap,
     path::Path,
     pin::Pin,
-    sync::{atomic::AtomicUsize, Arc},
+    sync::Arc,
     task::{Context, Poll},
 };
 use tokio::{
-    io::AsyncWriteExt,
+    io::{AsyncRead, AsyncWrite, AsyncWriteExt},
     sync::{mpsc, oneshot},
 };
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tokio_util::io::ReaderStream;
 
+#[cfg(windows)]
+use tokio::net::windows::named_pipe::ClientOptions;
 #[cfg(unix)]
 use tokio::net::UnixStream;
 
-/// Unix Domain Sockets (IPC) transport.
+/// Windows error code returned when all instances of a named pipe are busy.
+#[cfg(windows)]
+const ERROR_PIPE_BUSY: i32 = 231;
+
+/// IPC transport over Unix Domain Sockets or, on Windows, named pipes.
 #[derive(Debug, Clone)]
 pub struct Ipc {
-    id: Arc<AtomicUsize>,
+    id: Arc<dyn RequestIdGenerator>,
     messages_tx: mpsc::UnboundedSender<TransportMessage>,
 }
 
 #[cfg(unix)]
 impl Ipc {
-    /// Creates a new IPC transport from a given path.
-    ///
-    /// IPC is only available on Unix.
+    /// Creates a new IPC transport connected to the Unix Domain Socket at given path.
     pub async fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
         let stream = UnixStream::connect(path).await?;
 
         Ok(Self::with_stream(stream))
     }
+}
+
+#[cfg(windows)]
+impl Ipc {
+    /// Creates a new IPC transport connected to a named pipe, e.g. `\\.\pipe\geth.ipc`.
+    ///
+    /// Waits for the pipe to become available if all its instances are busy.
+    pub async fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let stream = loop {
+            match ClientOptions::new().open(path.as_ref()) {
+                Ok(stream) => break stream,
+                Err(err) if err.raw_os_error() == Some(ERROR_PIPE_BUSY) => {
+                    tokio::time::sleep(std::time::Duration::from_millis(50)).await
+                }
+                Err(err) => return Err(err.into()),
+            }
+        };
+
+        Ok(Self::with_stream(stream))
+    }
+}
 
-    fn with_stream(stream: UnixStream) -> Self {
-        let id = Arc::new(AtomicUsize::new(1));
+impl Ipc {
+    fn with_stream<S>(stream: S) -> Self
+    where
+        S: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        let id = Arc::new(SequentialIds::new(1));
         let (messages_tx, messages_rx) = mpsc::unbounded_channel();
 
         tokio::spawn(run_server(stream, UnboundedReceiverStream::new(messages_rx)));
 
         Ipc { id, messages_tx }
     }
+
+    /// Use the given generator for ids of subsequent requests.
+    pub fn with_id_generator<G: RequestIdGenerator + 'static>(mut self, generator: G) -> Self {
+        self.id = Arc::new(generator);
+        self
+    }
 }
 
 impl Transport for Ipc {
     type Out = SingleResponse;
 
     fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (crate::RequestId, rpc::Call) {
-        let id = self.id.fetch_add(1, std::sync::atomic::Ordering::AcqRel);
+        let id = self.id.next_id();
         let request = helpers::build_request(id, method, params);
         (id, request)
     }
@@ -162,9 +200,11 @@ enum TransportMessage {
     Unsubscribe(SubscriptionId),
 }
 
-#[cfg(unix)]
-async fn run_server(unix_stream: UnixStream, messages_rx: UnboundedReceiverStream<TransportMessage>) -> Result<()> {
-    let (socket_reader, mut socket_writer) = unix_stream.into_split();
+async fn run_server<S>(stream: S, messages_rx: UnboundedReceiverStream<TransportMessage>) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite,
+{
+    let (socket_reader, mut socket_writer) = tokio::io::split(stream);
     let mut pending_response_txs = BTreeMap::default();
     let mut subscription_txs = BTreeMap::default();
 
@@ -175,7 +215,8 @@ async fn run_server(unix_stream: UnixStream, messages_rx: UnboundedReceiverStrea
 
     while !closed || !pending_response_txs.is_empty() {
         tokio::select! {
-            message = messages_rx.next() => match message {
+            // Once closed, only the responses to pending requests (like `eth_unsubscribe` sent on drop) are awaited.
+            message = messages_rx.next(), if !closed => match message {
                 None => closed = true,
                 Some(TransportMessage::Subscribe(id, tx)) => {
                     if subscription_txs.insert(id.clone(), tx).is_some() {
@@ -375,6 +416,32 @@ mod test {
         assert_eq!(response, Ok(expected_response_json));
     }
 
+    #[tokio::test]
+    async fn connects_to_socket_path() {
+        let path = std::env::temp_dir().join(format!("web3-ipc-test-{}.ipc", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            eth_node_single(stream).await;
+        });
+
+        let ipc = Ipc::new(&path).await.unwrap();
+        let response = ipc.execute("eth_test", vec![json!({ "test": -1 })]).await;
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(response, Ok(json!({ "test": 1 })));
+    }
+
+    #[tokio::test]
+    async fn fails_to_connect_to_missing_socket() {
+        let path = std::env::temp_dir().join("web3-ipc-test-missing.ipc");
+
+        let result = Ipc::new(&path).await;
+
+        assert!(matches!(result, Err(Error::Io(ref err)) if err.kind() == std::io::ErrorKind::NotFound));
+    }
+
     async fn eth_node_single(stream: UnixStream) {
         let (rx, mut tx) = stream.into_split();
 
@@ -423,6 +490,115 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn uses_custom_id_generator() {
+        let (stream1, stream2) = UnixStream::pair().unwrap();
+        let ipc = Ipc::with_stream(stream1).with_id_generator(SequentialIds::new(1000));
+
+        tokio::spawn(async move {
+            let (rx, mut tx) = stream2.into_split();
+            let mut rx = ReaderStream::new(rx);
+            if let Some(Ok(bytes)) = rx.next().await {
+                let v: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+                assert_eq!(v["id"], json!(1000));
+
+                tx.write_all(r#"{"jsonrpc": "2.0", "id": 1000, "result": "x"}"#.as_ref())
+                    .await
+                    .unwrap();
+                tx.flush().await.unwrap();
+            }
+        });
+
+        let response = ipc.execute("eth_test", vec![]).await;
+        assert_eq!(response, Ok(json!("x")));
+    }
+
+    #[tokio::test]
+    async fn unsubscribes_when_subscription_is_dropped() {
+        use crate::api::{EthSubscribe, Namespace};
+
+        let (stream1, stream2) = UnixStream::pair().unwrap();
+        let ipc = Ipc::with_stream(stream1);
+
+        let node = tokio::spawn(async move {
+            let (rx, mut tx) = stream2.into_split();
+            let mut rx = ReaderStream::new(rx);
+
+            let bytes = rx.next().await.unwrap().unwrap();
+            let v: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+            assert_eq!(v["method"], json!("eth_subscribe"));
+            let response = json!({"jsonrpc": "2.0", "id": v["id"], "result": "0x1"});
+            tx.write_all(response.to_string().as_ref()).await.unwrap();
+            tx.flush().await.unwrap();
+
+            let bytes = rx.next().await.unwrap().unwrap();
+            let v: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+            assert_eq!(v["method"], json!("eth_unsubscribe"));
+            assert_eq!(v["params"], json!(["0x1"]));
+        });
+
+        let subscription = EthSubscribe::new(ipc).subscribe_new_heads().await.unwrap();
+        drop(subscription);
+
+        node.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn subscribes_to_full_pending_transactions() {
+        use crate::{
+            api::{EthSubscribe, Namespace},
+            types::{Transaction, H256},
+        };
+
+        let (stream1, stream2) = UnixStream::pair().unwrap();
+        let ipc = Ipc::with_stream(stream1);
+        let transaction = Transaction {
+            hash: H256::from_low_u64_be(0x111),
+            ..Default::default()
+        };
+
+        let (subscribed_tx, subscribed_rx) = tokio::sync::oneshot::channel();
+        let node = {
+            let transaction = transaction.clone();
+            tokio::spawn(async move {
+                let (rx, mut tx) = stream2.into_split();
+                let mut rx = ReaderStream::new(rx);
+
+                let bytes = rx.next().await.unwrap().unwrap();
+                let v: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+                assert_eq!(v["method"], json!("eth_subscribe"));
+                assert_eq!(v["params"], json!(["newPendingTransactions", true]));
+                let response = json!({"jsonrpc": "2.0", "id": v["id"], "result": "0x1"});
+                tx.write_all(response.to_string().as_ref()).await.unwrap();
+                tx.flush().await.unwrap();
+
+                // Notifications for subscriptions the transport doesn't know about yet are dropped.
+                subscribed_rx.await.unwrap();
+                let notification = json!({
+                    "jsonrpc": "2.0",
+                    "method": "eth_subscription",
+                    "params": {"subscription": "0x1", "result": transaction},
+                });
+                tx.write_all(notification.to_string().as_ref()).await.unwrap();
+                tx.flush().await.unwrap();
+
+                let bytes = rx.next().await.unwrap().unwrap();
+                let v: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+                assert_eq!(v["method"], json!("eth_unsubscribe"));
+            })
+        };
+
+        let mut subscription = EthSubscribe::new(ipc)
+            .subscribe_full_pending_transactions()
+            .await
+            .unwrap();
+        subscribed_tx.send(()).unwrap();
+        assert_eq!(subscription.next().await, Some(Ok(transaction)));
+        drop(subscription);
+
+        node.await.unwrap();
+    }
+
     #[tokio::test]
     async fn works_for_batch_request() {
         let (stream1, stream2) = UnixStream::pair().unwrap();
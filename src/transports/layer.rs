@@ -0,0 +1,294 @@
+//! Composable transport layers and request/response interceptors.
+
+use crate::{error, rpc, RequestId, Transport};
+use futures::{
+    task::{Context, Poll},
+    Future,
+};
+use pin_project::pin_project;
+use std::{fmt, pin::Pin, sync::Arc};
+
+/// Decorates a transport with additional behaviour, e.g. retries, logging or caching.
+///
+/// Layers are applied with `Transport::layer`. Any `Fn(T) -> U` returning a transport is a layer,
+/// so existing wrappers can be used directly, e.g. `transport.layer(|t| Retry::new(t, 3))`.
+pub trait TransportLayer<T> {
+    /// The transport produced by this layer.
+    type Transport: Transport;
+
+    /// Wraps the `inner` transport.
+    fn layer(&self, inner: T) -> Self::Transport;
+}
+
+impl<T, U, F> TransportLayer<T> for F
+where
+    F: Fn(T) -> U,
+    U: Transport,
+{
+    type Transport = U;
+
+    fn layer(&self, inner: T) -> Self::Transport {
+        self(inner)
+    }
+}
+
+/// Two layers applied on top of each other, `inner` first.
+#[derive(Debug, Clone)]
+pub struct Stack<Inner, Outer> {
+    inner: Inner,
+    outer: Outer,
+}
+
+impl<Inner, Outer> Stack<Inner, Outer> {
+    /// Creates a layer applying `inner` and then `outer`.
+    pub fn new(inner: Inner, outer: Outer) -> Self {
+        Stack { inner, outer }
+    }
+}
+
+impl<T, Inner, Outer> TransportLayer<T> for Stack<Inner, Outer>
+where
+    Inner: TransportLayer<T>,
+    Outer: TransportLayer<Inner::Transport>,
+{
+    type Transport = Outer::Transport;
+
+    fn layer(&self, inner: T) -> Self::Transport {
+        self.outer.layer(self.inner.layer(inner))
+    }
+}
+
+/// Hooks called around every request made through an `Intercepted` transport.
+///
+/// All hooks default to doing nothing, so implementations only override the ones they need.
+pub trait Interceptor {
+    /// State of a single request, created by `on_request` and handed over to `on_response`.
+    type Context: Default;
+
+    /// Called before the request is built, may modify its parameters.
+    fn on_prepare(&self, _method: &str, _params: &mut Vec<rpc::Value>) {}
+
+    /// Called before the request is sent, returning the context of the request. Returning a result as well answers
+    /// the request without sending it.
+    fn on_request(&self, _id: RequestId, _request: &rpc::Call) -> (Self::Context, Option<error::Result<rpc::Value>>) {
+        (Default::default(), None)
+    }
+
+    /// Called with the context and the result of the request (including short-circuited ones), may modify the
+    /// result.
+    fn on_response(&self, _id: RequestId, _context: Self::Context, _result: &mut error::Result<rpc::Value>) {}
+}
+
+/// Layer wrapping transports with an `Interceptor`.
+pub struct InterceptorLayer<I>(Arc<I>);
+
+impl<I> InterceptorLayer<I> {
+    /// Creates a layer calling `interceptor` around every request.
+    pub fn new(interceptor: I) -> Self {
+        InterceptorLayer(Arc::new(interceptor))
+    }
+}
+
+impl<I> fmt::Debug for InterceptorLayer<I> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("InterceptorLayer").finish()
+    }
+}
+
+impl<I> Clone for InterceptorLayer<I> {
+    fn clone(&self) -> Self {
+        InterceptorLayer(self.0.clone())
+    }
+}
+
+impl<T: Transport, I: Interceptor> TransportLayer<T> for InterceptorLayer<I> {
+    type Transport = Intercepted<T, I>;
+
+    fn layer(&self, inner: T) -> Self::Transport {
+        Intercepted {
+            inner,
+            interceptor: self.0.clone(),
+        }
+    }
+}
+
+/// A transport calling an `Interceptor` around every request of the inner transport.
+pub struct Intercepted<T, I> {
+    inner: T,
+    interceptor: Arc<I>,
+}
+
+impl<T, I> Intercepted<T, I> {
+    /// Wraps the `inner` transport, calling `interceptor` around every request.
+    pub fn new(inner: T, interceptor: I) -> Self {
+        Intercepted {
+            inner,
+            interceptor: Arc::new(interceptor),
+        }
+    }
+
+    /// Borrows the inner transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: fmt::Debug, I> fmt::Debug for Intercepted<T, I> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Intercepted").field("inner", &self.inner).finish()
+    }
+}
+
+impl<T: Clone, I> Clone for Intercepted<T, I> {
+    fn clone(&self) -> Self {
+        Intercepted {
+            inner: self.inner.clone(),
+            interceptor: self.interceptor.clone(),
+        }
+    }
+}
+
+impl<T: Transport, I: Interceptor> Transport for Intercepted<T, I> {
+    type Out = InterceptedResponse<T::Out, I>;
+
+    fn prepare(&self, method: &str, mut params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+        self.interceptor.on_prepare(method, &mut params);
+        self.inner.prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: rpc::Call) -> Self::Out {
+        let (context, result) = self.interceptor.on_request(id, &request);
+        let state = match result {
+            Some(result) => State::Done(Some(result)),
+            None => State::Sending(self.inner.send(id, request)),
+        };
+        InterceptedResponse {
+            state,
+            id,
+            context: Some(context),
+            interceptor: self.interceptor.clone(),
+        }
+    }
+}
+
+#[pin_project(project = StateProj)]
+#[derive(Debug)]
+enum State<F> {
+    Done(Option<error::Result<rpc::Value>>),
+    Sending(#[pin] F),
+}
+
+/// Response of `Intercepted`, passing the result through the interceptor.
+#[pin_project]
+pub struct InterceptedResponse<F, I: Interceptor> {
+    #[pin]
+    state: State<F>,
+    id: RequestId,
+    context: Option<I::Context>,
+    interceptor: Arc<I>,
+}
+
+impl<F: fmt::Debug, I: Interceptor> fmt::Debug for InterceptedResponse<F, I> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("InterceptedResponse")
+            .field("state", &self.state)
+            .field("id", &self.id)
+            .finish()
+    }
+}
+
+impl<F, I> Future for InterceptedResponse<F, I>
+where
+    F: Future<Output = error::Result<rpc::Value>>,
+    I: Interceptor,
+{
+    type Output = error::Result<rpc::Value>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        let mut result = match this.state.project() {
+            StateProj::Done(result) => result.take().expect("response polled after completion"),
+            StateProj::Sending(response) => ready!(response.poll(ctx)),
+        };
+        let context = this.context.take().expect("response polled after completion");
+        this.interceptor.on_response(*this.id, context, &mut result);
+        Poll::Ready(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Intercepted, Interceptor, InterceptorLayer, Stack};
+    use crate::{
+        error, rpc,
+        transports::{test::TestTransport, Timeout},
+        RequestId, Transport,
+    };
+    use std::time::Duration;
+
+    struct Tagging;
+
+    impl Interceptor for Tagging {
+        type Context = String;
+
+        fn on_prepare(&self, method: &str, params: &mut Vec<rpc::Value>) {
+            if method == "eth_getBalance" {
+                params.push(rpc::Value::String("latest".into()));
+            }
+        }
+
+        fn on_request(&self, _id: RequestId, request: &rpc::Call) -> (String, Option<error::Result<rpc::Value>>) {
+            match request {
+                rpc::Call::MethodCall(call) if call.method == "eth_chainId" => {
+                    (call.method.clone(), Some(Ok(rpc::Value::String("0x1".into()))))
+                }
+                rpc::Call::MethodCall(call) => (call.method.clone(), None),
+                _ => (String::new(), None),
+            }
+        }
+
+        fn on_response(&self, _id: RequestId, method: String, result: &mut error::Result<rpc::Value>) {
+            if let Ok(rpc::Value::String(value)) = result {
+                *value = format!("{} ({})", value, method);
+            }
+        }
+    }
+
+    #[test]
+    fn should_intercept_requests_and_responses() {
+        // given
+        let mut inner = TestTransport::default();
+        inner.add_response(rpc::Value::String("0x10".into()));
+        let transport = (&inner).layer(InterceptorLayer::new(Tagging));
+
+        // when
+        let balance = futures::executor::block_on(transport.execute("eth_getBalance", vec![rpc::Value::Null]));
+        let chain_id = futures::executor::block_on(transport.execute("eth_chainId", vec![]));
+
+        // then
+        assert_eq!(balance, Ok(rpc::Value::String("0x10 (eth_getBalance)".into())));
+        assert_eq!(chain_id, Ok(rpc::Value::String("0x1 (eth_chainId)".into())));
+        inner.assert_request("eth_getBalance", &[r#"null"#.into(), r#""latest""#.into()]);
+        inner.assert_request("eth_chainId", &[]);
+        inner.assert_no_more_requests();
+    }
+
+    #[test]
+    fn should_apply_stacked_layers_in_order() {
+        // given
+        let mut inner = TestTransport::default();
+        inner.add_response(rpc::Value::String("0x10".into()));
+        let layer = Stack::new(
+            InterceptorLayer::new(Tagging),
+            |transport: Intercepted<TestTransport, Tagging>| Timeout::new(transport, Duration::from_secs(10)),
+        );
+        let transport = inner.clone().layer(layer);
+
+        // when
+        let balance = futures::executor::block_on(transport.execute("eth_getBalance", vec![]));
+
+        // then
+        assert_eq!(balance, Ok(rpc::Value::String("0x10 (eth_getBalance)".into())));
+        inner.assert_request("eth_getBalance", &[r#""latest""#.into()]);
+    }
+}
@@ -0,0 +1,176 @@
+//! Observer logging all requests and responses.
+
+use crate::{
+    rpc,
+    transports::logging::{Event, LoggingTransport, Observer},
+    RequestId,
+};
+use ::log::Level;
+use std::collections::BTreeSet;
+
+/// Methods whose parameters carry passwords or keys and are redacted by default.
+const SENSITIVE_METHODS: &[&str] = &[
+    "personal_importRawKey",
+    "personal_newAccount",
+    "personal_sendTransaction",
+    "personal_sign",
+    "personal_signTransaction",
+    "personal_unlockAccount",
+];
+
+/// A transport logging every request through the `Log` observer.
+///
+/// Create one with `LoggingTransport::new(transport, Log::new())`.
+pub type Logged<T> = LoggingTransport<T, Log>;
+
+/// An observer logging every request (method, params and id) and its response (with latency) through the `log`
+/// crate.
+///
+/// Requests and responses are logged at `Debug` and errors at `Warn` level by default. Parameters of methods taking
+/// passwords or keys (like `personal_unlockAccount`) are redacted unless disabled with `without_redaction`.
+#[derive(Debug)]
+pub struct Log {
+    request_level: Level,
+    response_level: Level,
+    error_level: Level,
+    redacted: BTreeSet<String>,
+}
+
+impl Default for Log {
+    fn default() -> Self {
+        Log {
+            request_level: Level::Debug,
+            response_level: Level::Debug,
+            error_level: Level::Warn,
+            redacted: SENSITIVE_METHODS.iter().map(|method| method.to_string()).collect(),
+        }
+    }
+}
+
+impl Log {
+    /// Creates an interceptor logging at default levels, redacting the sensitive methods.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Log requests at `request` and successful responses at `response` level.
+    pub fn with_levels(self, request: Level, response: Level) -> Self {
+        Log {
+            request_level: request,
+            response_level: response,
+            ..self
+        }
+    }
+
+    /// Log failed responses at `level`.
+    pub fn with_error_level(self, level: Level) -> Self {
+        Log {
+            error_level: level,
+            ..self
+        }
+    }
+
+    /// Redact parameters of calls to `method` in addition to the default sensitive methods.
+    pub fn redact(mut self, method: &str) -> Self {
+        self.redacted.insert(method.to_owned());
+        self
+    }
+
+    /// Log parameters of all calls, including the default sensitive methods.
+    pub fn without_redaction(self) -> Self {
+        Log {
+            redacted: BTreeSet::new(),
+            ..self
+        }
+    }
+
+    fn format_request(&self, id: RequestId, method: &str, params: &rpc::Params) -> String {
+        if self.redacted.contains(method) {
+            return format!("[id:{}] -> {} [redacted]", id, method);
+        }
+        let params = serde_json::to_string(params).unwrap_or_else(|err| format!("<{}>", err));
+        format!("[id:{}] -> {} {}", id, method, params)
+    }
+}
+
+impl Observer for Log {
+    fn observe(&self, event: Event) {
+        match event {
+            Event::Request { id, method, params } => {
+                if ::log::log_enabled!(self.request_level) {
+                    ::log::log!(self.request_level, "{}", self.format_request(id, method, params));
+                }
+            }
+            Event::Response {
+                id,
+                method,
+                result: Ok(value),
+                elapsed,
+            } => ::log::log!(
+                self.response_level,
+                "[id:{}] <- {} {} ({:?})",
+                id,
+                method,
+                value,
+                elapsed
+            ),
+            Event::Response {
+                id,
+                method,
+                result: Err(err),
+                elapsed,
+            } => ::log::log!(
+                self.error_level,
+                "[id:{}] <- {} failed: {} ({:?})",
+                id,
+                method,
+                err,
+                elapsed
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Log, Logged};
+    use crate::{
+        rpc::{Params, Value},
+        transports::{test::TestTransport, LoggingTransport},
+        Transport,
+    };
+
+    #[test]
+    fn should_format_requests_redacting_sensitive_params() {
+        // given
+        let log = Log::new().redact("eth_sign");
+        let secret = Params::Array(vec![Value::String("secret".into())]);
+        let latest = Params::Array(vec![Value::String("latest".into())]);
+
+        // when
+        let unlock = log.format_request(1, "personal_unlockAccount", &secret);
+        let sign = log.format_request(1, "eth_sign", &secret);
+        let balance = log.format_request(1, "eth_getBalance", &latest);
+
+        // then
+        assert_eq!(unlock, "[id:1] -> personal_unlockAccount [redacted]");
+        assert_eq!(sign, "[id:1] -> eth_sign [redacted]");
+        assert_eq!(balance, r#"[id:1] -> eth_getBalance ["latest"]"#);
+    }
+
+    #[test]
+    fn should_pass_through_responses() {
+        // given
+        let mut inner = TestTransport::default();
+        inner.add_response(Value::String("0x1".into()));
+        let transport: Logged<_> = LoggingTransport::new(inner.clone(), Log::new().without_redaction());
+
+        // when
+        let result = futures::executor::block_on(transport.execute("personal_unlockAccount", vec![]));
+
+        // then
+        assert_eq!(result, Ok(Value::String("0x1".into())));
+        inner.assert_request("personal_unlockAccount", &[]);
+        inner.assert_no_more_requests();
+    }
+}
@@ -0,0 +1,242 @@
+//! Transport observing all requests and responses.
+
+use crate::{error, rpc, time::Instant, RequestId, Transport};
+use futures::{
+    task::{Context, Poll},
+    Future,
+};
+use pin_project::pin_project;
+use std::{fmt, pin::Pin, sync::Arc, time::Duration};
+
+/// A single step of a call made through `LoggingTransport`.
+#[derive(Debug)]
+pub enum Event<'a> {
+    /// The request is about to be sent.
+    Request {
+        /// Id of the request.
+        id: RequestId,
+        /// Name of the called method.
+        method: &'a str,
+        /// Parameters of the call.
+        params: &'a rpc::Params,
+    },
+    /// The response to the request has been received.
+    Response {
+        /// Id of the request.
+        id: RequestId,
+        /// Name of the called method.
+        method: &'a str,
+        /// Result of the call.
+        result: &'a error::Result<rpc::Value>,
+        /// Time elapsed since the request was sent.
+        elapsed: Duration,
+    },
+}
+
+/// Receives the events of calls made through `LoggingTransport`.
+///
+/// Implemented by closures taking an `Event` and by `Log`, which writes them to the `log` crate.
+pub trait Observer {
+    /// Called on every step of a call.
+    fn observe(&self, event: Event);
+}
+
+impl<F: Fn(Event)> Observer for F {
+    fn observe(&self, event: Event) {
+        self(event)
+    }
+}
+
+/// A transport wrapper calling the given observer on every request and response.
+///
+/// Calls are delegated to the inner transport unchanged, which makes this useful
+/// for logging, metrics or tracing of the JSON-RPC traffic.
+pub struct LoggingTransport<T, F> {
+    inner: T,
+    observer: Arc<F>,
+}
+
+impl<T, F> LoggingTransport<T, F>
+where
+    F: Observer,
+{
+    /// Wraps the `inner` transport, reporting its traffic to `observer`.
+    pub fn new(inner: T, observer: F) -> Self {
+        LoggingTransport {
+            inner,
+            observer: Arc::new(observer),
+        }
+    }
+
+    /// Borrows the inner transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for LoggingTransport<T, F> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("LoggingTransport")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<T: Clone, F> Clone for LoggingTransport<T, F> {
+    fn clone(&self) -> Self {
+        LoggingTransport {
+            inner: self.inner.clone(),
+            observer: self.observer.clone(),
+        }
+    }
+}
+
+impl<T, F> Transport for LoggingTransport<T, F>
+where
+    T: Transport,
+    F: Observer,
+{
+    type Out = LoggingResponse<T::Out, F>;
+
+    fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+        self.inner.prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: rpc::Call) -> Self::Out {
+        let method = match request {
+            rpc::Call::MethodCall(ref call) => {
+                self.observer.observe(Event::Request {
+                    id,
+                    method: &call.method,
+                    params: &call.params,
+                });
+                call.method.clone()
+            }
+            rpc::Call::Notification(ref notification) => {
+                self.observer.observe(Event::Request {
+                    id,
+                    method: &notification.method,
+                    params: &notification.params,
+                });
+                notification.method.clone()
+            }
+            rpc::Call::Invalid { .. } => String::new(),
+        };
+
+        LoggingResponse {
+            inner: self.inner.send(id, request),
+            id,
+            method,
+            started: Instant::now(),
+            observer: self.observer.clone(),
+        }
+    }
+}
+
+/// Response of `LoggingTransport`, reporting the result to the observer once resolved.
+#[pin_project]
+pub struct LoggingResponse<Fut, F> {
+    #[pin]
+    inner: Fut,
+    id: RequestId,
+    method: String,
+    started: Instant,
+    observer: Arc<F>,
+}
+
+impl<Fut: fmt::Debug, F> fmt::Debug for LoggingResponse<Fut, F> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("LoggingResponse")
+            .field("inner", &self.inner)
+            .field("id", &self.id)
+            .field("method", &self.method)
+            .field("started", &self.started)
+            .finish()
+    }
+}
+
+impl<Fut, F> Future for LoggingResponse<Fut, F>
+where
+    Fut: Future<Output = error::Result<rpc::Value>>,
+    F: Observer,
+{
+    type Output = error::Result<rpc::Value>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        let result = ready!(this.inner.poll(ctx));
+        this.observer.observe(Event::Response {
+            id: *this.id,
+            method: this.method,
+            result: &result,
+            elapsed: this.started.elapsed(),
+        });
+        Poll::Ready(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Event, LoggingTransport};
+    use crate::{
+        api::{Eth, Namespace},
+        rpc::Value,
+        transports::test::TestTransport,
+        types::{U256, U64},
+    };
+    use std::{cell::RefCell, rc::Rc};
+
+    #[test]
+    fn should_observe_requests_and_responses() {
+        // given
+        let mut inner = TestTransport::default();
+        inner.add_response(Value::String("0x123".into()));
+        inner.add_response(Value::String("0x1".into()));
+        let events = Rc::new(RefCell::new(vec![]));
+        let transport = {
+            let events = events.clone();
+            LoggingTransport::new(inner.clone(), move |event: Event| {
+                let event = match event {
+                    Event::Request { method, params, .. } => {
+                        format!("-> {} {}", method, serde_json::to_string(params).unwrap())
+                    }
+                    Event::Response { method, result, .. } => {
+                        format!(
+                            "<- {} {}",
+                            method,
+                            serde_json::to_string(result.as_ref().unwrap()).unwrap()
+                        )
+                    }
+                };
+                events.borrow_mut().push(event);
+            })
+        };
+        let eth = Eth::new(&transport);
+
+        // when
+        let block_number = futures::executor::block_on(eth.block_number());
+        let balance = futures::executor::block_on(eth.balance(Default::default(), None));
+
+        // then
+        assert_eq!(block_number, Ok(U64::from(0x123)));
+        assert_eq!(balance, Ok(U256::from(1)));
+        inner.assert_request("eth_blockNumber", &[]);
+        inner.assert_request(
+            "eth_getBalance",
+            &[
+                r#""0x0000000000000000000000000000000000000000""#.into(),
+                r#""latest""#.into(),
+            ],
+        );
+        inner.assert_no_more_requests();
+        assert_eq!(
+            *events.borrow(),
+            vec![
+                r#"-> eth_blockNumber []"#.to_owned(),
+                r#"<- eth_blockNumber "0x123""#.to_owned(),
+                r#"-> eth_getBalance ["0x0000000000000000000000000000000000000000","latest"]"#.to_owned(),
+                r#"<- eth_getBalance "0x1""#.to_owned(),
+            ]
+        );
+    }
+}
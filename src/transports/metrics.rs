@@ -0,0 +1,290 @@
+//! Transport collecting metrics of all requests.
+
+use crate::{error, rpc, time::Instant, RequestId, Transport};
+use futures::{
+    task::{Context, Poll},
+    Future,
+};
+use parking_lot::Mutex;
+use pin_project::{pin_project, pinned_drop};
+use std::{collections::BTreeMap, fmt::Write, pin::Pin, sync::Arc};
+
+/// Upper bounds (in seconds) of the latency histogram buckets, the Prometheus defaults.
+const BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Latency histogram with cumulative buckets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    /// Upper bound of each bucket (in seconds) and the number of observations lower or equal to it.
+    pub buckets: Vec<(f64, u64)>,
+    /// Sum of all observations (in seconds).
+    pub sum: f64,
+    /// Number of observations.
+    pub count: u64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Histogram {
+            buckets: BUCKETS.iter().map(|bound| (*bound, 0)).collect(),
+            sum: 0.0,
+            count: 0,
+        }
+    }
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        for (bound, count) in &mut self.buckets {
+            if seconds <= *bound {
+                *count += 1;
+            }
+        }
+        self.sum += seconds;
+        self.count += 1;
+    }
+}
+
+/// Metrics of a single RPC method.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MethodMetrics {
+    /// Number of requests sent.
+    pub requests: u64,
+    /// Number of requests resolved with an error.
+    pub errors: u64,
+    /// Number of requests currently waiting for a response.
+    pub in_flight: u64,
+    /// Latency of completed requests.
+    pub latency: Histogram,
+}
+
+/// Registry of per-method metrics, shared by all transports measured with it.
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    methods: Arc<Mutex<BTreeMap<String, MethodMetrics>>>,
+}
+
+impl Metrics {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns the metrics of given method, if it was called.
+    pub fn method(&self, method: &str) -> Option<MethodMetrics> {
+        self.methods.lock().get(method).cloned()
+    }
+
+    /// Returns the metrics of all called methods.
+    pub fn snapshot(&self) -> BTreeMap<String, MethodMetrics> {
+        self.methods.lock().clone()
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format, e.g. to serve them on a `/metrics` endpoint.
+    pub fn render_prometheus(&self) -> String {
+        let methods = self.snapshot();
+        let mut out = String::new();
+        let mut family = |name: &str, kind: &str, help: &str, value: &dyn Fn(&MethodMetrics) -> u64| {
+            let _ = writeln!(out, "# HELP {} {}\n# TYPE {} {}", name, help, name, kind);
+            for (method, metrics) in &methods {
+                let _ = writeln!(out, "{}{{method=\"{}\"}} {}", name, method, value(metrics));
+            }
+        };
+        family(
+            "web3_rpc_requests_total",
+            "counter",
+            "Number of JSON-RPC requests sent.",
+            &|metrics| metrics.requests,
+        );
+        family(
+            "web3_rpc_errors_total",
+            "counter",
+            "Number of JSON-RPC requests resolved with an error.",
+            &|metrics| metrics.errors,
+        );
+        family(
+            "web3_rpc_in_flight",
+            "gauge",
+            "Number of JSON-RPC requests waiting for a response.",
+            &|metrics| metrics.in_flight,
+        );
+
+        let name = "web3_rpc_request_duration_seconds";
+        let _ = writeln!(out, "# HELP {} Latency of JSON-RPC requests.", name);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        for (method, metrics) in &methods {
+            let latency = &metrics.latency;
+            for (bound, count) in &latency.buckets {
+                let _ = writeln!(
+                    out,
+                    "{}_bucket{{method=\"{}\",le=\"{}\"}} {}",
+                    name, method, bound, count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "{}_bucket{{method=\"{}\",le=\"+Inf\"}} {}",
+                name, method, latency.count
+            );
+            let _ = writeln!(out, "{}_sum{{method=\"{}\"}} {}", name, method, latency.sum);
+            let _ = writeln!(out, "{}_count{{method=\"{}\"}} {}", name, method, latency.count);
+        }
+        out
+    }
+
+    fn started(&self, method: &str) {
+        let mut methods = self.methods.lock();
+        let metrics = methods.entry(method.to_owned()).or_default();
+        metrics.requests += 1;
+        metrics.in_flight += 1;
+    }
+
+    fn completed(&self, method: &str, started: Instant, failed: bool) {
+        let mut methods = self.methods.lock();
+        let metrics = methods.entry(method.to_owned()).or_default();
+        metrics.latency.observe(started.elapsed().as_secs_f64());
+        if failed {
+            metrics.errors += 1;
+        }
+    }
+
+    fn finished(&self, method: &str) {
+        if let Some(metrics) = self.methods.lock().get_mut(method) {
+            metrics.in_flight = metrics.in_flight.saturating_sub(1);
+        }
+    }
+}
+
+/// A transport recording request count, error count, latency and in-flight requests per RPC method.
+///
+/// Multiple transports may share a single `Metrics` registry.
+#[derive(Debug, Clone)]
+pub struct Measured<T> {
+    inner: T,
+    metrics: Metrics,
+}
+
+impl<T: Transport> Measured<T> {
+    /// Wraps the `inner` transport, recording its metrics into `metrics`.
+    pub fn new(inner: T, metrics: Metrics) -> Self {
+        Measured { inner, metrics }
+    }
+
+    /// Returns the registry the metrics are recorded into.
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Borrows the inner transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: Transport> Transport for Measured<T> {
+    type Out = MeasuredResponse<T::Out>;
+
+    fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+        self.inner.prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: rpc::Call) -> Self::Out {
+        let method = match request {
+            rpc::Call::MethodCall(ref call) => call.method.clone(),
+            rpc::Call::Notification(ref notification) => notification.method.clone(),
+            rpc::Call::Invalid { .. } => "invalid".into(),
+        };
+        self.metrics.started(&method);
+        MeasuredResponse {
+            inner: self.inner.send(id, request),
+            method,
+            started: Instant::now(),
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+/// Response of `Measured`, recording the outcome once resolved (or dropped).
+#[pin_project(PinnedDrop)]
+#[derive(Debug)]
+pub struct MeasuredResponse<F> {
+    #[pin]
+    inner: F,
+    method: String,
+    started: Instant,
+    metrics: Metrics,
+}
+
+impl<F> Future for MeasuredResponse<F>
+where
+    F: Future<Output = error::Result<rpc::Value>>,
+{
+    type Output = error::Result<rpc::Value>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        let result = ready!(this.inner.poll(ctx));
+        this.metrics.completed(this.method, *this.started, result.is_err());
+        Poll::Ready(result)
+    }
+}
+
+#[pinned_drop]
+impl<F> PinnedDrop for MeasuredResponse<F> {
+    fn drop(self: Pin<&mut Self>) {
+        self.metrics.finished(&self.method);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Measured, Metrics};
+    use crate::{rpc::Value, transports::test::TestTransport, Error, Transport};
+
+    #[test]
+    fn should_record_metrics_per_method() {
+        // given
+        let mut inner = TestTransport::default();
+        inner.add_response(Value::String("0x1".into()));
+        let metrics = Metrics::new();
+        let transport = Measured::new(inner, metrics.clone());
+
+        // when
+        let pending = transport.execute("eth_blockNumber", vec![]);
+        let in_flight = metrics.method("eth_blockNumber").unwrap().in_flight;
+        let first = futures::executor::block_on(pending);
+        let second = futures::executor::block_on(transport.execute("eth_blockNumber", vec![]));
+
+        // then
+        assert_eq!(first, Ok(Value::String("0x1".into())));
+        assert_eq!(second, Err(Error::Unreachable));
+        assert_eq!(in_flight, 1);
+        let block_number = metrics.method("eth_blockNumber").unwrap();
+        assert_eq!(block_number.requests, 2);
+        assert_eq!(block_number.errors, 1);
+        assert_eq!(block_number.in_flight, 0);
+        assert_eq!(block_number.latency.count, 2);
+        assert!(metrics.method("eth_chainId").is_none());
+    }
+
+    #[test]
+    fn should_render_prometheus_format() {
+        // given
+        let mut inner = TestTransport::default();
+        inner.add_response(Value::String("0x1".into()));
+        let metrics = Metrics::new();
+        let transport = Measured::new(inner, metrics.clone());
+        futures::executor::block_on(transport.execute("eth_chainId", vec![])).unwrap();
+
+        // when
+        let rendered = metrics.render_prometheus();
+
+        // then
+        assert!(rendered.contains("# TYPE web3_rpc_requests_total counter\n"));
+        assert!(rendered.contains("web3_rpc_requests_total{method=\"eth_chainId\"} 1\n"));
+        assert!(rendered.contains("web3_rpc_errors_total{method=\"eth_chainId\"} 0\n"));
+        assert!(rendered.contains("web3_rpc_in_flight{method=\"eth_chainId\"} 0\n"));
+        assert!(rendered.contains("web3_rpc_request_duration_seconds_bucket{method=\"eth_chainId\",le=\"+Inf\"} 1\n"));
+        assert!(rendered.contains("web3_rpc_request_duration_seconds_count{method=\"eth_chainId\"} 1\n"));
+    }
+}
@@ -0,0 +1,318 @@
+//! Mock Transport
+
+use crate::{
+    error::{self, Error},
+    helpers, rpc, BatchTransport, RequestId, Transport,
+};
+use futures::future::{self, BoxFuture, FutureExt};
+use parking_lot::Mutex;
+use std::{fmt, sync::Arc};
+
+type Matcher = Box<dyn Fn(&[rpc::Value]) -> bool + Send + Sync>;
+
+/// A transport answering calls according to per-method expectations, for unit testing code built on `Web3<T>`.
+///
+/// Unlike `TestTransport`, responses are not consumed in the order of the requests: every call is answered by the
+/// first expectation matching its method and parameters. Calls not matching any expectation fail with
+/// `Error::Unreachable` and are reported by `verify`.
+#[derive(Debug, Clone, Default)]
+pub struct MockTransport {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    next_id: RequestId,
+    expectations: Vec<ExpectationState>,
+    calls: Vec<(String, Vec<rpc::Value>)>,
+    unexpected: Vec<(String, Vec<rpc::Value>)>,
+}
+
+struct ExpectationState {
+    method: String,
+    matcher: Option<Matcher>,
+    responses: Vec<error::Result<rpc::Value>>,
+    times: Option<usize>,
+    calls: usize,
+}
+
+impl fmt::Debug for ExpectationState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ExpectationState")
+            .field("method", &self.method)
+            .field("responses", &self.responses)
+            .field("times", &self.times)
+            .field("calls", &self.calls)
+            .finish()
+    }
+}
+
+impl ExpectationState {
+    fn matches(&self, method: &str, params: &[rpc::Value]) -> bool {
+        self.method == method
+            && self.times.is_none_or(|times| self.calls < times)
+            && self.matcher.as_ref().is_none_or(|matcher| matcher(params))
+    }
+
+    // Responses are used in order, the last one answering all remaining calls.
+    fn respond(&mut self) -> error::Result<rpc::Value> {
+        let response = match self.responses.get(self.calls).or_else(|| self.responses.last()) {
+            Some(response) => response.clone(),
+            None => Err(Error::Unreachable),
+        };
+        self.calls += 1;
+        response
+    }
+}
+
+impl MockTransport {
+    /// Creates a transport without any expectations.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Expect calls of `method`, returning a handle to configure the expectation.
+    pub fn expect(&self, method: &str) -> Expectation {
+        let mut state = self.state.lock();
+        state.expectations.push(ExpectationState {
+            method: method.into(),
+            matcher: None,
+            responses: vec![],
+            times: None,
+            calls: 0,
+        });
+        Expectation {
+            state: self.state.clone(),
+            index: state.expectations.len() - 1,
+        }
+    }
+
+    /// Returns the number of calls of `method` made so far (matched or not).
+    pub fn calls(&self, method: &str) -> usize {
+        self.state
+            .lock()
+            .calls
+            .iter()
+            .filter(|(called, _)| called == method)
+            .count()
+    }
+
+    /// Returns the parameters of all calls of `method` made so far, in order.
+    pub fn requests(&self, method: &str) -> Vec<Vec<rpc::Value>> {
+        self.state
+            .lock()
+            .calls
+            .iter()
+            .filter(|(called, _)| called == method)
+            .map(|(_, params)| params.clone())
+            .collect()
+    }
+
+    /// Assert that `method` has been called exactly `times` times.
+    pub fn assert_calls(&self, method: &str, times: usize) {
+        assert_eq!(
+            self.calls(method),
+            times,
+            "Expected {} calls of {}, got {}",
+            times,
+            method,
+            self.calls(method)
+        );
+    }
+
+    /// Assert that all calls matched an expectation and expectations with a number of calls were called that many times.
+    pub fn verify(&self) {
+        let state = self.state.lock();
+        assert!(state.unexpected.is_empty(), "Unexpected calls: {:?}", state.unexpected);
+        for expectation in &state.expectations {
+            if let Some(times) = expectation.times {
+                assert_eq!(
+                    expectation.calls, times,
+                    "Expected {} calls of {}, got {}",
+                    times, expectation.method, expectation.calls
+                );
+            }
+        }
+    }
+
+    fn respond(&self, request: &rpc::Call) -> error::Result<rpc::Value> {
+        let (method, params) = match request {
+            rpc::Call::MethodCall(call) => (call.method.clone(), params_of(&call.params)),
+            rpc::Call::Notification(notification) => (notification.method.clone(), params_of(&notification.params)),
+            rpc::Call::Invalid { .. } => return Err(Error::Internal),
+        };
+        let mut state = self.state.lock();
+        state.calls.push((method.clone(), params.clone()));
+        match state
+            .expectations
+            .iter_mut()
+            .find(|expectation| expectation.matches(&method, &params))
+        {
+            Some(expectation) => expectation.respond(),
+            None => {
+                state.unexpected.push((method, params));
+                Err(Error::Unreachable)
+            }
+        }
+    }
+}
+
+fn params_of(params: &rpc::Params) -> Vec<rpc::Value> {
+    match params {
+        rpc::Params::Array(params) => params.clone(),
+        rpc::Params::Map(map) => vec![rpc::Value::Object(map.clone())],
+        rpc::Params::None => vec![],
+    }
+}
+
+impl Transport for MockTransport {
+    type Out = BoxFuture<'static, error::Result<rpc::Value>>;
+
+    fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+        let id = {
+            let mut state = self.state.lock();
+            state.next_id += 1;
+            state.next_id
+        };
+        (id, helpers::build_request(id, method, params))
+    }
+
+    fn send(&self, _id: RequestId, request: rpc::Call) -> Self::Out {
+        future::ready(self.respond(&request)).boxed()
+    }
+}
+
+impl BatchTransport for MockTransport {
+    type Batch = BoxFuture<'static, error::Result<Vec<error::Result<rpc::Value>>>>;
+
+    fn send_batch<T>(&self, requests: T) -> Self::Batch
+    where
+        T: IntoIterator<Item = (RequestId, rpc::Call)>,
+    {
+        let responses = requests
+            .into_iter()
+            .map(|(_, request)| self.respond(&request))
+            .collect();
+        future::ready(Ok(responses)).boxed()
+    }
+}
+
+/// Handle configuring an expectation registered with `MockTransport::expect`.
+#[derive(Debug)]
+pub struct Expectation {
+    state: Arc<Mutex<State>>,
+    index: usize,
+}
+
+impl Expectation {
+    fn update(self, update: impl FnOnce(&mut ExpectationState)) -> Self {
+        update(&mut self.state.lock().expectations[self.index]);
+        self
+    }
+
+    /// Match only calls with exactly these parameters.
+    pub fn with_params(self, params: Vec<rpc::Value>) -> Self {
+        self.matching(move |actual| actual == &params[..])
+    }
+
+    /// Match only calls with parameters accepted by `matcher`.
+    pub fn matching<F>(self, matcher: F) -> Self
+    where
+        F: Fn(&[rpc::Value]) -> bool + Send + Sync + 'static,
+    {
+        self.update(|expectation| expectation.matcher = Some(Box::new(matcher)))
+    }
+
+    /// Match at most `times` calls, which `MockTransport::verify` asserts to be made.
+    pub fn times(self, times: usize) -> Self {
+        self.update(|expectation| expectation.times = Some(times))
+    }
+
+    /// Answer the next matching call with `value`. The last response answers all remaining calls.
+    pub fn returning(self, value: rpc::Value) -> Self {
+        self.update(|expectation| expectation.responses.push(Ok(value)))
+    }
+
+    /// Fail the next matching call with `error`. The last response answers all remaining calls.
+    pub fn returning_error(self, error: Error) -> Self {
+        self.update(|expectation| expectation.responses.push(Err(error)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MockTransport;
+    use crate::{
+        api::{Eth, Namespace},
+        rpc::Value,
+        types::{Address, U256},
+        Error, Transport,
+    };
+
+    #[test]
+    fn should_answer_by_method_and_params() {
+        // given
+        let transport = MockTransport::new();
+        let rich = Address::from_low_u64_be(1);
+        let poor = Address::from_low_u64_be(2);
+        transport
+            .expect("eth_getBalance")
+            .with_params(vec![serde_json::to_value(poor).unwrap(), "latest".into()])
+            .returning("0x0".into());
+        transport
+            .expect("eth_getBalance")
+            .matching(|params| params.len() == 2)
+            .returning("0x100".into());
+        let eth = Eth::new(&transport);
+
+        // when
+        let rich_balance = futures::executor::block_on(eth.balance(rich, None));
+        let poor_balance = futures::executor::block_on(eth.balance(poor, None));
+
+        // then
+        assert_eq!(rich_balance, Ok(U256::from(0x100)));
+        assert_eq!(poor_balance, Ok(U256::from(0)));
+        transport.assert_calls("eth_getBalance", 2);
+        assert_eq!(
+            transport.requests("eth_getBalance")[1][1],
+            Value::String("latest".into())
+        );
+        transport.verify();
+    }
+
+    #[test]
+    fn should_inject_errors_and_count_calls() {
+        // given
+        let transport = MockTransport::new();
+        transport
+            .expect("eth_blockNumber")
+            .times(2)
+            .returning_error(Error::Timeout)
+            .returning("0x1".into());
+
+        // when
+        let first = futures::executor::block_on(transport.execute("eth_blockNumber", vec![]));
+        let second = futures::executor::block_on(transport.execute("eth_blockNumber", vec![]));
+        let third = futures::executor::block_on(transport.execute("eth_blockNumber", vec![]));
+
+        // then
+        assert_eq!(first, Err(Error::Timeout));
+        assert_eq!(second, Ok(Value::String("0x1".into())));
+        assert_eq!(third, Err(Error::Unreachable));
+        transport.assert_calls("eth_blockNumber", 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected 2 calls of eth_chainId, got 1")]
+    fn should_verify_expected_number_of_calls() {
+        // given
+        let transport = MockTransport::new();
+        transport.expect("eth_chainId").times(2).returning("0x1".into());
+
+        // when
+        futures::executor::block_on(transport.execute("eth_chainId", vec![])).unwrap();
+
+        // then
+        transport.verify();
+    }
+}
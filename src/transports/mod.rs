@@ -1,14 +1,54 @@
 //! Supported Ethereum JSON-RPC transports.
 
+pub mod balance;
+pub use self::balance::RoundRobin;
 pub mod batch;
 
 pub use self::batch::Batch;
+pub mod boxed;
+pub use self::boxed::BoxedTransport;
+pub mod caching;
+pub use self::caching::CachingTransport;
+pub mod correlated;
+pub use self::correlated::Correlated;
+pub mod dedup;
+pub use self::dedup::Dedup;
 pub mod either;
 pub use self::either::Either;
+pub mod failover;
+pub use self::failover::{Failover, FailoverTransport};
+pub mod layer;
+pub use self::layer::{Intercepted, Interceptor, InterceptorLayer, Stack, TransportLayer};
+pub mod log;
+pub use self::log::{Log, Logged};
+pub mod logging;
+pub use self::logging::{LoggingTransport, Observer};
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "metrics")]
+pub use self::metrics::{Measured, Metrics};
+pub mod rate_limit;
+pub use self::rate_limit::Throttle;
+pub mod request_id;
+pub use self::request_id::{CallbackIds, RequestIdGenerator, SequentialIds};
+pub mod retry;
+pub use self::retry::Retry;
+pub mod throttled;
+pub use self::throttled::ThrottledTransport;
+pub mod timeout;
+pub use self::timeout::Timeout;
+#[cfg(feature = "tracing")]
+pub mod traced;
+#[cfg(feature = "tracing")]
+pub use self::traced::Traced;
 
-#[cfg(any(feature = "http", feature = "http-rustls"))]
+#[cfg(feature = "http")]
+pub mod graphql;
+#[cfg(feature = "http")]
+pub use self::graphql::GraphQl;
+#[cfg(feature = "http")]
 pub mod http;
-#[cfg(any(feature = "http", feature = "http-rustls"))]
+#[cfg(feature = "http")]
 pub use self::http::Http;
 
 #[cfg(any(feature = "ws-tokio", feature = "ws-async-std"))]
@@ -21,6 +61,10 @@ pub mod ipc;
 #[cfg(feature = "ipc-tokio")]
 pub use self::ipc::Ipc;
 
+#[cfg(any(feature = "test", test))]
+pub mod mock;
+#[cfg(any(feature = "test", test))]
+pub use self::mock::MockTransport;
 #[cfg(any(feature = "test", test))]
 pub mod test;
 
@@ -42,3 +86,5 @@ impl From<async_native_tls::Error> for crate::Error {
 
 #[cfg(feature = "eip-1193")]
 pub mod eip_1193;
+#[cfg(feature = "eip-1193")]
+pub use self::eip_1193::Eip1193;
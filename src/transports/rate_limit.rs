@@ -0,0 +1,183 @@
+//! Transport limiting the rate of requests.
+
+use crate::{error, rpc, time::Instant, RequestId, Transport};
+use futures::{
+    task::{Context, Poll},
+    Future,
+};
+use futures_timer::Delay;
+use parking_lot::Mutex;
+use pin_project::pin_project;
+use std::{pin::Pin, sync::Arc, time::Duration};
+
+/// A transport sending at most a given number of requests per second, using a token bucket.
+///
+/// Up to `burst` requests are sent right away, after that requests are delayed (not rejected) until the budget
+/// refills at `requests_per_second`. Unlike `ThrottledTransport`, which limits the number of concurrent requests,
+/// this limits the rate at which requests are sent regardless of how fast they complete.
+#[derive(Debug, Clone)]
+pub struct Throttle<T> {
+    inner: T,
+    bucket: Arc<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    rate: f64,
+    burst: f64,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    // Takes a token, returning how long to wait until it's actually available.
+    fn reserve(&self) -> Duration {
+        let mut state = self.state.lock();
+        let now = Instant::now();
+        let refilled = now.duration_since(state.updated).as_secs_f64() * self.rate;
+        state.tokens = (state.tokens + refilled).min(self.burst) - 1.0;
+        state.updated = now;
+        if state.tokens >= 0.0 {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_secs_f64(-state.tokens / self.rate)
+        }
+    }
+}
+
+impl<T: Transport> Throttle<T> {
+    /// Wraps the `inner` transport, sending at most `requests_per_second` requests per second
+    /// with bursts of up to `burst` requests.
+    ///
+    /// Panics if `requests_per_second` is not positive or `burst` is zero.
+    pub fn new(inner: T, requests_per_second: f64, burst: usize) -> Self {
+        assert!(requests_per_second > 0.0, "The rate of requests must be positive.");
+        assert!(burst > 0, "The burst must allow at least one request.");
+        Throttle {
+            inner,
+            bucket: Arc::new(Bucket {
+                rate: requests_per_second,
+                burst: burst as f64,
+                state: Mutex::new(BucketState {
+                    tokens: burst as f64,
+                    updated: Instant::now(),
+                }),
+            }),
+        }
+    }
+
+    /// Borrows the inner transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: Transport> Transport for Throttle<T> {
+    type Out = ThrottleResponse<T>;
+
+    fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+        self.inner.prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: rpc::Call) -> Self::Out {
+        let wait = self.bucket.reserve();
+        if wait == Duration::from_secs(0) {
+            return ThrottleResponse {
+                state: State::Sending(self.inner.send(id, request)),
+                transport: self.inner.clone(),
+                request: None,
+            };
+        }
+        log::trace!("[id:{}] rate limit exhausted, delaying request by {:?}", id, wait);
+        ThrottleResponse {
+            state: State::Waiting(Delay::new(wait)),
+            transport: self.inner.clone(),
+            request: Some((id, request)),
+        }
+    }
+}
+
+#[pin_project(project = StateProj)]
+#[derive(Debug)]
+enum State<F> {
+    Waiting(#[pin] Delay),
+    Sending(#[pin] F),
+}
+
+/// Response of `Throttle`, sending the request once the rate limit allows it.
+#[pin_project]
+#[derive(Debug)]
+pub struct ThrottleResponse<T: Transport> {
+    #[pin]
+    state: State<T::Out>,
+    transport: T,
+    request: Option<(RequestId, rpc::Call)>,
+}
+
+impl<T: Transport> Future for ThrottleResponse<T> {
+    type Output = error::Result<rpc::Value>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let mut this = self.project();
+        loop {
+            let next = match this.state.as_mut().project() {
+                StateProj::Waiting(delay) => {
+                    ready!(delay.poll(ctx));
+                    let (id, request) = this.request.take().expect("request is sent only once");
+                    State::Sending(this.transport.send(id, request))
+                }
+                StateProj::Sending(response) => return response.poll(ctx),
+            };
+            this.state.set(next);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Throttle;
+    use crate::{rpc::Value, transports::test::TestTransport, Transport};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn should_allow_bursts_and_delay_the_rest() {
+        // given
+        let transport = Throttle::new(TestTransport::default(), 10.0, 2);
+
+        // when
+        let first = transport.bucket.reserve();
+        let second = transport.bucket.reserve();
+        let third = transport.bucket.reserve();
+        let fourth = transport.bucket.reserve();
+
+        // then
+        assert_eq!(first, Duration::from_secs(0));
+        assert_eq!(second, Duration::from_secs(0));
+        assert!(third > Duration::from_millis(90) && third <= Duration::from_millis(100));
+        assert!(fourth > Duration::from_millis(190) && fourth <= Duration::from_millis(200));
+    }
+
+    #[test]
+    fn should_queue_requests_over_the_limit() {
+        // given
+        let mut inner = TestTransport::default();
+        inner.add_response(Value::String("a".into()));
+        inner.add_response(Value::String("b".into()));
+        let transport = Throttle::new(inner, 50.0, 1);
+        let started = Instant::now();
+
+        // when
+        let first = futures::executor::block_on(transport.execute("eth_test", vec![]));
+        let second = futures::executor::block_on(transport.execute("eth_test", vec![]));
+
+        // then
+        assert_eq!(first, Ok(Value::String("a".into())));
+        assert_eq!(second, Ok(Value::String("b".into())));
+        assert!(started.elapsed() >= Duration::from_millis(15));
+    }
+}
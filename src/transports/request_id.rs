@@ -0,0 +1,93 @@
+//! Request id generation.
+
+use crate::RequestId;
+use std::{
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Generates ids of JSON-RPC requests sent by a transport.
+///
+/// Ids have to be unique among the in-flight requests of a transport, otherwise responses
+/// can't be matched with their requests.
+///
+/// Ids are numbers (`RequestId` is `usize`), string ids like UUIDs can't be sent. To correlate requests with such
+/// ids, generate a number for every request and keep track of the correlation id it stands for.
+pub trait RequestIdGenerator: fmt::Debug + Send + Sync {
+    /// Returns the id to use for the next request.
+    fn next_id(&self) -> RequestId;
+}
+
+/// Generates consecutive ids starting from a given value.
+///
+/// This is the default generator of all transports.
+#[derive(Debug)]
+pub struct SequentialIds {
+    next: AtomicUsize,
+}
+
+impl SequentialIds {
+    /// Creates a new generator yielding `start`, `start + 1`, ...
+    pub fn new(start: RequestId) -> Self {
+        SequentialIds {
+            next: AtomicUsize::new(start),
+        }
+    }
+}
+
+impl RequestIdGenerator for SequentialIds {
+    fn next_id(&self) -> RequestId {
+        self.next.fetch_add(1, Ordering::AcqRel)
+    }
+}
+
+/// Generates ids by calling a user provided function, e.g. to use correlation ids shared with provider-side logs.
+pub struct CallbackIds<F> {
+    callback: F,
+}
+
+impl<F> CallbackIds<F>
+where
+    F: Fn() -> RequestId + Send + Sync,
+{
+    /// Creates a new generator yielding the values returned by `callback`.
+    pub fn new(callback: F) -> Self {
+        CallbackIds { callback }
+    }
+}
+
+impl<F> fmt::Debug for CallbackIds<F> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("CallbackIds").finish()
+    }
+}
+
+impl<F> RequestIdGenerator for CallbackIds<F>
+where
+    F: Fn() -> RequestId + Send + Sync,
+{
+    fn next_id(&self) -> RequestId {
+        (self.callback)()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequential_ids_start_at_given_value() {
+        let ids = SequentialIds::new(1000);
+        assert_eq!(ids.next_id(), 1000);
+        assert_eq!(ids.next_id(), 1001);
+        assert_eq!(ids.next_id(), 1002);
+    }
+
+    #[test]
+    fn callback_ids_are_provided_by_the_callback() {
+        let next = AtomicUsize::new(7);
+        let ids = CallbackIds::new(move || next.fetch_add(7, Ordering::SeqCst));
+        assert_eq!(ids.next_id(), 7);
+        assert_eq!(ids.next_id(), 14);
+    }
+}
@@ -0,0 +1,384 @@
+//! Transport retrying failed requests with exponential backoff.
+
+use crate::{
+    error::{self, TransportError},
+    rpc, Error, RequestId, Transport,
+};
+use futures::{
+    task::{Context, Poll},
+    Future,
+};
+use futures_timer::Delay;
+use pin_project::pin_project;
+use std::{
+    collections::hash_map::RandomState,
+    fmt,
+    hash::{BuildHasher, Hasher},
+    pin::Pin,
+    sync::Arc,
+    time::Duration,
+};
+
+/// JSON-RPC error code used by providers to signal exceeded rate limits.
+const RATE_LIMITED: i64 = -32005;
+
+/// Methods with side effects, which must not be sent again once the node may have processed them.
+pub(crate) const NON_IDEMPOTENT_METHODS: &[&str] = &[
+    "eth_sendRawTransaction",
+    "eth_sendTransaction",
+    "personal_sendTransaction",
+];
+
+/// Returns whether the error is likely transient, i.e. the request may succeed when retried.
+///
+/// These are transport-level failures (unreachable endpoint, timeouts, IO errors), HTTP `429` and `5xx` statuses
+/// and the `-32005` (rate limited) RPC error.
+pub fn is_transient(err: &Error) -> bool {
+    match err.root() {
+        Error::Unreachable | Error::Timeout | Error::Io(_) => true,
+        Error::Transport(TransportError::Code(code)) => *code == 429 || *code >= 500,
+        err => is_rate_limited(err),
+    }
+}
+
+// Returns whether the request was rejected by rate limiting, i.e. certainly not processed.
+fn is_rate_limited(err: &Error) -> bool {
+    match err.root() {
+        Error::Transport(TransportError::Code(code)) => *code == 429,
+        Error::Rpc(err) => err.code == rpc::ErrorCode::ServerError(RATE_LIMITED),
+        _ => false,
+    }
+}
+
+/// Returns whether a call to `method` failing with `err` may be sent again. This is the default predicate of `Retry`.
+///
+/// Transient errors (see `is_transient`) are retried, except for methods with side effects like
+/// `eth_sendRawTransaction`: the node may have accepted the request before failing, so those are only retried once
+/// rejected by rate limiting.
+pub fn is_retryable(method: &str, err: &Error) -> bool {
+    if NON_IDEMPOTENT_METHODS.contains(&method) {
+        is_rate_limited(err)
+    } else {
+        is_transient(err)
+    }
+}
+
+/// A transport retrying requests failing with a retryable error.
+///
+/// Every retry waits for a backoff doubling with each attempt (up to a maximum) and randomized by up to a half to
+/// avoid retrying in lockstep with other clients. Once the attempts are exhausted the last error is returned.
+#[derive(Debug, Clone)]
+pub struct Retry<T> {
+    inner: T,
+    policy: Arc<Policy>,
+}
+
+#[derive(Clone)]
+struct Policy {
+    max_attempts: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    retryable: Arc<dyn Fn(&str, &Error) -> bool + Send + Sync>,
+}
+
+impl fmt::Debug for Policy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Policy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .finish()
+    }
+}
+
+impl Policy {
+    fn backoff(&self, attempt: usize) -> Duration {
+        let factor = 1u32.checked_shl(attempt.saturating_sub(1) as u32).unwrap_or(u32::MAX);
+        let backoff = self
+            .initial_backoff
+            .checked_mul(factor)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff);
+        jitter(backoff)
+    }
+}
+
+// Returns a random duration between half of `backoff` and `backoff`.
+fn jitter(backoff: Duration) -> Duration {
+    let half = backoff / 2;
+    let random = RandomState::new().build_hasher().finish();
+    half + Duration::from_nanos(random % (half.as_nanos() as u64 + 1))
+}
+
+impl<T: Transport> Retry<T> {
+    /// Wraps the `inner` transport, sending every request at most `max_attempts` times.
+    ///
+    /// Retryable errors (see `is_retryable`) are retried, starting with a backoff of 100ms capped at 10s.
+    /// Panics if `max_attempts` is zero.
+    pub fn new(inner: T, max_attempts: usize) -> Self {
+        assert!(max_attempts > 0, "At least one attempt is required.");
+        Retry {
+            inner,
+            policy: Arc::new(Policy {
+                max_attempts,
+                initial_backoff: Duration::from_millis(100),
+                max_backoff: Duration::from_secs(10),
+                retryable: Arc::new(is_retryable),
+            }),
+        }
+    }
+
+    /// Wait `initial` before the first retry, doubling the backoff for every subsequent one up to `max`.
+    pub fn with_backoff(self, initial: Duration, max: Duration) -> Self {
+        self.with_policy(|policy| Policy {
+            initial_backoff: initial,
+            max_backoff: max,
+            ..policy
+        })
+    }
+
+    /// Retry only calls for which `retryable` returns `true`, given the called method and the error.
+    pub fn with_retryable<F>(self, retryable: F) -> Self
+    where
+        F: Fn(&str, &Error) -> bool + Send + Sync + 'static,
+    {
+        self.with_policy(|policy| Policy {
+            retryable: Arc::new(retryable),
+            ..policy
+        })
+    }
+
+    fn with_policy(self, update: impl FnOnce(Policy) -> Policy) -> Self {
+        Retry {
+            policy: Arc::new(update((*self.policy).clone())),
+            inner: self.inner,
+        }
+    }
+
+    /// Borrows the inner transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: Transport> Transport for Retry<T> {
+    type Out = RetryResponse<T>;
+
+    fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+        self.inner.prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: rpc::Call) -> Self::Out {
+        let method = match request {
+            rpc::Call::MethodCall(ref call) => call.method.clone(),
+            rpc::Call::Notification(ref notification) => notification.method.clone(),
+            rpc::Call::Invalid { .. } => String::new(),
+        };
+        RetryResponse {
+            state: State::Sending(self.inner.send(id, request.clone())),
+            transport: self.inner.clone(),
+            id,
+            method,
+            request,
+            attempt: 1,
+            policy: self.policy.clone(),
+        }
+    }
+}
+
+#[pin_project(project = StateProj)]
+#[derive(Debug)]
+enum State<F> {
+    Sending(#[pin] F),
+    Waiting(#[pin] Delay),
+}
+
+/// Response of `Retry`, resending the request when it fails with a retryable error.
+#[pin_project]
+#[derive(Debug)]
+pub struct RetryResponse<T: Transport> {
+    #[pin]
+    state: State<T::Out>,
+    transport: T,
+    id: RequestId,
+    method: String,
+    request: rpc::Call,
+    attempt: usize,
+    policy: Arc<Policy>,
+}
+
+impl<T: Transport> Future for RetryResponse<T> {
+    type Output = error::Result<rpc::Value>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let mut this = self.project();
+        loop {
+            let next = match this.state.as_mut().project() {
+                StateProj::Sending(response) => match ready!(response.poll(ctx)) {
+                    Err(ref err)
+                        if *this.attempt < this.policy.max_attempts && (this.policy.retryable)(this.method, err) =>
+                    {
+                        let backoff = this.policy.backoff(*this.attempt);
+                        log::debug!(
+                            "[id:{}] attempt {} failed with {}, retrying in {:?}",
+                            this.id,
+                            this.attempt,
+                            err,
+                            backoff
+                        );
+                        State::Waiting(Delay::new(backoff))
+                    }
+                    result => return Poll::Ready(result),
+                },
+                StateProj::Waiting(delay) => {
+                    ready!(delay.poll(ctx));
+                    *this.attempt += 1;
+                    State::Sending(this.transport.send(*this.id, this.request.clone()))
+                }
+            };
+            this.state.set(next);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_retryable, is_transient, Retry};
+    use crate::{
+        error::{self, TransportError},
+        rpc, Error, RequestId, Transport,
+    };
+    use futures::future::{self, Ready};
+    use std::{cell::RefCell, collections::VecDeque, rc::Rc, time::Duration};
+
+    #[derive(Debug, Clone, Default)]
+    struct ScriptedTransport {
+        results: Rc<RefCell<VecDeque<error::Result<rpc::Value>>>>,
+        sent: Rc<RefCell<usize>>,
+    }
+
+    impl ScriptedTransport {
+        fn new(results: Vec<error::Result<rpc::Value>>) -> Self {
+            ScriptedTransport {
+                results: Rc::new(RefCell::new(results.into())),
+                sent: Default::default(),
+            }
+        }
+    }
+
+    impl Transport for ScriptedTransport {
+        type Out = Ready<error::Result<rpc::Value>>;
+
+        fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+            (1, crate::helpers::build_request(1, method, params))
+        }
+
+        fn send(&self, _id: RequestId, _request: rpc::Call) -> Self::Out {
+            *self.sent.borrow_mut() += 1;
+            future::ready(self.results.borrow_mut().pop_front().unwrap_or(Err(Error::Unreachable)))
+        }
+    }
+
+    fn rpc_error(code: i64) -> Error {
+        Error::Rpc(rpc::Error {
+            code: rpc::ErrorCode::ServerError(code),
+            message: "error".into(),
+            data: None,
+        })
+    }
+
+    #[test]
+    fn should_retry_transient_errors() {
+        // given
+        let inner = ScriptedTransport::new(vec![
+            Err(Error::Timeout),
+            Err(rpc_error(-32005)),
+            Ok(rpc::Value::String("x".into())),
+        ]);
+        let transport = Retry::new(inner.clone(), 3).with_backoff(Duration::from_millis(1), Duration::from_millis(2));
+
+        // when
+        let result = futures::executor::block_on(transport.execute("eth_test", vec![]));
+
+        // then
+        assert_eq!(result, Ok(rpc::Value::String("x".into())));
+        assert_eq!(*inner.sent.borrow(), 3);
+    }
+
+    #[test]
+    fn should_return_last_error_when_attempts_are_exhausted() {
+        // given
+        let inner = ScriptedTransport::new(vec![Err(Error::Timeout), Err(Error::Unreachable)]);
+        let transport = Retry::new(inner.clone(), 2).with_backoff(Duration::from_millis(1), Duration::from_millis(2));
+
+        // when
+        let result = futures::executor::block_on(transport.execute("eth_test", vec![]));
+
+        // then
+        assert_eq!(result, Err(Error::Unreachable));
+        assert_eq!(*inner.sent.borrow(), 2);
+    }
+
+    #[test]
+    fn should_not_retry_permanent_errors() {
+        // given
+        let inner = ScriptedTransport::new(vec![Err(rpc_error(-32000))]);
+        let transport = Retry::new(inner.clone(), 3)
+            .with_retryable(|method, err| is_retryable(method, err) && !matches!(err, Error::Timeout))
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(2));
+
+        // when
+        let result = futures::executor::block_on(transport.execute("eth_test", vec![]));
+
+        // then
+        assert_eq!(result, Err(rpc_error(-32000)));
+        assert_eq!(*inner.sent.borrow(), 1);
+    }
+
+    #[test]
+    fn should_not_resend_transactions_the_node_may_have_accepted() {
+        // given
+        let inner = ScriptedTransport::new(vec![
+            Err(Error::Transport(TransportError::Code(429))),
+            Err(Error::Timeout),
+        ]);
+        let transport = Retry::new(inner.clone(), 3).with_backoff(Duration::from_millis(1), Duration::from_millis(2));
+
+        // when
+        let result = futures::executor::block_on(transport.execute("eth_sendRawTransaction", vec![]));
+
+        // then
+        assert_eq!(result, Err(Error::Timeout));
+        assert_eq!(*inner.sent.borrow(), 2);
+    }
+
+    #[test]
+    fn should_classify_retryable_errors() {
+        let message = Error::Transport(TransportError::Message("failed to deserialize".into()));
+
+        assert!(!is_transient(&message));
+        assert!(is_retryable("eth_blockNumber", &Error::Timeout));
+        assert!(is_retryable(
+            "eth_blockNumber",
+            &Error::Transport(TransportError::Code(503))
+        ));
+        assert!(!is_retryable("eth_sendTransaction", &Error::Unreachable));
+        assert!(!is_retryable(
+            "eth_sendRawTransaction",
+            &Error::Transport(TransportError::Code(503))
+        ));
+        assert!(is_retryable("eth_sendRawTransaction", &rpc_error(-32005)));
+    }
+
+    #[test]
+    fn should_classify_errors_wrapped_with_request_id() {
+        let wrap = |error| Error::Request {
+            id: 1,
+            error: Box::new(error),
+        };
+
+        assert!(is_retryable("eth_blockNumber", &wrap(Error::Timeout)));
+        assert!(!is_retryable("eth_sendTransaction", &wrap(Error::Unreachable)));
+        assert!(is_retryable("eth_sendRawTransaction", &wrap(rpc_error(-32005))));
+    }
+}
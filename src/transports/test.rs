@@ -1,20 +1,37 @@
 //! Test Transport
 
 use crate::{
+    api::SubscriptionId,
     error::{self, Error},
-    helpers, rpc, RequestId, Transport,
+    helpers, rpc, BatchTransport, DuplexTransport, RequestId, Transport,
+};
+use futures::{
+    channel::mpsc,
+    future::{self, BoxFuture, FutureExt, Ready},
+};
+use parking_lot::Mutex;
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, VecDeque},
+    rc::Rc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
 };
-use futures::future::{self, BoxFuture, FutureExt};
-use std::{cell::RefCell, collections::VecDeque, rc::Rc};
 
 type Result<T> = BoxFuture<'static, error::Result<T>>;
 
 /// Test Transport
+///
+/// Requests are recorded once prepared and responses are consumed once sent, so requests a wrapping transport
+/// prepares but answers itself (e.g. from a cache) are recorded without consuming a response.
 #[derive(Debug, Default, Clone)]
 pub struct TestTransport {
     asserted: usize,
     requests: Rc<RefCell<Vec<(String, Vec<rpc::Value>)>>>,
-    responses: Rc<RefCell<VecDeque<rpc::Value>>>,
+    responses: Rc<RefCell<VecDeque<error::Result<rpc::Value>>>>,
+    subscriptions: Rc<RefCell<BTreeMap<SubscriptionId, mpsc::UnboundedSender<rpc::Value>>>>,
 }
 
 impl Transport for TestTransport {
@@ -27,39 +44,96 @@ impl Transport for TestTransport {
     }
 
     fn send(&self, id: RequestId, request: rpc::Call) -> Result<rpc::Value> {
-        future::ready(match self.responses.borrow_mut().pop_front() {
-            Some(response) => Ok(response),
+        future::ready(self.next_response(id, request)).boxed()
+    }
+}
+
+impl BatchTransport for TestTransport {
+    type Batch = Result<Vec<error::Result<rpc::Value>>>;
+
+    fn send_batch<T>(&self, requests: T) -> Self::Batch
+    where
+        T: IntoIterator<Item = (RequestId, rpc::Call)>,
+    {
+        let responses = requests
+            .into_iter()
+            .map(|(id, request)| self.next_response(id, request))
+            .collect();
+        future::ready(Ok(responses)).boxed()
+    }
+}
+
+impl DuplexTransport for TestTransport {
+    type NotificationStream = mpsc::UnboundedReceiver<rpc::Value>;
+
+    fn subscribe(&self, id: SubscriptionId) -> error::Result<Self::NotificationStream> {
+        let (sink, stream) = mpsc::unbounded();
+        self.subscriptions.borrow_mut().insert(id, sink);
+        Ok(stream)
+    }
+
+    fn unsubscribe(&self, id: SubscriptionId) -> error::Result<()> {
+        self.subscriptions.borrow_mut().remove(&id);
+        Ok(())
+    }
+}
+
+impl TestTransport {
+    fn next_response(&self, id: RequestId, request: rpc::Call) -> error::Result<rpc::Value> {
+        match self.responses.borrow_mut().pop_front() {
+            Some(response) => response,
             None => {
                 println!("Unexpected request (id: {:?}): {:?}", id, request);
                 Err(Error::Unreachable)
             }
-        })
-        .boxed()
+        }
     }
-}
 
-impl TestTransport {
     /// Set response
     pub fn set_response(&mut self, value: rpc::Value) {
-        *self.responses.borrow_mut() = vec![value].into();
+        *self.responses.borrow_mut() = vec![Ok(value)].into();
     }
 
     /// Add response
     pub fn add_response(&mut self, value: rpc::Value) {
-        self.responses.borrow_mut().push_back(value);
+        self.responses.borrow_mut().push_back(Ok(value));
     }
 
-    /// Assert request
-    pub fn assert_request(&mut self, method: &str, params: &[String]) {
+    /// Add a failed response
+    pub fn add_error(&mut self, error: Error) {
+        self.responses.borrow_mut().push_back(Err(error));
+    }
+
+    // Returns the next request not asserted yet.
+    fn next_request(&mut self) -> (String, Vec<rpc::Value>) {
         let idx = self.asserted;
         self.asserted += 1;
+        self.requests.borrow().get(idx).expect("Expected result.").clone()
+    }
 
-        let (m, p) = self.requests.borrow().get(idx).expect("Expected result.").clone();
+    /// Assert request
+    pub fn assert_request(&mut self, method: &str, params: &[String]) {
+        let (m, p) = self.next_request();
         assert_eq!(&m, method);
         let p: Vec<String> = p.into_iter().map(|p| serde_json::to_string(&p).unwrap()).collect();
         assert_eq!(p, params);
     }
 
+    /// Assert request, comparing parameters as JSON values so that the order of object fields doesn't matter
+    pub fn assert_request_values(&mut self, method: &str, params: &[rpc::Value]) {
+        let (m, p) = self.next_request();
+        assert_eq!(&m, method);
+        assert_eq!(p, params);
+    }
+
+    /// Send a notification to the subscription with given id, returns whether it is subscribed
+    pub fn notify(&self, id: &str, value: rpc::Value) -> bool {
+        match self.subscriptions.borrow().get(&SubscriptionId::from(id.to_owned())) {
+            Some(sink) => sink.unbounded_send(value).is_ok(),
+            None => false,
+        }
+    }
+
     /// Assert no more requests
     pub fn assert_no_more_requests(&self) {
         let requests = self.requests.borrow();
@@ -71,3 +145,55 @@ impl TestTransport {
         );
     }
 }
+
+/// Transport answering every call with its method name and recording the size of each batch sent.
+///
+/// Unlike `TestTransport` it is `Send` and `Sync`, so it can be wrapped by transports requiring it.
+#[derive(Debug, Default, Clone)]
+pub struct EchoTransport {
+    next_id: Arc<AtomicUsize>,
+    batches: Arc<Mutex<Vec<usize>>>,
+}
+
+impl EchoTransport {
+    fn echo(request: rpc::Call) -> error::Result<rpc::Value> {
+        match request {
+            rpc::Call::MethodCall(call) => Ok(rpc::Value::String(call.method)),
+            _ => Err(Error::Internal),
+        }
+    }
+
+    /// Sizes of the batches sent so far
+    pub fn batches(&self) -> Vec<usize> {
+        self.batches.lock().clone()
+    }
+}
+
+impl Transport for EchoTransport {
+    type Out = Ready<error::Result<rpc::Value>>;
+
+    fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        (id, helpers::build_request(id, method, params))
+    }
+
+    fn send(&self, _id: RequestId, request: rpc::Call) -> Self::Out {
+        future::ready(Self::echo(request))
+    }
+}
+
+impl BatchTransport for EchoTransport {
+    type Batch = Ready<error::Result<Vec<error::Result<rpc::Value>>>>;
+
+    fn send_batch<T>(&self, requests: T) -> Self::Batch
+    where
+        T: IntoIterator<Item = (RequestId, rpc::Call)>,
+    {
+        let results = requests
+            .into_iter()
+            .map(|(_, request)| Self::echo(request))
+            .collect::<Vec<_>>();
+        self.batches.lock().push(results.len());
+        future::ready(Ok(results))
+    }
+}
@@ -0,0 +1,205 @@
+//! Transport limiting the number of concurrent requests.
+
+use crate::{error, rpc, RequestId, Transport};
+use futures::{
+    task::{Context, Poll, Waker},
+    Future,
+};
+use parking_lot::Mutex;
+use pin_project::pin_project;
+use std::{collections::VecDeque, pin::Pin, sync::Arc};
+
+/// A transport allowing at most a fixed number of requests to be in flight at the same time.
+///
+/// Requests above the limit are queued and sent once one of the in-flight requests completes, which smooths
+/// out bursts (e.g. hundreds of calls fanned out with `join_all`) that could otherwise trip the rate limits
+/// of a provider. Note that a request is only sent once its future is polled.
+#[derive(Debug, Clone)]
+pub struct ThrottledTransport<T> {
+    inner: T,
+    limiter: Arc<Limiter>,
+}
+
+#[derive(Debug)]
+struct Limiter {
+    limit: usize,
+    state: Mutex<LimiterState>,
+}
+
+#[derive(Debug, Default)]
+struct LimiterState {
+    in_flight: usize,
+    waiting: VecDeque<Waker>,
+}
+
+impl Limiter {
+    fn try_acquire(self: &Arc<Self>, waker: &Waker) -> Option<Permit> {
+        let mut state = self.state.lock();
+        if state.in_flight < self.limit {
+            state.in_flight += 1;
+            Some(Permit(self.clone()))
+        } else {
+            state.waiting.push_back(waker.clone());
+            None
+        }
+    }
+
+    fn release(&self) {
+        let waiting = {
+            let mut state = self.state.lock();
+            state.in_flight -= 1;
+            std::mem::take(&mut state.waiting)
+        };
+        // Waiting requests may have been dropped in the meantime, so all of them get a chance to take the slot.
+        for waker in waiting {
+            waker.wake();
+        }
+    }
+}
+
+/// A slot of the limiter, released when dropped.
+#[derive(Debug)]
+struct Permit(Arc<Limiter>);
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.0.release();
+    }
+}
+
+impl<T: Transport> ThrottledTransport<T> {
+    /// Wraps the `inner` transport, allowing at most `limit` concurrent requests.
+    ///
+    /// Panics if `limit` is zero.
+    pub fn new(inner: T, limit: usize) -> Self {
+        assert!(limit > 0, "The limit of concurrent requests must be positive.");
+        ThrottledTransport {
+            inner,
+            limiter: Arc::new(Limiter {
+                limit,
+                state: Mutex::new(LimiterState::default()),
+            }),
+        }
+    }
+
+    /// Returns the number of requests currently in flight.
+    pub fn in_flight(&self) -> usize {
+        self.limiter.state.lock().in_flight
+    }
+
+    /// Borrows the inner transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: Transport> Transport for ThrottledTransport<T> {
+    type Out = ThrottledResponse<T, T::Out>;
+
+    fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+        self.inner.prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: rpc::Call) -> Self::Out {
+        ThrottledResponse {
+            transport: self.inner.clone(),
+            request: Some((id, request)),
+            inner: None,
+            permit: None,
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+/// Response of `ThrottledTransport`, sending the request once a slot is available.
+#[pin_project]
+#[derive(Debug)]
+pub struct ThrottledResponse<T, F> {
+    transport: T,
+    request: Option<(RequestId, rpc::Call)>,
+    #[pin]
+    inner: Option<F>,
+    permit: Option<Permit>,
+    limiter: Arc<Limiter>,
+}
+
+impl<T> Future for ThrottledResponse<T, T::Out>
+where
+    T: Transport,
+{
+    type Output = error::Result<rpc::Value>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let mut this = self.project();
+        if this.inner.is_none() {
+            let permit = match this.limiter.try_acquire(ctx.waker()) {
+                Some(permit) => permit,
+                None => return Poll::Pending,
+            };
+            let (id, request) = this.request.take().expect("ThrottledResponse polled after completion");
+            *this.permit = Some(permit);
+            this.inner.set(Some(this.transport.send(id, request)));
+        }
+        let result = ready!(this.inner.as_pin_mut().expect("inner future is set above").poll(ctx));
+        this.permit.take();
+        Poll::Ready(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ThrottledTransport;
+    use crate::{error, rpc, RequestId, Transport};
+    use futures::future::BoxFuture;
+    use futures_timer::Delay;
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    #[derive(Debug, Clone, Default)]
+    struct SlowTransport {
+        active: Arc<AtomicUsize>,
+        max_active: Arc<AtomicUsize>,
+    }
+
+    impl Transport for SlowTransport {
+        type Out = BoxFuture<'static, error::Result<rpc::Value>>;
+
+        fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+            (1, crate::helpers::build_request(1, method, params))
+        }
+
+        fn send(&self, _id: RequestId, _request: rpc::Call) -> Self::Out {
+            let active = self.active.clone();
+            let max_active = self.max_active.clone();
+            Box::pin(async move {
+                let now_active = active.fetch_add(1, Ordering::SeqCst) + 1;
+                max_active.fetch_max(now_active, Ordering::SeqCst);
+                Delay::new(Duration::from_millis(10)).await;
+                active.fetch_sub(1, Ordering::SeqCst);
+                Ok(rpc::Value::Null)
+            })
+        }
+    }
+
+    #[test]
+    fn should_limit_concurrent_requests() {
+        // given
+        let inner = SlowTransport::default();
+        let transport = ThrottledTransport::new(inner.clone(), 3);
+
+        // when
+        let calls = (0..10).map(|_| transport.execute("eth_test", vec![]));
+        let results = futures::executor::block_on(futures::future::join_all(calls));
+
+        // then
+        assert_eq!(results.len(), 10);
+        assert!(results.iter().all(|result| *result == Ok(rpc::Value::Null)));
+        assert_eq!(inner.max_active.load(Ordering::SeqCst), 3);
+        assert_eq!(transport.in_flight(), 0);
+    }
+}
@@ -0,0 +1,140 @@
+//! Transport bounding how long requests may take.
+
+use crate::{error, rpc, BatchTransport, Error, RequestId, Transport};
+use futures::{
+    task::{Context, Poll},
+    Future,
+};
+use futures_timer::Delay;
+use pin_project::pin_project;
+use std::{pin::Pin, time::Duration};
+
+/// A transport resolving requests (and batches) with `Error::Timeout` if the inner transport doesn't respond in time.
+///
+/// This works with any transport, e.g. to avoid hanging forever on a dead socket. The underlying request is
+/// dropped on timeout, which may or may not cancel it depending on the transport.
+#[derive(Debug, Clone)]
+pub struct Timeout<T> {
+    inner: T,
+    timeout: Duration,
+}
+
+impl<T: Transport> Timeout<T> {
+    /// Wraps the `inner` transport, failing requests not completed within `timeout`.
+    pub fn new(inner: T, timeout: Duration) -> Self {
+        Timeout { inner, timeout }
+    }
+
+    /// Borrows the inner transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: Transport> Transport for Timeout<T> {
+    type Out = TimeoutResponse<T::Out>;
+
+    fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+        self.inner.prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: rpc::Call) -> Self::Out {
+        TimeoutResponse::new(self.inner.send(id, request), self.timeout)
+    }
+}
+
+impl<T: BatchTransport> BatchTransport for Timeout<T> {
+    type Batch = TimeoutResponse<T::Batch>;
+
+    fn send_batch<I>(&self, requests: I) -> Self::Batch
+    where
+        I: IntoIterator<Item = (RequestId, rpc::Call)>,
+    {
+        TimeoutResponse::new(self.inner.send_batch(requests), self.timeout)
+    }
+}
+
+/// Response of `Timeout`, failing with `Error::Timeout` once the deadline passes.
+#[pin_project]
+#[derive(Debug)]
+pub struct TimeoutResponse<F> {
+    #[pin]
+    inner: F,
+    #[pin]
+    delay: Delay,
+}
+
+impl<F> TimeoutResponse<F> {
+    /// Bounds how long the `inner` future may take, e.g. a request sent by a transport with a built-in timeout.
+    pub(crate) fn new(inner: F, timeout: Duration) -> Self {
+        TimeoutResponse {
+            inner,
+            delay: Delay::new(timeout),
+        }
+    }
+}
+
+impl<F, O> Future for TimeoutResponse<F>
+where
+    F: Future<Output = error::Result<O>>,
+{
+    type Output = error::Result<O>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        if let Poll::Ready(result) = this.inner.poll(ctx) {
+            return Poll::Ready(result);
+        }
+        ready!(this.delay.poll(ctx));
+        Poll::Ready(Err(Error::Timeout))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Timeout;
+    use crate::{error, rpc, transports::test::TestTransport, Error, RequestId, Transport};
+    use futures::future::{self, Pending};
+    use std::time::Duration;
+
+    #[derive(Debug, Clone)]
+    struct DeadTransport;
+
+    impl Transport for DeadTransport {
+        type Out = Pending<error::Result<rpc::Value>>;
+
+        fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+            (1, crate::helpers::build_request(1, method, params))
+        }
+
+        fn send(&self, _id: RequestId, _request: rpc::Call) -> Self::Out {
+            future::pending()
+        }
+    }
+
+    #[test]
+    fn should_time_out_hanging_requests() {
+        // given
+        let transport = Timeout::new(DeadTransport, Duration::from_millis(10));
+
+        // when
+        let result = futures::executor::block_on(transport.execute("eth_blockNumber", vec![]));
+
+        // then
+        assert_eq!(result, Err(Error::Timeout));
+    }
+
+    #[test]
+    fn should_pass_through_timely_responses() {
+        // given
+        let mut inner = TestTransport::default();
+        inner.add_response(rpc::Value::String("0x1".into()));
+        let transport = Timeout::new(inner, Duration::from_secs(10));
+
+        // when
+        let result = futures::executor::block_on(transport.execute("eth_blockNumber", vec![]));
+
+        // then
+        assert_eq!(result, Ok(rpc::Value::String("0x1".into())));
+    }
+}
@@ -0,0 +1,286 @@
+//! Transport creating a `tracing` span for every request.
+
+use crate::{error, rpc, BatchTransport, RequestId, Transport};
+use futures::{
+    task::{Context, Poll},
+    Future,
+};
+use pin_project::pin_project;
+use std::{pin::Pin, sync::Arc};
+use tracing::{field, Span};
+
+/// A transport instrumenting every request with an `rpc` span of the `tracing` crate.
+///
+/// The span carries the `method`, request `id`, serialized `params_size` (in bytes) and `endpoint` (if configured),
+/// and records the `outcome` (`ok` or `error`, with the `error` itself) once the request resolves. Batches get a
+/// single `rpc_batch` span with the number of requests in `size` instead.
+///
+/// Spans are children of the span current when the request is sent. The only span opened by the library itself is
+/// the one of `send_transaction_with_confirmation`, requests of other calls are grouped only under spans of the
+/// caller, e.g. with `tracing::Instrument`.
+#[derive(Debug, Clone)]
+pub struct Traced<T> {
+    inner: T,
+    endpoint: Option<Arc<str>>,
+}
+
+impl<T: Transport> Traced<T> {
+    /// Wraps the `inner` transport.
+    pub fn new(inner: T) -> Self {
+        Traced { inner, endpoint: None }
+    }
+
+    /// Record `endpoint` (e.g. the URL or name of the node, without credentials) in every span.
+    pub fn with_endpoint(self, endpoint: &str) -> Self {
+        Traced {
+            endpoint: Some(endpoint.into()),
+            ..self
+        }
+    }
+
+    /// Borrows the inner transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: Transport> Transport for Traced<T> {
+    type Out = TracedResponse<T::Out>;
+
+    fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+        self.inner.prepare(method, params)
+    }
+
+    fn send(&self, id: RequestId, request: rpc::Call) -> Self::Out {
+        let (method, params) = match request {
+            rpc::Call::MethodCall(ref call) => (call.method.as_str(), Some(&call.params)),
+            rpc::Call::Notification(ref notification) => (notification.method.as_str(), Some(&notification.params)),
+            rpc::Call::Invalid { .. } => ("invalid", None),
+        };
+        let params_size = params
+            .and_then(|params| serde_json::to_vec(params).ok())
+            .map_or(0, |params| params.len());
+        let span = tracing::info_span!(
+            "rpc",
+            method,
+            id,
+            params_size,
+            endpoint = self.endpoint.as_deref().unwrap_or(""),
+            outcome = field::Empty,
+            error = field::Empty,
+        );
+        let inner = {
+            let _enter = span.enter();
+            self.inner.send(id, request)
+        };
+        TracedResponse { inner, span }
+    }
+}
+
+impl<T: BatchTransport> BatchTransport for Traced<T> {
+    type Batch = TracedResponse<T::Batch>;
+
+    fn send_batch<I>(&self, requests: I) -> Self::Batch
+    where
+        I: IntoIterator<Item = (RequestId, rpc::Call)>,
+    {
+        let requests = requests.into_iter().collect::<Vec<_>>();
+        let span = tracing::info_span!(
+            "rpc_batch",
+            size = requests.len(),
+            endpoint = self.endpoint.as_deref().unwrap_or(""),
+            outcome = field::Empty,
+            error = field::Empty,
+        );
+        let inner = {
+            let _enter = span.enter();
+            self.inner.send_batch(requests)
+        };
+        TracedResponse { inner, span }
+    }
+}
+
+/// Response of `Traced`, polled within the span of the request or batch.
+#[pin_project]
+#[derive(Debug)]
+pub struct TracedResponse<F> {
+    #[pin]
+    inner: F,
+    span: Span,
+}
+
+impl<F, O> Future for TracedResponse<F>
+where
+    F: Future<Output = error::Result<O>>,
+{
+    type Output = error::Result<O>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        let _enter = this.span.enter();
+        let result = ready!(this.inner.poll(ctx));
+        match result {
+            Ok(_) => {
+                this.span.record("outcome", "ok");
+            }
+            Err(ref err) => {
+                this.span.record("outcome", "error");
+                this.span.record("error", field::display(err));
+            }
+        }
+        Poll::Ready(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Traced;
+    use crate::{rpc::Value, transports::test::TestTransport, BatchTransport, Error, Transport};
+    use parking_lot::Mutex;
+    use std::{collections::BTreeMap, fmt, sync::Arc};
+    use tracing::{
+        field::{Field, Visit},
+        span, Event, Metadata, Subscriber,
+    };
+
+    type Fields = BTreeMap<&'static str, String>;
+
+    struct Visitor<'a>(&'a mut Fields);
+
+    // Collects names and fields of all spans.
+    #[derive(Default, Clone)]
+    struct Recorder {
+        spans: Arc<Mutex<Vec<(&'static str, Fields)>>>,
+    }
+
+    impl Recorder {
+        fn spans(&self) -> Vec<(&'static str, Fields)> {
+            self.spans.lock().clone()
+        }
+    }
+
+    impl Visit for Visitor<'_> {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name(), value.into());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            self.0.insert(field.name(), format!("{:?}", value));
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _metadata: &Metadata) -> bool {
+            true
+        }
+
+        fn new_span(&self, attributes: &span::Attributes) -> span::Id {
+            let mut fields = Fields::new();
+            attributes.record(&mut Visitor(&mut fields));
+            let mut spans = self.spans.lock();
+            spans.push((attributes.metadata().name(), fields));
+            span::Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, span: &span::Id, values: &span::Record) {
+            values.record(&mut Visitor(&mut self.spans.lock()[span.into_u64() as usize - 1].1));
+        }
+
+        fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+        fn event(&self, _event: &Event) {}
+
+        fn enter(&self, _span: &span::Id) {}
+
+        fn exit(&self, _span: &span::Id) {}
+    }
+
+    #[test]
+    fn should_pass_through_responses() {
+        // given
+        let mut inner = TestTransport::default();
+        inner.add_response(Value::String("0x1".into()));
+        let transport = Traced::new(inner.clone()).with_endpoint("http://localhost:8545");
+
+        // when
+        let ok = futures::executor::block_on(transport.execute("eth_getBalance", vec![Value::String("latest".into())]));
+        let failed = futures::executor::block_on(transport.execute("eth_blockNumber", vec![]));
+
+        // then
+        assert_eq!(ok, Ok(Value::String("0x1".into())));
+        assert_eq!(failed, Err(Error::Unreachable));
+        assert_eq!(transport.endpoint.as_deref(), Some("http://localhost:8545"));
+        inner.assert_request("eth_getBalance", &[r#""latest""#.into()]);
+        inner.assert_request("eth_blockNumber", &[]);
+        inner.assert_no_more_requests();
+    }
+
+    #[test]
+    fn should_record_request_in_span() {
+        // given
+        let recorder = Recorder::default();
+        let mut inner = TestTransport::default();
+        inner.add_response(Value::String("0x1".into()));
+        let transport = Traced::new(inner).with_endpoint("http://localhost:8545");
+
+        // when
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let ok = futures::executor::block_on(transport.execute("eth_getBalance", vec![]));
+            let failed = futures::executor::block_on(transport.execute("eth_blockNumber", vec![]));
+            assert!(ok.is_ok());
+            assert!(failed.is_err());
+        });
+
+        // then
+        let spans = recorder.spans();
+        assert_eq!(spans.len(), 2);
+        let (name, ref fields) = spans[0];
+        assert_eq!(name, "rpc");
+        assert_eq!(fields["method"], "eth_getBalance");
+        assert_eq!(fields["id"], "1");
+        assert_eq!(fields["params_size"], "2");
+        assert_eq!(fields["endpoint"], "http://localhost:8545");
+        assert_eq!(fields["outcome"], "ok");
+        assert!(!fields.contains_key("error"));
+        let (name, ref fields) = spans[1];
+        assert_eq!(name, "rpc");
+        assert_eq!(fields["method"], "eth_blockNumber");
+        assert_eq!(fields["id"], "2");
+        assert_eq!(fields["outcome"], "error");
+        assert_eq!(fields["error"], Error::Unreachable.to_string());
+    }
+
+    #[test]
+    fn should_record_batch_in_span() {
+        // given
+        let recorder = Recorder::default();
+        let mut inner = TestTransport::default();
+        inner.add_response(Value::String("0x1".into()));
+        inner.add_response(Value::String("0x2".into()));
+        let transport = Traced::new(inner.clone());
+        let requests = vec![
+            transport.prepare("eth_blockNumber", vec![]),
+            transport.prepare("eth_chainId", vec![]),
+        ];
+
+        // when
+        let result = tracing::subscriber::with_default(recorder.clone(), || {
+            futures::executor::block_on(transport.send_batch(requests))
+        });
+
+        // then
+        assert_eq!(
+            result,
+            Ok(vec![Ok(Value::String("0x1".into())), Ok(Value::String("0x2".into()))])
+        );
+        let spans = recorder.spans();
+        assert_eq!(spans.len(), 1);
+        let (name, ref fields) = spans[0];
+        assert_eq!(name, "rpc_batch");
+        assert_eq!(fields["size"], "2");
+        assert_eq!(fields["outcome"], "ok");
+        inner.assert_request("eth_blockNumber", &[]);
+        inner.assert_request("eth_chainId", &[]);
+        inner.assert_no_more_requests();
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{signing::keccak256, types::Address};
+
+/// Error parsing a checksummed address.
+#[derive(Debug, derive_more::Display, PartialEq, Clone)]
+pub enum ParseChecksumError {
+    /// The string is not a `0x`-prefixed, 40 hex characters long address.
+    #[display(fmt = "Address has to be a 0x-prefixed, 40 hex characters long string.")]
+    InvalidAddress,
+    /// The casing of the address doesn't match its EIP-55 checksum.
+    #[display(fmt = "Address checksum does not match.")]
+    ChecksumMismatch,
+}
+impl std::error::Error for ParseChecksumError {}
+
+/// [EIP-55](https://eips.ethereum.org/EIPS/eip-55) mixed-case checksum encoding of addresses.
+pub trait Checksum: Sized {
+    /// Formats the address as a `0x`-prefixed hex string with checksum casing.
+    fn to_checksum(&self) -> String;
+
+    /// Parses a `0x`-prefixed hex address and validates its checksum casing.
+    ///
+    /// All-lowercase and all-uppercase strings carry no checksum and are rejected as well,
+    /// unless they happen to match the checksum casing.
+    fn from_checksum_str(s: &str) -> Result<Self, ParseChecksumError>;
+}
+
+impl Checksum for Address {
+    fn to_checksum(&self) -> String {
+        let address = hex::encode(self.as_bytes());
+        let hash = keccak256(address.as_bytes());
+
+        let mut checksummed = String::with_capacity(2 + address.len());
+        checksummed.push_str("0x");
+        for (i, c) in address.chars().enumerate() {
+            let nibble = if i % 2 == 0 {
+                hash[i / 2] >> 4
+            } else {
+                hash[i / 2] & 0x0f
+            };
+            if nibble >= 8 {
+                checksummed.push(c.to_ascii_uppercase());
+            } else {
+                checksummed.push(c);
+            }
+        }
+        checksummed
+    }
+
+    fn from_checksum_str(s: &str) -> Result<Self, ParseChecksumError> {
+        let digits = s.strip_prefix("0x").ok_or(ParseChecksumError::InvalidAddress)?;
+        let mut bytes = [0u8; 20];
+        hex::decode_to_slice(digits, &mut bytes).map_err(|_| ParseChecksumError::InvalidAddress)?;
+
+        let address = Address::from(bytes);
+        if address.to_checksum() != s {
+            return Err(ParseChecksumError::ChecksumMismatch);
+        }
+        Ok(address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    const CHECKSUMMED: [&str; 4] = [
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ];
+
+    #[test]
+    fn to_checksum() {
+        for checksummed in CHECKSUMMED.iter() {
+            let address: Address = checksummed[2..].to_lowercase().parse().unwrap();
+            assert_eq!(address.to_checksum(), *checksummed);
+        }
+    }
+
+    #[test]
+    fn from_checksum_str() {
+        for checksummed in CHECKSUMMED.iter() {
+            let address = Address::from_checksum_str(checksummed).unwrap();
+            assert_eq!(address.to_checksum(), *checksummed);
+        }
+        assert_eq!(
+            Address::from_checksum_str(CHECKSUMMED[0]),
+            Ok(hex!("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").into())
+        );
+    }
+
+    #[test]
+    fn from_checksum_str_rejects_wrong_casing() {
+        assert_eq!(
+            Address::from_checksum_str("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD"),
+            Err(ParseChecksumError::ChecksumMismatch)
+        );
+        assert_eq!(
+            Address::from_checksum_str("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"),
+            Err(ParseChecksumError::ChecksumMismatch)
+        );
+    }
+
+    #[test]
+    fn from_checksum_str_rejects_invalid_address() {
+        for invalid in &[
+            "5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA",
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAedAA",
+            "0xzaAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        ] {
+            assert_eq!(
+                Address::from_checksum_str(invalid),
+                Err(ParseChecksumError::InvalidAddress)
+            );
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use serde::{
-    de::{Error, Unexpected, Visitor},
+    de::{Error, Visitor},
     Deserialize, Deserializer, Serialize, Serializer,
 };
 use std::fmt;
@@ -40,19 +40,17 @@ impl<'a> Visitor<'a> for BytesVisitor {
     type Value = Bytes;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        write!(formatter, "a 0x-prefixed hex-encoded vector of bytes")
+        write!(formatter, "a hex-encoded vector of bytes, optionally 0x-prefixed")
     }
 
     fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
     where
         E: Error,
     {
-        if value.len() >= 2 && &value[0..2] == "0x" {
-            let bytes = hex::decode(&value[2..]).map_err(|e| Error::custom(format!("Invalid hex: {}", e)))?;
-            Ok(Bytes(bytes))
-        } else {
-            Err(Error::invalid_value(Unexpected::Str(value), &"0x prefix"))
-        }
+        // Some nodes omit the prefix, in particular for empty data.
+        let value = value.strip_prefix("0x").unwrap_or(value);
+        let bytes = hex::decode(value).map_err(|e| Error::custom(format!("Invalid hex: {}", e)))?;
+        Ok(Bytes(bytes))
     }
 
     fn visit_string<E>(self, value: String) -> Result<Self::Value, E>
@@ -62,3 +60,39 @@ impl<'a> Visitor<'a> for BytesVisitor {
         self.visit_str(value.as_ref())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_and_deserialize() {
+        let bytes = Bytes(vec![0x01, 0x23, 0xab]);
+        let serialized = serde_json::to_string(&bytes).unwrap();
+        assert_eq!(serialized, r#""0x0123ab""#);
+        assert_eq!(serde_json::from_str::<Bytes>(&serialized).unwrap(), bytes);
+    }
+
+    #[test]
+    fn serialize_and_deserialize_empty() {
+        let bytes = Bytes::default();
+        let serialized = serde_json::to_string(&bytes).unwrap();
+        assert_eq!(serialized, r#""0x""#);
+        assert_eq!(serde_json::from_str::<Bytes>(&serialized).unwrap(), bytes);
+        assert_eq!(serde_json::from_str::<Bytes>(r#""""#).unwrap(), bytes);
+    }
+
+    #[test]
+    fn deserialize_without_prefix() {
+        assert_eq!(
+            serde_json::from_str::<Bytes>(r#""0123ab""#).unwrap(),
+            Bytes(vec![0x01, 0x23, 0xab])
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid_hex() {
+        assert!(serde_json::from_str::<Bytes>(r#""0x0g""#).is_err());
+        assert!(serde_json::from_str::<Bytes>(r#""0x123""#).is_err());
+    }
+}
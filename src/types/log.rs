@@ -166,6 +166,41 @@ impl FilterBuilder {
         )
     }
 
+    /// Sets the topics matching `event` with the given values of its indexed parameters, in order.
+    ///
+    /// `None` matches any value of the parameter. For non-anonymous events `topic0` is set to the event signature.
+    /// Fails if the number of values doesn't match the number of indexed parameters or a value doesn't match the type
+    /// of its parameter.
+    pub fn event_topics(self, event: &ethabi::Event, values: Vec<Option<ethabi::Token>>) -> ethabi::Result<Self> {
+        let indexed = event.inputs.iter().filter(|input| input.indexed).collect::<Vec<_>>();
+        if indexed.len() != values.len() {
+            return Err(ethabi::Error::InvalidData);
+        }
+        let mut topics = vec![];
+        if !event.anonymous {
+            topics.push(Some(vec![event.signature()]));
+        }
+        for (param, value) in indexed.into_iter().zip(values) {
+            let topic = match value {
+                Some(token) if token.type_check(&param.kind) => Some(vec![encode_topic(&token)]),
+                Some(_) => return Err(ethabi::Error::InvalidData),
+                None => None,
+            };
+            topics.push(topic);
+        }
+        if topics.len() > 4 {
+            return Err(ethabi::Error::InvalidData);
+        }
+        topics.resize(4, None);
+        let mut topics = topics.into_iter();
+        Ok(self.topics(
+            topics.next().flatten(),
+            topics.next().flatten(),
+            topics.next().flatten(),
+            topics.next().flatten(),
+        ))
+    }
+
     /// Limit the result
     pub fn limit(mut self, limit: usize) -> Self {
         self.filter.limit = Some(limit);
@@ -178,6 +213,41 @@ impl FilterBuilder {
     }
 }
 
+/// Encodes the value of an indexed event parameter into its topic.
+///
+/// Value types are padded to 32 bytes, while `string`, `bytes`, arrays and tuples are stored as the keccak256 hash of
+/// their in-place encoding, as specified by Solidity.
+pub fn encode_topic(token: &ethabi::Token) -> H256 {
+    let hash = |bytes: &[u8]| -> H256 { crate::signing::keccak256(bytes).into() };
+    match token {
+        ethabi::Token::String(string) => hash(string.as_bytes()),
+        ethabi::Token::Bytes(bytes) => hash(bytes),
+        ethabi::Token::Array(_) | ethabi::Token::FixedArray(_) | ethabi::Token::Tuple(_) => {
+            hash(&encode_in_place(token))
+        }
+        token => H256::from_slice(&ethabi::encode(std::slice::from_ref(token))),
+    }
+}
+
+/// Encoding of a topic value nested in an array or tuple before hashing: byte strings without length, padded to a
+/// multiple of 32 bytes, and the elements of arrays and tuples concatenated.
+fn encode_in_place(token: &ethabi::Token) -> Vec<u8> {
+    match token {
+        ethabi::Token::String(string) => pad_right(string.as_bytes()),
+        ethabi::Token::Bytes(bytes) => pad_right(bytes),
+        ethabi::Token::Array(tokens) | ethabi::Token::FixedArray(tokens) | ethabi::Token::Tuple(tokens) => {
+            tokens.iter().flat_map(encode_in_place).collect()
+        }
+        token => ethabi::encode(std::slice::from_ref(token)),
+    }
+}
+
+fn pad_right(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = bytes.to_vec();
+    padded.resize(bytes.len().div_ceil(32) * 32, 0);
+    padded
+}
+
 /// Converts a `Topic` to an equivalent `Option<Vec<T>>`, suitable for `FilterBuilder::topics`
 fn topic_to_option<T>(topic: ethabi::Topic<T>) -> Option<Vec<T>> {
     match topic {
@@ -190,7 +260,7 @@ fn topic_to_option<T>(topic: ethabi::Topic<T>) -> Option<Vec<T>> {
 #[cfg(test)]
 mod tests {
     use crate::types::{
-        log::{FilterBuilder, Log},
+        log::{encode_topic, FilterBuilder, Log},
         Address, H160, H256,
     };
     use hex_literal::hex;
@@ -304,4 +374,62 @@ mod tests {
             .build();
         assert_eq!(filter0, filter1);
     }
+
+    #[test]
+    fn should_build_erc20_transfer_topics() {
+        let param = |name: &str, kind, indexed| ethabi::EventParam {
+            name: name.into(),
+            kind,
+            indexed,
+        };
+        let transfer = ethabi::Event {
+            name: "Transfer".into(),
+            inputs: vec![
+                param("from", ethabi::ParamType::Address, true),
+                param("to", ethabi::ParamType::Address, true),
+                param("value", ethabi::ParamType::Uint(256), false),
+            ],
+            anonymous: false,
+        };
+        let from = Address::from_low_u64_be(0x123);
+
+        let filter = FilterBuilder::default()
+            .event_topics(&transfer, vec![Some(ethabi::Token::Address(from)), None])
+            .unwrap()
+            .build();
+
+        let expected = FilterBuilder::default()
+            .topics(
+                Some(vec![hex!(
+                    "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+                )
+                .into()]),
+                Some(vec![hex!(
+                    "0000000000000000000000000000000000000000000000000000000000000123"
+                )
+                .into()]),
+                None,
+                None,
+            )
+            .build();
+        assert_eq!(filter, expected);
+        assert!(FilterBuilder::default()
+            .event_topics(&transfer, vec![Some(ethabi::Token::Bool(true)), None])
+            .is_err());
+        assert!(FilterBuilder::default().event_topics(&transfer, vec![None]).is_err());
+    }
+
+    #[test]
+    fn should_hash_dynamic_topics() {
+        // keccak256("hello")
+        assert_eq!(
+            encode_topic(&ethabi::Token::String("hello".into())),
+            hex!("1c8aff950685c2ed4bc3174f3472287b56d9517b9c948127319a09a7a36deac8").into()
+        );
+        assert_eq!(
+            encode_topic(&ethabi::Token::Bytes(b"hello".to_vec())),
+            encode_topic(&ethabi::Token::String("hello".into()))
+        );
+        assert_eq!(encode_topic(&ethabi::Token::Uint(1.into())), H256::from_low_u64_be(1));
+    }
 }
@@ -1,15 +1,19 @@
 //! Web3 Types
 
+mod address;
 mod block;
 mod bytes;
 mod bytes_array;
 mod fee_history;
 mod log;
+mod node_health;
+mod parity_info;
 mod parity_peers;
 mod parity_pending_transaction;
 mod proof;
 mod recovery;
 mod signed;
+mod state_override;
 mod sync_state;
 mod trace_filtering;
 mod traces;
@@ -17,24 +21,29 @@ mod transaction;
 mod transaction_id;
 mod transaction_request;
 mod txpool;
+mod typed_data;
 mod uint;
 mod work;
 
 pub use self::{
+    address::{Checksum, ParseChecksumError},
     block::{Block, BlockHeader, BlockId, BlockNumber},
     bytes::Bytes,
     bytes_array::BytesArray,
     fee_history::FeeHistory,
-    log::{Filter, FilterBuilder, Log},
+    log::{encode_topic, Filter, FilterBuilder, Log},
+    node_health::NodeHealth,
+    parity_info::{ParityGasPriceHistogram, ParityVersion, ParityVersionInfo},
     parity_peers::{
         EthProtocolInfo, ParityPeerInfo, ParityPeerType, PeerNetworkInfo, PeerProtocolsInfo, PipProtocolInfo,
     },
     parity_pending_transaction::{
         FilterCondition, ParityPendingTransactionFilter, ParityPendingTransactionFilterBuilder, ToFilter,
     },
-    proof::Proof,
+    proof::{Proof, StorageProof},
     recovery::{ParseSignatureError, Recovery, RecoveryMessage},
     signed::{SignedData, SignedTransaction, TransactionParameters},
+    state_override::{AccountOverride, StateOverride},
     sync_state::{SyncInfo, SyncState},
     trace_filtering::{
         Action, ActionType, Call, CallResult, CallType, Create, CreateResult, Res, Reward, RewardType, Suicide, Trace,
@@ -44,11 +53,14 @@ pub use self::{
         AccountDiff, BlockTrace, ChangedType, Diff, MemoryDiff, StateDiff, StorageDiff, TraceType, TransactionTrace,
         VMExecutedOperation, VMOperation, VMTrace,
     },
-    transaction::{AccessList, AccessListItem, RawTransaction, Receipt as TransactionReceipt, Transaction},
+    transaction::{
+        AccessList, AccessListItem, AccessListResult, RawTransaction, Receipt as TransactionReceipt, Transaction,
+    },
     transaction_id::TransactionId,
-    transaction_request::{CallRequest, TransactionCondition, TransactionRequest},
+    transaction_request::{CallRequest, TransactionCondition, TransactionRequest, TransactionRequestError},
     txpool::{TxpoolContentInfo, TxpoolInspectInfo, TxpoolStatus},
-    uint::{H128, H160, H2048, H256, H512, H520, H64, U128, U256, U64},
+    typed_data::{TypedData, TypedDataDomain, TypedDataField},
+    uint::{deserialize_u256_lenient, format_ether, H128, H160, H2048, H256, H512, H520, H64, U128, U256, U64},
     work::Work,
 };
 
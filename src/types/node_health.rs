@@ -0,0 +1,22 @@
+use crate::types::{SyncState, U64};
+use std::time::Duration;
+
+/// Status of a node, as probed by `Web3::node_status`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeHealth {
+    /// Network id reported by `net_version`.
+    pub network_id: String,
+    /// Sync state reported by `eth_syncing`.
+    pub sync_state: SyncState,
+    /// Latest block number reported by `eth_blockNumber`.
+    pub block_number: U64,
+    /// Round-trip time of the `net_version` probe.
+    pub latency: Duration,
+}
+
+impl NodeHealth {
+    /// Returns whether the node is fully synced, i.e. serves up-to-date state.
+    pub fn is_synced(&self) -> bool {
+        self.sync_state == SyncState::NotSyncing
+    }
+}
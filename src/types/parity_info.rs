@@ -0,0 +1,35 @@
+//! Types for getting information about parity nodes
+use crate::types::{H160, U256};
+use serde::{Deserialize, Serialize};
+
+/// Version of a parity node
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct ParityVersionInfo {
+    /// hash of the release commit
+    pub hash: H160,
+    /// release track, e.g. `stable`, `beta` or `null` for custom builds
+    pub track: String,
+    /// semantic version of the release
+    pub version: ParityVersion,
+}
+
+/// Semantic version of a parity node
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Copy)]
+pub struct ParityVersion {
+    /// major version
+    pub major: u64,
+    /// minor version
+    pub minor: u64,
+    /// patch version
+    pub patch: u64,
+}
+
+/// Histogram of gas prices of recent transactions
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ParityGasPriceHistogram {
+    /// bounds of the buckets, one more than the number of buckets
+    pub bucket_bounds: Vec<U256>,
+    /// number of transactions in each bucket
+    pub counts: Vec<u64>,
+}
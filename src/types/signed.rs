@@ -141,6 +141,14 @@ pub struct SignedTransaction {
     pub transaction_hash: H256,
 }
 
+impl SignedTransaction {
+    /// Computes the transaction hash (keccak256 of the raw signed transaction), as reported by
+    /// `eth_sendRawTransaction`.
+    pub fn hash(&self) -> H256 {
+        crate::signing::keccak256(&self.raw_transaction.0).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -149,4 +157,23 @@ mod tests {
     fn verify_transaction_default_gas() {
         assert_eq!(TRANSACTION_DEFAULT_GAS, U256::from(100_000));
     }
+
+    #[test]
+    fn should_compute_transaction_hash() {
+        use hex_literal::hex;
+
+        let signed = SignedTransaction {
+            message_hash: hex!("88cfbd7e51c7a40540b233cf68b62ad1df3e92462f1c6018d6d67eae0f3b08f5").into(),
+            v: 0x25,
+            r: hex!("c9cf86333bcb065d140032ecaab5d9281bde80f21b9687b3e94161de42d51895").into(),
+            s: hex!("727a108a0b8d101465414033c3f705a9c7b826e596766046ee1183dbc8aeaa68").into(),
+            raw_transaction: hex!("f869808504e3b29200831e848094f0109fc8df283027b6285cc889f5aa624eac1f55843b9aca008025a0c9cf86333bcb065d140032ecaab5d9281bde80f21b9687b3e94161de42d51895a0727a108a0b8d101465414033c3f705a9c7b826e596766046ee1183dbc8aeaa68").into(),
+            transaction_hash: H256::zero(),
+        };
+
+        assert_eq!(
+            signed.hash(),
+            hex!("de8db924885b0803d2edc335f745b2b8750c8848744905684c20b987443a9593").into()
+        );
+    }
 }
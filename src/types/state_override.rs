@@ -0,0 +1,66 @@
+use crate::types::{Address, Bytes, H256, U256, U64};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// State overrides of `eth_call`, keyed by the address of the overridden account.
+pub type StateOverride = BTreeMap<Address, AccountOverride>;
+
+/// Overrides of a single account's state applied before executing a call.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountOverride {
+    /// Fake balance to set for the account.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    /// Fake nonce to set for the account.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<U64>,
+    /// Fake code to inject into the account.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// Storage replacing all of the account's storage.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<BTreeMap<H256, H256>>,
+    /// Storage slots to override, leaving the rest of the account's storage intact.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<BTreeMap<H256, H256>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_skip_missing_fields() {
+        let overrides: StateOverride = vec![
+            (
+                Address::from_low_u64_be(0x123),
+                AccountOverride {
+                    balance: Some(0x10.into()),
+                    ..Default::default()
+                },
+            ),
+            (
+                Address::from_low_u64_be(0x456),
+                AccountOverride {
+                    code: Some(Bytes(vec![0x60, 0x00])),
+                    state_diff: Some(
+                        vec![(H256::from_low_u64_be(1), H256::from_low_u64_be(2))]
+                            .into_iter()
+                            .collect(),
+                    ),
+                    ..Default::default()
+                },
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        let serialized = serde_json::to_string(&overrides).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"0x0000000000000000000000000000000000000123":{"balance":"0x10"},"0x0000000000000000000000000000000000000456":{"code":"0x6000","stateDiff":{"0x0000000000000000000000000000000000000000000000000000000000000001":"0x0000000000000000000000000000000000000000000000000000000000000002"}}}"#
+        );
+        assert_eq!(serde_json::from_str::<StateOverride>(&serialized).unwrap(), overrides);
+    }
+}
@@ -100,7 +100,8 @@ pub struct Receipt {
     /// State root.
     pub root: Option<H256>,
     /// Logs bloom
-    #[serde(rename = "logsBloom")]
+    /// Note: empty bloom if the client did not return this value
+    #[serde(rename = "logsBloom", default)]
     pub logs_bloom: H2048,
     /// Transaction type, Some(1) for AccessList transaction, None for Legacy
     #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
@@ -110,6 +111,34 @@ pub struct Receipt {
     pub effective_gas_price: Option<U256>,
 }
 
+impl Receipt {
+    /// Total fee paid for the transaction, i.e. `gas_used * effective_gas_price`.
+    ///
+    /// Returns `None` if the client did not return either of the values or the fee overflows.
+    pub fn fee_paid(&self) -> Option<U256> {
+        self.gas_used?.checked_mul(self.effective_gas_price?)
+    }
+
+    /// Ratio of the gas used by the transaction to its `gas_limit`, between `0.0` and `1.0`.
+    ///
+    /// Returns `None` if the client did not return the gas used or `gas_limit` is zero.
+    pub fn gas_used_ratio(&self, gas_limit: U256) -> Option<f64> {
+        let gas_used = self.gas_used?;
+        if gas_limit.is_zero() {
+            return None;
+        }
+        Some(u256_to_f64(gas_used) / u256_to_f64(gas_limit))
+    }
+}
+
+fn u256_to_f64(value: U256) -> f64 {
+    value
+        .0
+        .iter()
+        .rev()
+        .fold(0.0, |acc, &limb| acc * 2f64.powi(64) + limb as f64)
+}
+
 /// Raw bytes of a signed, but not yet sent transaction
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RawTransaction {
@@ -132,9 +161,23 @@ pub struct AccessListItem {
     pub storage_keys: Vec<H256>,
 }
 
+/// Access list generated for a transaction by `eth_createAccessList`
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListResult {
+    /// Addresses and storage keys accessed by the transaction
+    pub access_list: AccessList,
+    /// Gas used by the transaction with the access list attached
+    pub gas_used: U256,
+    /// Error of the execution, if it failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{RawTransaction, Receipt};
+    use super::{RawTransaction, Receipt, Transaction};
+    use crate::types::{H2048, U256};
 
     #[test]
     fn test_deserialize_receipt() {
@@ -229,6 +272,161 @@ mod tests {
         let _receipt: Receipt = serde_json::from_str(receipt_str).unwrap();
     }
 
+    #[test]
+    fn should_deserialize_receipt_with_logs() {
+        let receipt_str = r#"{
+        "blockHash": "0x83eaba432089a0bfe99e9fc9022d1cfcb78f95f407821be81737c84ae0b439c5",
+        "blockNumber": "0x38",
+        "from": "0x407d73d8a49eeb85d32cf465507dd71d507100c1",
+        "to": "0x853f43d8a49eeb85d32cf465507dd71d507100c1",
+        "cumulativeGasUsed": "0x927c0",
+        "gasUsed": "0x927c0",
+        "logs": [
+            {
+                "address": "0x853f43d8a49eeb85d32cf465507dd71d507100c1",
+                "topics": [
+                    "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+                    "0x000000000000000000000000407d73d8a49eeb85d32cf465507dd71d507100c1"
+                ],
+                "data": "0x0000000000000000000000000000000000000000000000000000000000000064",
+                "blockHash": "0x83eaba432089a0bfe99e9fc9022d1cfcb78f95f407821be81737c84ae0b439c5",
+                "blockNumber": "0x38",
+                "transactionHash": "0x422fb0d5953c0c48cbb42fb58e1c30f5e150441c68374d70ca7d4f191fd56f26",
+                "transactionIndex": "0x0",
+                "logIndex": "0x0",
+                "removed": false
+            },
+            {
+                "address": "0x853f43d8a49eeb85d32cf465507dd71d507100c1",
+                "topics": [],
+                "data": "0x",
+                "blockHash": "0x83eaba432089a0bfe99e9fc9022d1cfcb78f95f407821be81737c84ae0b439c5",
+                "blockNumber": "0x38",
+                "transactionHash": "0x422fb0d5953c0c48cbb42fb58e1c30f5e150441c68374d70ca7d4f191fd56f26",
+                "transactionIndex": "0x0",
+                "logIndex": "0x1",
+                "removed": false
+            }
+        ],
+        "logsBloom": "0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+        "transactionHash": "0x422fb0d5953c0c48cbb42fb58e1c30f5e150441c68374d70ca7d4f191fd56f26",
+        "transactionIndex": "0x0",
+        "status": "0x1",
+        "effectiveGasPrice": "0x100"
+    }"#;
+
+        let receipt: Receipt = serde_json::from_str(receipt_str).unwrap();
+        assert_eq!(receipt.logs.len(), 2);
+        let transfer = &receipt.logs[0];
+        assert_eq!(
+            transfer.address,
+            "853f43d8a49eeb85d32cf465507dd71d507100c1".parse().unwrap()
+        );
+        assert_eq!(transfer.topics.len(), 2);
+        assert_eq!(transfer.data.0.len(), 32);
+        assert_eq!(transfer.log_index, Some(0.into()));
+        assert_eq!(transfer.removed, Some(false));
+        assert_eq!(receipt.logs[1].log_index, Some(1.into()));
+        assert!(receipt.logs[1].topics.is_empty());
+    }
+
+    #[test]
+    fn should_deserialize_eip1559_receipt_without_optional_fields() {
+        let receipt_str = r#"{
+        "blockHash": "0x83eaba432089a0bfe99e9fc9022d1cfcb78f95f407821be81737c84ae0b439c5",
+        "blockNumber": "0xd2f1a7",
+        "from": "0x407d73d8a49eeb85d32cf465507dd71d507100c1",
+        "to": "0x853f43d8a49eeb85d32cf465507dd71d507100c1",
+        "cumulativeGasUsed": "0x1c9c38",
+        "gasUsed": "0x5208",
+        "logs": [],
+        "transactionHash": "0x422fb0d5953c0c48cbb42fb58e1c30f5e150441c68374d70ca7d4f191fd56f26",
+        "transactionIndex": "0x3",
+        "type": "0x2",
+        "status": "0x1",
+        "effectiveGasPrice": "0x2540be400"
+    }"#;
+
+        let receipt: Receipt = serde_json::from_str(receipt_str).unwrap();
+        assert_eq!(receipt.contract_address, None);
+        assert_eq!(receipt.root, None);
+        assert_eq!(receipt.logs_bloom, H2048::zero());
+        assert_eq!(receipt.status, Some(1.into()));
+        assert_eq!(receipt.transaction_type, Some(2.into()));
+        assert_eq!(receipt.effective_gas_price, Some(10_000_000_000u64.into()));
+    }
+
+    #[test]
+    fn should_compute_fee_paid() {
+        let receipt = Receipt {
+            gas_used: Some(21_000.into()),
+            effective_gas_price: Some(10_000_000_000u64.into()),
+            ..Default::default()
+        };
+        assert_eq!(receipt.fee_paid(), Some(210_000_000_000_000u64.into()));
+
+        let receipt = Receipt {
+            gas_used: Some(21_000.into()),
+            effective_gas_price: None,
+            ..Default::default()
+        };
+        assert_eq!(receipt.fee_paid(), None);
+
+        let receipt = Receipt {
+            gas_used: Some(21_000.into()),
+            effective_gas_price: Some(U256::MAX),
+            ..Default::default()
+        };
+        assert_eq!(receipt.fee_paid(), None);
+    }
+
+    #[test]
+    fn should_compute_gas_used_ratio() {
+        let receipt = Receipt {
+            gas_used: Some(21_000.into()),
+            ..Default::default()
+        };
+        assert_eq!(receipt.gas_used_ratio(84_000.into()), Some(0.25));
+        assert_eq!(receipt.gas_used_ratio(0.into()), None);
+
+        let receipt = Receipt {
+            gas_used: None,
+            ..Default::default()
+        };
+        assert_eq!(receipt.gas_used_ratio(84_000.into()), None);
+    }
+
+    #[test]
+    fn should_deserialize_pending_contract_creation() {
+        let tx_str = r#"{
+        "blockHash": null,
+        "blockNumber": null,
+        "from": "0x407d73d8a49eeb85d32cf465507dd71d507100c1",
+        "gas": "0x1e8480",
+        "gasPrice": "0x4a817c800",
+        "hash": "0x422fb0d5953c0c48cbb42fb58e1c30f5e150441c68374d70ca7d4f191fd56f26",
+        "input": "0x6080604052348015600f57600080fd5b50603f80601d6000396000f3fe6080604052600080fdfea164736f6c6343000807000a",
+        "nonce": "0x15",
+        "to": null,
+        "transactionIndex": null,
+        "value": "0x0",
+        "v": "0x25",
+        "r": "0x6484d00575e961a7db35ebe5badaaca5cb7ee65d1f2f22f22da87c238b99d30d",
+        "s": "0x7a85d65797e4b555c1d3f64beebb2cb6f16a6fbd40c43cc48451eaf85305f66e"
+        }"#;
+
+        let tx: Transaction = serde_json::from_str(tx_str).unwrap();
+        assert_eq!(tx.to, None);
+        assert_eq!(tx.block_hash, None);
+        assert_eq!(tx.block_number, None);
+        assert_eq!(tx.transaction_index, None);
+        assert_eq!(tx.input.0.len(), 51);
+        assert_eq!(
+            serde_json::from_str::<Transaction>(&serde_json::to_string(&tx).unwrap()).unwrap(),
+            tx
+        );
+    }
+
     #[test]
     fn test_deserialize_signed_tx_parity() {
         // taken from RPC docs.
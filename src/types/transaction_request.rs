@@ -48,6 +48,27 @@ impl CallRequest {
     }
 }
 
+impl From<TransactionRequest> for CallRequest {
+    /// Converts a transaction request into a call request.
+    ///
+    /// Fields that only make sense for submitted transactions (`nonce` and `condition`)
+    /// are dropped.
+    fn from(tx: TransactionRequest) -> Self {
+        CallRequest {
+            from: Some(tx.from),
+            to: tx.to,
+            gas: tx.gas,
+            gas_price: tx.gas_price,
+            value: tx.value,
+            data: tx.data,
+            transaction_type: tx.transaction_type,
+            access_list: tx.access_list,
+            max_fee_per_gas: tx.max_fee_per_gas,
+            max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+        }
+    }
+}
+
 /// Call Request Builder
 #[derive(Clone, Debug, Default)]
 pub struct CallRequestBuilder {
@@ -117,6 +138,8 @@ impl CallRequestBuilder {
 }
 
 /// Send Transaction Parameters
+///
+/// Fields are serialized in declaration order and unset optional fields are omitted.
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
 pub struct TransactionRequest {
     /// Sender address
@@ -196,6 +219,25 @@ impl TransactionRequestBuilder {
         self
     }
 
+    /// Set gas price of a legacy transaction. The gas price and the EIP-1559 fee pair
+    /// (`max_fee_per_gas` and `max_priority_fee_per_gas`) are mutually exclusive, `build` rejects both being set.
+    pub fn gas_price(mut self, gas_price: U256) -> Self {
+        self.transaction_request.gas_price = Some(gas_price);
+        self
+    }
+
+    /// Set EIP-1559 max fee per gas. Mutually exclusive with the gas price.
+    pub fn max_fee_per_gas(mut self, max_fee_per_gas: U256) -> Self {
+        self.transaction_request.max_fee_per_gas = Some(max_fee_per_gas);
+        self
+    }
+
+    /// Set EIP-1559 max priority fee per gas. Mutually exclusive with the gas price.
+    pub fn max_priority_fee_per_gas(mut self, max_priority_fee_per_gas: U256) -> Self {
+        self.transaction_request.max_priority_fee_per_gas = Some(max_priority_fee_per_gas);
+        self
+    }
+
     /// Set transfered value (None for no transfer)
     pub fn value(mut self, value: U256) -> Self {
         self.transaction_request.value = Some(value);
@@ -233,11 +275,24 @@ impl TransactionRequestBuilder {
     }
 
     /// build the Transaction Request
-    pub fn build(&self) -> TransactionRequest {
-        self.transaction_request.clone()
+    pub fn build(&self) -> Result<TransactionRequest, TransactionRequestError> {
+        let tx = &self.transaction_request;
+        if tx.gas_price.is_some() && (tx.max_fee_per_gas.is_some() || tx.max_priority_fee_per_gas.is_some()) {
+            return Err(TransactionRequestError::ConflictingFees);
+        }
+        Ok(tx.clone())
     }
 }
 
+/// Error building an invalid transaction request.
+#[derive(Debug, derive_more::Display, PartialEq, Clone)]
+pub enum TransactionRequestError {
+    /// Both the legacy gas price and the EIP-1559 fees are set.
+    #[display(fmt = "Gas price and EIP-1559 fees are mutually exclusive.")]
+    ConflictingFees,
+}
+impl std::error::Error for TransactionRequestError {}
+
 /// Represents condition on minimum block number or block timestamp.
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -254,6 +309,7 @@ pub enum TransactionCondition {
 mod tests {
     use super::{
         Address, CallRequest, CallRequestBuilder, TransactionCondition, TransactionRequest, TransactionRequestBuilder,
+        TransactionRequestError,
     };
     use hex_literal::hex;
 
@@ -306,6 +362,45 @@ mod tests {
         assert_eq!(deserialized.data, Some(hex!("010203").into()));
     }
 
+    #[test]
+    fn should_not_serialize_transaction_only_fields_in_call_request() {
+        // given
+        let tx_request = TransactionRequest {
+            from: Address::from_low_u64_be(5),
+            to: Some(Address::from_low_u64_be(6)),
+            nonce: Some(1.into()),
+            condition: Some(TransactionCondition::Block(5)),
+            ..Default::default()
+        };
+
+        // when
+        let serialized = serde_json::to_string(&CallRequest::from(tx_request)).unwrap();
+
+        // then
+        assert_eq!(
+            serialized,
+            r#"{"from":"0x0000000000000000000000000000000000000005","to":"0x0000000000000000000000000000000000000006"}"#
+        );
+    }
+
+    #[test]
+    fn should_serialize_and_deserialize_transaction_conditions() {
+        let block = serde_json::to_string(&TransactionCondition::Block(5)).unwrap();
+        let time = serde_json::to_string(&TransactionCondition::Timestamp(1_600_000_000)).unwrap();
+
+        assert_eq!(block, r#"{"block":5}"#);
+        assert_eq!(time, r#"{"time":1600000000}"#);
+        assert_eq!(
+            serde_json::from_str::<TransactionCondition>(&block).unwrap(),
+            TransactionCondition::Block(5)
+        );
+        assert_eq!(
+            serde_json::from_str::<TransactionCondition>(&time).unwrap(),
+            TransactionCondition::Timestamp(1_600_000_000)
+        );
+        assert!(serde_json::from_str::<TransactionCondition>(r#"{"height":5}"#).is_err());
+    }
+
     #[test]
     fn should_serialize_transaction_request() {
         // given
@@ -342,6 +437,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_serialize_all_transaction_request_fields_in_declaration_order() {
+        // given
+        let tx_request = TransactionRequest {
+            from: Address::from_low_u64_be(5),
+            to: Some(Address::from_low_u64_be(6)),
+            gas: Some(21_000.into()),
+            gas_price: Some(1.into()),
+            value: Some(2.into()),
+            data: Some(hex!("01").into()),
+            nonce: Some(3.into()),
+            condition: Some(TransactionCondition::Block(5)),
+            transaction_type: Some(2.into()),
+            access_list: Some(vec![]),
+            max_fee_per_gas: Some(4.into()),
+            max_priority_fee_per_gas: Some(5.into()),
+        };
+
+        // when
+        let serialized = serde_json::to_string(&tx_request).unwrap();
+
+        // then
+        assert_eq!(
+            serialized,
+            r#"{"from":"0x0000000000000000000000000000000000000005","to":"0x0000000000000000000000000000000000000006","gas":"0x5208","gasPrice":"0x1","value":"0x2","data":"0x01","nonce":"0x3","condition":{"block":5},"type":"0x2","accessList":[],"maxFeePerGas":"0x4","maxPriorityFeePerGas":"0x5"}"#
+        );
+    }
+
     #[test]
     fn should_deserialize_transaction_request() {
         let serialized = r#"{
@@ -408,7 +531,7 @@ mod tests {
         //when
         let tx_request_builder = TransactionRequestBuilder::new();
         //then
-        assert_eq!(tx_request_builder.build(), tx_request);
+        assert_eq!(tx_request_builder.build(), Ok(tx_request));
     }
 
     #[test]
@@ -436,6 +559,30 @@ mod tests {
             .data(hex!("010203").into())
             .condition(TransactionCondition::Block(5));
         //then
-        assert_eq!(tx_request_builder.build(), tx_request);
+        assert_eq!(tx_request_builder.build(), Ok(tx_request));
+    }
+
+    #[test]
+    fn should_reject_both_gas_price_and_eip1559_fees() {
+        // given
+        let legacy = TransactionRequestBuilder::new().gas_price(1.into());
+        let eip1559 = TransactionRequestBuilder::new()
+            .max_fee_per_gas(3.into())
+            .max_priority_fee_per_gas(2.into());
+
+        // when
+        let both = legacy.clone().max_fee_per_gas(3.into()).build();
+        let priority = TransactionRequestBuilder::new()
+            .max_priority_fee_per_gas(2.into())
+            .gas_price(1.into())
+            .build();
+
+        // then
+        assert_eq!(both, Err(TransactionRequestError::ConflictingFees));
+        assert_eq!(priority, Err(TransactionRequestError::ConflictingFees));
+        assert_eq!(legacy.build().unwrap().gas_price, Some(1.into()));
+        let eip1559 = eip1559.build().unwrap();
+        assert_eq!(eip1559.max_fee_per_gas, Some(3.into()));
+        assert_eq!(eip1559.max_priority_fee_per_gas, Some(2.into()));
     }
 }
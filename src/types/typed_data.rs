@@ -0,0 +1,111 @@
+use crate::types::{deserialize_u256_lenient, Address, H256, U256};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+
+/// EIP-712 typed data, in the JSON layout accepted by `eth_signTypedData_v4`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedData {
+    /// Struct types referenced by the data, including `EIP712Domain`.
+    pub types: BTreeMap<String, Vec<TypedDataField>>,
+    /// Name of the type of `message`.
+    pub primary_type: String,
+    /// Domain separator values.
+    pub domain: TypedDataDomain,
+    /// The structured message to sign.
+    pub message: BTreeMap<String, serde_json::Value>,
+}
+
+/// A single member of a struct type of `TypedData`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct TypedDataField {
+    /// Name of the member.
+    pub name: String,
+    /// Solidity type of the member, e.g. `address`, `uint256` or the name of another struct type.
+    #[serde(rename = "type")]
+    pub r#type: String,
+}
+
+/// The EIP-712 domain of `TypedData`. Only the fields declared in the `EIP712Domain` type should be set.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedDataDomain {
+    /// Name of the signing domain.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Current major version of the signing domain.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Chain id of the network.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_chain_id"
+    )]
+    pub chain_id: Option<U256>,
+    /// Address of the contract verifying the signature.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verifying_contract: Option<Address>,
+    /// Disambiguating salt of the protocol.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub salt: Option<H256>,
+}
+
+/// Wallets commonly send the chain id as a JSON number.
+fn deserialize_chain_id<'de, D>(deserializer: D) -> Result<Option<U256>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_u256_lenient(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_round_trip_v4_layout() {
+        let json = json!({
+            "types": {
+                "EIP712Domain": [
+                    { "name": "name", "type": "string" },
+                    { "name": "version", "type": "string" },
+                    { "name": "chainId", "type": "uint256" },
+                    { "name": "verifyingContract", "type": "address" }
+                ],
+                "Person": [
+                    { "name": "name", "type": "string" },
+                    { "name": "wallet", "type": "address" }
+                ],
+                "Mail": [
+                    { "name": "from", "type": "Person" },
+                    { "name": "to", "type": "Person" },
+                    { "name": "contents", "type": "string" }
+                ]
+            },
+            "primaryType": "Mail",
+            "domain": {
+                "name": "Ether Mail",
+                "version": "1",
+                "chainId": 1,
+                "verifyingContract": "0xcccccccccccccccccccccccccccccccccccccccc"
+            },
+            "message": {
+                "from": { "name": "Cow", "wallet": "0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826" },
+                "to": { "name": "Bob", "wallet": "0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb" },
+                "contents": "Hello, Bob!"
+            }
+        });
+
+        let typed_data: TypedData = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(typed_data.primary_type, "Mail");
+        assert_eq!(typed_data.types["Mail"][0].r#type, "Person");
+        assert_eq!(typed_data.domain.chain_id, Some(1.into()));
+        assert_eq!(typed_data.domain.salt, None);
+
+        let mut expected = json;
+        expected["domain"]["chainId"] = json!("0x1");
+        assert_eq!(serde_json::to_value(&typed_data).unwrap(), expected);
+    }
+}
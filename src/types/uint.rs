@@ -1,4 +1,71 @@
 pub use ethereum_types::{BigEndianHash, Bloom as H2048, H128, H160, H256, H512, H520, H64, U128, U256, U64};
+use serde::de::{Deserializer, Error, Unexpected, Visitor};
+use std::fmt;
+
+/// Formats an amount of wei as a decimal amount of ether, e.g. `1.5` for `1_500_000_000_000_000_000` wei.
+///
+/// The conversion is exact (no floating point is involved), trailing zeros of the fractional part are omitted.
+pub fn format_ether(wei: U256) -> String {
+    let (ether, remainder) = wei.div_mod(U256::exp10(18));
+    if remainder.is_zero() {
+        return ether.to_string();
+    }
+    let fraction = format!("{:0>18}", remainder.to_string());
+    format!("{}.{}", ether, fraction.trim_end_matches('0'))
+}
+
+/// Deserializes `U256` from a `0x`-prefixed hex string, a decimal string or a JSON number.
+///
+/// The default deserialization only supports hex strings, use this with
+/// `#[serde(deserialize_with = "deserialize_u256_lenient")]` for endpoints which encode quantities differently.
+pub fn deserialize_u256_lenient<'de, D>(deserializer: D) -> Result<U256, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(LenientU256Visitor)
+}
+
+struct LenientU256Visitor;
+
+impl<'de> Visitor<'de> for LenientU256Visitor {
+    type Value = U256;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "a non-negative integer, either a number or a hex or decimal string"
+        )
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(value.into())
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        if value < 0 {
+            return Err(Error::invalid_value(Unexpected::Signed(value), &self));
+        }
+        Ok((value as u64).into())
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        let parsed = match value.strip_prefix("0x") {
+            Some(hex) if !hex.is_empty() => U256::from_str_radix(hex, 16).ok(),
+            None if !value.is_empty() => U256::from_dec_str(value).ok(),
+            _ => None,
+        };
+        parsed.ok_or_else(|| Error::invalid_value(Unexpected::Str(value), &self))
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -156,6 +223,40 @@ mod tests {
         assert_eq!(111u64, U256::from(111u64).low_u64());
     }
 
+    #[test]
+    fn should_deserialize_u256_leniently() {
+        #[derive(Debug, serde::Deserialize)]
+        struct Lenient(#[serde(deserialize_with = "deserialize_u256_lenient")] U256);
+
+        let from_hex: Lenient = serde_json::from_str(r#""0x1a""#).unwrap();
+        let from_decimal: Lenient = serde_json::from_str(r#""26""#).unwrap();
+        let from_number: Lenient = serde_json::from_str("26").unwrap();
+        let large: Lenient = serde_json::from_str(r#""1000000000000000000000000""#).unwrap();
+
+        assert_eq!(from_hex.0, 26.into());
+        assert_eq!(from_decimal.0, 26.into());
+        assert_eq!(from_number.0, 26.into());
+        assert_eq!(large.0, U256::exp10(24));
+        assert!(serde_json::from_str::<Lenient>(r#""""#).is_err());
+        assert!(serde_json::from_str::<Lenient>(r#""0x""#).is_err());
+        assert!(serde_json::from_str::<Lenient>(r#""0x1g""#).is_err());
+        assert!(serde_json::from_str::<Lenient>(r#""12a""#).is_err());
+        assert!(serde_json::from_str::<Lenient>("-1").is_err());
+    }
+
+    #[test]
+    fn should_format_ether() {
+        assert_eq!(format_ether(U256::zero()), "0");
+        assert_eq!(format_ether(U256::exp10(18)), "1");
+        assert_eq!(format_ether(U256::exp10(17) * 15), "1.5");
+        assert_eq!(format_ether(1.into()), "0.000000000000000001");
+        assert_eq!(format_ether(U256::exp10(24) + 1), "1000000.000000000000000001");
+        assert_eq!(
+            format_ether(U256::max_value()),
+            "115792089237316195423570985008687907853269984665640564039457.584007913129639935"
+        );
+    }
+
     // Getting random numbers uses a different code path in JS, so we sanity
     // check it here.
     #[wasm_bindgen_test]